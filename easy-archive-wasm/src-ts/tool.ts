@@ -128,7 +128,7 @@ export function extractToByWasm(
   if (buf.length > MAX_SIZE) {
     return;
   }
-  const fmt = guess(compressedFilePath);
+  const fmt = guess(compressedFilePath, buf);
   if (!outputDir) {
     outputDir = join(tmpdir(), randomId());
     if (!existsSync(outputDir)) {
@@ -88,6 +88,46 @@ export enum Fmt {
    * 7z archive format
    */
   SevenZip = 6,
+  /**
+   * Gzip-compressed single file (.gz)
+   */
+  Gz = 7,
+  /**
+   * XZ-compressed single file (.xz)
+   */
+  Xz = 8,
+  /**
+   * Bzip2-compressed single file (.bz2)
+   */
+  Bz = 9,
+  /**
+   * Zstd-compressed single file (.zst, .zstd)
+   */
+  Zstd = 10,
+  /**
+   * LZ4-compressed tar archive (.tar.lz4, .tlz4)
+   */
+  TarLz4 = 11,
+  /**
+   * LZMA-compressed tar archive in the legacy LZMA-alone container (.tar.lzma, .tlz)
+   */
+  TarLzma = 12,
+  /**
+   * Lzip-compressed tar archive (.tar.lz)
+   */
+  TarLz = 13,
+  /**
+   * Tar archive compressed with Unix `compress` (.tar.Z, .taZ), decode only
+   */
+  TarZ = 14,
+  /**
+   * Unix ar archive, including Debian packages (.ar, .a, .deb)
+   */
+  Ar = 15,
+  /**
+   * cpio archive in the newc, crc or odc variant (.cpio)
+   */
+  Cpio = 16,
 }
 
 /**
@@ -102,7 +142,7 @@ export enum Fmt {
  */
 export function compress(entries: string[], datas: Uint8Array[]): Uint8Array;
 
-export function decode(fmt: Fmt, buffer: Uint8Array): File[] | undefined;
+export function decode(fmt: Fmt, buffer: Uint8Array, password?: string | null): File[] | undefined;
 
 /**
  * Decompresses a 7z archive in WebAssembly environment.
@@ -117,9 +157,9 @@ export function decode(fmt: Fmt, buffer: Uint8Array): File[] | undefined;
  */
 export function decompress(src: Uint8Array, pwd: string, f: Function): void;
 
-export function encode(fmt: Fmt, files: File[]): Uint8Array | undefined;
+export function encode(fmt: Fmt, files: File[], password?: string | null): Uint8Array | undefined;
 
 export function extensions(fmt: Fmt): string[];
 
-export function guess(name: string): Fmt | undefined;
+export function guess(name: string, buffer?: Uint8Array | null): Fmt | undefined;
 
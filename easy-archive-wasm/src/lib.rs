@@ -1,9 +1,9 @@
-use easy_archive::{File, Fmt};
+use easy_archive::{DecodeOptions, EncodeOptions, File, Fmt};
 use wasm_bindgen::prelude::wasm_bindgen;
 
 #[wasm_bindgen]
-pub fn guess(name: String) -> Option<Fmt> {
-    Fmt::guess(&name)
+pub fn guess(name: String, buffer: Option<Vec<u8>>) -> Option<Fmt> {
+    Fmt::guess(&name).or_else(|| buffer.and_then(|buffer| Fmt::detect(&buffer)))
 }
 
 #[wasm_bindgen]
@@ -12,11 +12,19 @@ pub fn extensions(fmt: Fmt) -> Vec<String> {
 }
 
 #[wasm_bindgen]
-pub fn decode(fmt: Fmt, buffer: Vec<u8>) -> Option<Vec<File>> {
-    fmt.decode(buffer).ok()
+pub fn decode(fmt: Fmt, buffer: Vec<u8>, password: Option<String>) -> Option<Vec<File>> {
+    let options = DecodeOptions {
+        password,
+        ..Default::default()
+    };
+    fmt.decode_with(buffer, &options).ok()
 }
 
 #[wasm_bindgen]
-pub fn encode(fmt: Fmt, files: Vec<File>) -> Option<Vec<u8>> {
-    fmt.encode(files).ok()
+pub fn encode(fmt: Fmt, files: Vec<File>, password: Option<String>) -> Option<Vec<u8>> {
+    let options = EncodeOptions {
+        password,
+        ..Default::default()
+    };
+    fmt.encode_with(files, &options).ok()
 }
@@ -0,0 +1,52 @@
+/// Debian package helpers
+use crate::{
+    Fmt,
+    archive::tar::Entries,
+    error::{ArchiveError, Result},
+};
+use std::io::Read;
+
+use super::{Ar, decode::ArReader};
+
+impl Ar {
+    /// Stream the entries of the `data.tar.*` member of a Debian package
+    ///
+    /// A `.deb` file is an ar archive holding `debian-binary`,
+    /// `control.tar.*` and `data.tar.*`. The data member is decompressed
+    /// with the matching TAR variant, which must be enabled, without
+    /// buffering the package.
+    ///
+    /// # Arguments
+    /// * `reader` - A reader providing the `.deb` file
+    ///
+    /// # Returns
+    /// * `Ok(Entries)` - A streaming iterator over the installed files
+    /// * `Err(ArchiveError)` - If the package is malformed or its data compression is not enabled
+    ///
+    /// # Example
+    /// ```no_run
+    /// use easy_archive::archive::Ar;
+    /// let reader = std::fs::File::open("hello_2.10-3_amd64.deb")?;
+    /// let mut entries = Ar::deb_data(reader)?;
+    /// while let Some(entry) = entries.next_entry()? {
+    ///     println!("{} {}", entry.info().path, entry.info().size);
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn deb_data<'a, R: Read + 'a>(reader: R) -> Result<Entries<'a>> {
+        let mut members = ArReader::new(reader);
+        while let Some(info) = members.next_member()? {
+            if !info.path.starts_with("data.tar") {
+                continue;
+            }
+            let fmt = Fmt::guess(&info.path)
+                .filter(|fmt| !fmt.is_single_file())
+                .ok_or_else(|| ArchiveError::UnsupportedFormat(info.path.clone()))?;
+            return fmt.entries(members.into_member());
+        }
+
+        Err(ArchiveError::InvalidArchive(
+            "Debian package has no data.tar member".to_string(),
+        ))
+    }
+}
@@ -0,0 +1,286 @@
+/// AR decoding implementation
+use crate::{
+    File,
+    error::{ArchiveError, Result},
+    traits::Decode,
+    types::{DecodeOptions, EntryInfo},
+    utils::{LimitTracker, clean},
+};
+use std::io::{self, Read};
+
+use super::{Ar, BSD_LONG_NAME, HEADER_END, HEADER_SIZE, MAGIC};
+
+/// Maximum size of a GNU long name table, which is buffered
+const MAX_NAME_TABLE_SIZE: u64 = 16 << 20;
+
+/// Convert an I/O error into an ar decode error
+fn decode_error(e: io::Error) -> ArchiveError {
+    ArchiveError::DecodeFailed {
+        format: "ar".to_string(),
+        reason: e.to_string(),
+    }
+}
+
+fn invalid(reason: &str) -> ArchiveError {
+    ArchiveError::InvalidArchive(format!("Invalid ar archive: {}", reason))
+}
+
+/// Parse a space-padded numeric header field
+fn parse_field(field: &[u8], radix: u32) -> Option<u64> {
+    let text = std::str::from_utf8(field).ok()?.trim();
+    if text.is_empty() {
+        return None;
+    }
+    u64::from_str_radix(text, radix).ok()
+}
+
+/// Streaming reader over the members of an ar archive
+///
+/// Symbol tables and the GNU long name table are consumed internally and
+/// never reported as members. The reader implements [`Read`] for the
+/// content of the current member.
+pub(crate) struct ArReader<R: Read> {
+    reader: R,
+    started: bool,
+    /// Unread content of the current member
+    remaining: u64,
+    /// Whether the current member is followed by a padding byte
+    padding: bool,
+    /// GNU long name table, referenced by `/<offset>` names
+    names: Option<Vec<u8>>,
+}
+
+impl<R: Read> ArReader<R> {
+    pub(crate) fn new(reader: R) -> Self {
+        ArReader {
+            reader,
+            started: false,
+            remaining: 0,
+            padding: false,
+            names: None,
+        }
+    }
+
+    /// Fill `buf` completely, returning the number of bytes read before the end of input
+    fn fill(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let mut filled = 0;
+        while filled < buf.len() {
+            match self.reader.read(&mut buf[filled..]) {
+                Ok(0) => break,
+                Ok(n) => filled += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(decode_error(e)),
+            }
+        }
+        Ok(filled)
+    }
+
+    /// Read exactly `size` bytes of the current member
+    fn read_bytes(&mut self, size: u64) -> Result<Vec<u8>> {
+        let mut data = vec![0; size as usize];
+        if self.fill(&mut data)? < data.len() {
+            return Err(invalid("truncated member"));
+        }
+        self.remaining -= size;
+        Ok(data)
+    }
+
+    /// Discard the unread content of the current member and its padding
+    fn skip(&mut self) -> Result<()> {
+        let remaining = self.remaining;
+        let skipped = io::copy(&mut (&mut self.reader).take(remaining), &mut io::sink())
+            .map_err(decode_error)?;
+        if skipped < remaining {
+            return Err(invalid("truncated member"));
+        }
+        if self.padding {
+            // The padding of the last member may be missing
+            self.fill(&mut [0; 1])?;
+        }
+        self.remaining = 0;
+        self.padding = false;
+        Ok(())
+    }
+
+    /// Resolve a `/<offset>` reference into the GNU long name table
+    fn long_name(&self, offset: &str) -> Result<String> {
+        let names = self
+            .names
+            .as_deref()
+            .ok_or_else(|| invalid("long name without a name table"))?;
+        let offset: usize = offset
+            .parse()
+            .map_err(|_| invalid("bad long name offset"))?;
+        let rest = names
+            .get(offset..)
+            .ok_or_else(|| invalid("long name offset out of range"))?;
+        let end = rest.iter().position(|b| *b == b'\n').unwrap_or(rest.len());
+        let name = &rest[..end];
+        let name = name.strip_suffix(b"/").unwrap_or(name);
+        Ok(String::from_utf8_lossy(name).to_string())
+    }
+
+    /// Advance to the next member of the archive
+    ///
+    /// # Returns
+    /// * `Ok(Some(EntryInfo))` - The next member, readable for its content
+    /// * `Ok(None)` - If the end of the archive is reached
+    /// * `Err(ArchiveError)` - If the archive is malformed or reading fails
+    pub(crate) fn next_member(&mut self) -> Result<Option<EntryInfo>> {
+        if !self.started {
+            let mut magic = [0; MAGIC.len()];
+            if self.fill(&mut magic)? < magic.len() || &magic != MAGIC {
+                return Err(invalid("missing !<arch> header"));
+            }
+            self.started = true;
+        }
+
+        loop {
+            self.skip()?;
+
+            let mut header = [0; HEADER_SIZE];
+            match self.fill(&mut header)? {
+                0 => return Ok(None),
+                HEADER_SIZE => {}
+                _ => return Err(invalid("truncated member header")),
+            }
+            if &header[58..] != HEADER_END {
+                return Err(invalid("bad member header"));
+            }
+
+            let size =
+                parse_field(&header[48..58], 10).ok_or_else(|| invalid("bad member size"))?;
+            self.remaining = size;
+            self.padding = size % 2 == 1;
+
+            let field = String::from_utf8_lossy(&header[..16]);
+            let field = field.trim_end_matches(' ');
+            let name = match field {
+                // GNU symbol tables
+                "/" | "/SYM64/" => continue,
+                "//" => {
+                    if size > MAX_NAME_TABLE_SIZE {
+                        return Err(invalid("long name table is too large"));
+                    }
+                    self.names = Some(self.read_bytes(size)?);
+                    continue;
+                }
+                _ => {
+                    if let Some(len) = field.strip_prefix(BSD_LONG_NAME) {
+                        let len = len
+                            .parse::<u64>()
+                            .ok()
+                            .filter(|len| *len <= size)
+                            .ok_or_else(|| invalid("bad BSD long name length"))?;
+                        let mut name = self.read_bytes(len)?;
+                        while name.last() == Some(&0) {
+                            name.pop();
+                        }
+                        String::from_utf8_lossy(&name).to_string()
+                    } else if let Some(offset) = field.strip_prefix('/') {
+                        self.long_name(offset)?
+                    } else {
+                        field.strip_suffix('/').unwrap_or(field).to_string()
+                    }
+                }
+            };
+
+            // BSD symbol tables
+            if name.starts_with("__.SYMDEF") {
+                continue;
+            }
+
+            return Ok(Some(EntryInfo {
+                path: clean(&name),
+                size: self.remaining,
+                mode: parse_field(&header[40..48], 8).map(|mode| mode as u32 & 0o7777),
+                is_dir: false,
+                last_modified: parse_field(&header[16..28], 10),
+                link: None,
+                uid: parse_field(&header[28..34], 10).map(|uid| uid as u32),
+                gid: parse_field(&header[34..40], 10).map(|gid| gid as u32),
+            }));
+        }
+    }
+
+    /// Turn the reader into a reader over the content of the current member
+    #[cfg(feature = "tar")]
+    pub(crate) fn into_member(self) -> io::Take<R> {
+        self.reader.take(self.remaining)
+    }
+}
+
+impl<R: Read> Read for ArReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.remaining == 0 || buf.is_empty() {
+            return Ok(0);
+        }
+
+        let max = (buf.len() as u64).min(self.remaining) as usize;
+        let n = self.reader.read(&mut buf[..max])?;
+        if n == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "unexpected end of ar member",
+            ));
+        }
+
+        self.remaining -= n as u64;
+        Ok(n)
+    }
+}
+
+impl Ar {
+    /// Pass every member of an archive to `f`, along with its content
+    pub(crate) fn for_each_entry<R: Read>(
+        reader: R,
+        mut f: impl FnMut(&EntryInfo, &mut dyn Read) -> Result<()>,
+    ) -> Result<()> {
+        let mut members = ArReader::new(reader);
+        while let Some(info) = members.next_member()? {
+            f(&info, &mut members)?;
+        }
+        Ok(())
+    }
+
+    /// Describe the members of an archive without reading their content
+    pub(crate) fn list<R: Read>(reader: R) -> Result<Vec<EntryInfo>> {
+        let mut members = ArReader::new(reader);
+        let mut list = Vec::new();
+        while let Some(info) = members.next_member()? {
+            list.push(info);
+        }
+        Ok(list)
+    }
+}
+
+impl Decode for Ar {
+    fn decode<T: AsRef<[u8]>>(buffer: T) -> Result<Vec<File>> {
+        Self::decode_with(buffer, &DecodeOptions::default())
+    }
+
+    fn decode_with<T: AsRef<[u8]>>(buffer: T, options: &DecodeOptions) -> Result<Vec<File>> {
+        let buffer = buffer.as_ref();
+        let mut members = ArReader::new(buffer);
+        let mut tracker = LimitTracker::new(&options.limits, buffer.len());
+        let mut files = Vec::new();
+
+        while let Some(info) = members.next_member()? {
+            let data = tracker.read(&info.path, info.size, &mut members, |e| {
+                ArchiveError::DecodeFailed {
+                    format: "ar".to_string(),
+                    reason: format!("Failed to read file content: {}", e),
+                }
+            })?;
+            files.push(File::new(
+                info.path,
+                data,
+                info.mode,
+                false,
+                info.last_modified,
+            ));
+        }
+
+        Ok(files)
+    }
+}
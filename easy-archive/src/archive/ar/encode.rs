@@ -0,0 +1,156 @@
+/// AR encoding implementation
+use crate::{
+    File,
+    error::{ArchiveError, Result},
+    traits::Encode,
+    types::{EncodeOptions, EntryInfo},
+    utils::{check_duplicate_files, reject_password},
+};
+use std::io::{Read, Write};
+
+use super::{Ar, BSD_LONG_NAME, HEADER_END, MAGIC};
+
+/// Largest member size the 10-digit size field can hold
+const MAX_SIZE: u64 = 9_999_999_999;
+
+/// Largest modification time the 12-digit field can hold
+const MAX_MTIME: u64 = 999_999_999_999;
+
+/// Largest user or group id the 6-digit fields can hold
+const MAX_ID: u32 = 999_999;
+
+fn encode_error(reason: String) -> ArchiveError {
+    ArchiveError::EncodeFailed {
+        format: "ar".to_string(),
+        reason,
+    }
+}
+
+/// Write a left-aligned, space-padded header field
+fn field(header: &mut Vec<u8>, value: &str, width: usize) {
+    header.extend_from_slice(value.as_bytes());
+    header.resize(header.len() + width - value.len(), b' ');
+}
+
+impl Ar {
+    /// Validate the options and write the global header
+    pub(crate) fn start<W: Write>(writer: &mut W, options: &EncodeOptions) -> Result<()> {
+        reject_password("ar", options)?;
+        writer.write_all(MAGIC)?;
+        Ok(())
+    }
+}
+
+/// Append a single member to an ar archive
+///
+/// ar archives are flat and cannot store directories or symbolic links:
+/// directories are skipped, since extracting the members recreates their
+/// parents, and symbolic links are rejected. Names that do not fit the
+/// 16-byte name field are written as BSD long names.
+///
+/// # Arguments
+/// * `writer` - The archive being written, after its global header
+/// * `info` - The entry metadata
+/// * `reader` - The entry content, exactly `info.size` bytes
+pub(crate) fn append_ar_entry<W: Write, R: Read>(
+    writer: &mut W,
+    info: &EntryInfo,
+    reader: R,
+) -> Result<()> {
+    if info.is_dir {
+        return Ok(());
+    }
+    if info.link.is_some() {
+        return Err(encode_error(format!(
+            "'{}' is a symbolic link, which ar cannot store",
+            info.path
+        )));
+    }
+
+    let name = info.path.as_bytes();
+    let long = name.len() > 16
+        || name.is_empty()
+        || name.contains(&b' ')
+        || name.contains(&b'/')
+        || info.path.starts_with(BSD_LONG_NAME);
+    let size = if long {
+        info.size + name.len() as u64
+    } else {
+        info.size
+    };
+    if size > MAX_SIZE {
+        return Err(encode_error(format!(
+            "'{}' is too large for an ar member",
+            info.path
+        )));
+    }
+
+    let mtime = info.last_modified.unwrap_or(0);
+    let uid = info.uid.unwrap_or(0);
+    let gid = info.gid.unwrap_or(0);
+    if mtime > MAX_MTIME || uid > MAX_ID || gid > MAX_ID {
+        return Err(encode_error(format!(
+            "Metadata of '{}' does not fit an ar header",
+            info.path
+        )));
+    }
+
+    let mut header = Vec::with_capacity(super::HEADER_SIZE);
+    if long {
+        field(&mut header, &format!("{}{}", BSD_LONG_NAME, name.len()), 16);
+    } else {
+        field(&mut header, &info.path, 16);
+    }
+    field(&mut header, &mtime.to_string(), 12);
+    field(&mut header, &uid.to_string(), 6);
+    field(&mut header, &gid.to_string(), 6);
+    field(
+        &mut header,
+        &format!("{:o}", 0o100000 | (info.mode.unwrap_or(0o644) & 0o7777)),
+        8,
+    );
+    field(&mut header, &size.to_string(), 10);
+    header.extend_from_slice(HEADER_END);
+    writer.write_all(&header)?;
+    if long {
+        writer.write_all(name)?;
+    }
+
+    let mut data = reader.take(info.size);
+    std::io::copy(&mut data, writer)
+        .map_err(|e| encode_error(format!("Failed to write file '{}': {}", info.path, e)))?;
+
+    // A short reader would leave the archive with a truncated member
+    if data.limit() != 0 {
+        return Err(encode_error(format!(
+            "Content of '{}' is shorter than its declared size of {} bytes",
+            info.path, info.size
+        )));
+    }
+
+    if size % 2 == 1 {
+        writer.write_all(b"\n")?;
+    }
+    Ok(())
+}
+
+impl Encode for Ar {
+    fn encode(files: Vec<File>) -> Result<Vec<u8>> {
+        Self::encode_with(files, &EncodeOptions::default())
+    }
+
+    fn encode_with(files: Vec<File>, options: &EncodeOptions) -> Result<Vec<u8>> {
+        check_duplicate_files(&files)?;
+        let files = options.prepare_files(files);
+
+        let estimated_size: usize = files.iter().map(|f| f.buffer.len() + 60).sum();
+        let mut buffer = Vec::with_capacity(MAGIC.len() + estimated_size);
+
+        Self::start(&mut buffer, options)?;
+        for file in &files {
+            append_ar_entry(&mut buffer, &EntryInfo::from(file), &file.buffer[..])?;
+        }
+
+        Ok(buffer)
+    }
+}
@@ -0,0 +1,32 @@
+/// Unix ar archive format, also the container of Debian packages
+///
+/// Both the GNU and the BSD variants are decoded, including their long
+/// names. Archives are written in the BSD variant, whose long names are
+/// stored next to each member so entries can be streamed.
+#[cfg(feature = "ar")]
+pub struct Ar;
+
+#[cfg(all(feature = "ar", feature = "decode"))]
+pub(crate) mod decode;
+
+#[cfg(all(feature = "ar", feature = "encode"))]
+pub(crate) mod encode;
+
+#[cfg(all(feature = "ar", feature = "tar", feature = "decode"))]
+mod deb;
+
+/// Global header of an ar archive
+#[cfg(feature = "ar")]
+pub(crate) const MAGIC: &[u8; 8] = b"!<arch>\n";
+
+/// Size of a member header in bytes
+#[cfg(all(feature = "ar", any(feature = "decode", feature = "encode")))]
+const HEADER_SIZE: usize = 60;
+
+/// Terminator of a member header
+#[cfg(all(feature = "ar", any(feature = "decode", feature = "encode")))]
+const HEADER_END: &[u8; 2] = b"`\n";
+
+/// Prefix of BSD long names, followed by the length of the name
+#[cfg(all(feature = "ar", any(feature = "decode", feature = "encode")))]
+const BSD_LONG_NAME: &str = "#1/";
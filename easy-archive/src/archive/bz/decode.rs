@@ -0,0 +1,58 @@
+/// BZ2 decoding implementation
+use crate::{
+    File,
+    archive::single::{SingleStream, count_single, for_each_single, read_single},
+    error::Result,
+    traits::Decode,
+    types::{DecodeOptions, EntryInfo},
+};
+use bzip2_rs::DecoderReader;
+use std::io::{BufReader, Read, Seek};
+
+use super::Bz;
+
+impl Bz {
+    /// Wrap a reader with a streaming bzip2 decompressor
+    pub(crate) fn decompress<'a, R: Read + 'a>(reader: R) -> Result<Box<dyn Read + 'a>> {
+        Ok(Box::new(BufReader::new(DecoderReader::new(reader))))
+    }
+
+    /// Wrap a reader with a streaming decompressor, along with the file it holds
+    ///
+    /// The format records no metadata, so the file is unnamed, and its size
+    /// is left at zero as it is only known once the stream is read.
+    pub(crate) fn open<'a, R: Read + 'a>(reader: R) -> Result<SingleStream<'a>> {
+        Ok((EntryInfo::default(), Self::decompress(reader)?))
+    }
+
+    /// Describe the compressed file, decompressing it without buffering its content
+    pub(crate) fn list<R: Read>(reader: R) -> Result<Vec<EntryInfo>> {
+        let (mut info, decoder) = Self::open(reader)?;
+        info.size = count_single("bz2", decoder)?;
+        Ok(vec![info])
+    }
+
+    /// Pass the compressed file to `f`, decompressing it without buffering its content
+    pub(crate) fn for_each_entry<R: Read + Seek>(
+        reader: R,
+        options: &DecodeOptions,
+        f: &mut dyn FnMut(&EntryInfo, &mut dyn Read) -> Result<()>,
+    ) -> Result<()> {
+        for_each_single("bz2", reader, &options.limits, |r| Self::open(r), f)
+    }
+}
+
+impl Decode for Bz {
+    fn decode<T: AsRef<[u8]>>(buffer: T) -> Result<Vec<File>> {
+        Self::decode_with(buffer, &DecodeOptions::default())
+    }
+
+    fn decode_with<T: AsRef<[u8]>>(buffer: T, options: &DecodeOptions) -> Result<Vec<File>> {
+        let buffer = buffer.as_ref();
+        let decoder = Self::decompress(buffer)?;
+        // Bzip2 does not record the file name
+        let data = read_single("bz2", "", decoder, &options.limits, buffer.len())?;
+
+        Ok(vec![File::new(String::new(), data, None, false, None)])
+    }
+}
@@ -0,0 +1,53 @@
+/// BZ2 encoding implementation
+use crate::{
+    File,
+    archive::single::{compress_single, single_file},
+    error::Result,
+    traits::{Compressor, Encode},
+    types::EncodeOptions,
+    utils::reject_password,
+};
+use bzip2::{Compression, write::BzEncoder};
+use std::io::Write;
+
+use super::Bz;
+
+impl<W: Write> Compressor for BzEncoder<W> {
+    fn finish(self: Box<Self>) -> std::io::Result<()> {
+        BzEncoder::finish(*self).map(|_| ())
+    }
+}
+
+impl Bz {
+    /// Wrap a writer with a bzip2 compressor
+    pub(crate) fn compressor<'a, W: Write + 'a>(
+        writer: W,
+        options: &EncodeOptions,
+    ) -> Result<Box<dyn Compressor + 'a>> {
+        reject_password("bz2", options)?;
+
+        // Use default compression level (6) for balanced speed/compression
+        let level = options.level.map_or(Compression::default(), |level| {
+            Compression::new(level.clamp(1, 9))
+        });
+        Ok(Box::new(BzEncoder::new(writer, level)))
+    }
+}
+
+impl Encode for Bz {
+    fn encode(files: Vec<File>) -> Result<Vec<u8>> {
+        Self::encode_with(files, &EncodeOptions::default())
+    }
+
+    fn encode_with(files: Vec<File>, options: &EncodeOptions) -> Result<Vec<u8>> {
+        let file = single_file("bz2", files)?;
+        let mut compressed = Vec::new();
+
+        compress_single(
+            Self::compressor(&mut compressed, options)?,
+            &file.buffer[..],
+        )?;
+
+        Ok(compressed)
+    }
+}
@@ -0,0 +1,9 @@
+/// Bzip2-compressed single file format
+#[cfg(feature = "bz")]
+pub struct Bz;
+
+#[cfg(all(feature = "bz", feature = "decode"))]
+mod decode;
+
+#[cfg(all(feature = "bz", feature = "encode"))]
+mod encode;
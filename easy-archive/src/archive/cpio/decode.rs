@@ -0,0 +1,367 @@
+/// CPIO decoding implementation
+use crate::{
+    File,
+    error::{ArchiveError, Result},
+    traits::Decode,
+    types::{DecodeLimits, DecodeOptions, EntryInfo},
+    utils::{LimitTracker, clean},
+};
+use std::collections::HashMap;
+use std::io::{self, Read};
+
+use super::{
+    CRC_MAGIC, Cpio, NEWC_HEADER_SIZE, NEWC_MAGIC, ODC_HEADER_SIZE, ODC_MAGIC, S_IFDIR, S_IFLNK,
+    S_IFMT, S_IFREG, TRAILER,
+};
+
+/// Maximum size of member names and symbolic link targets, which are buffered
+const MAX_NAME_SIZE: u64 = 1 << 20;
+
+/// Convert an I/O error into a cpio decode error
+fn decode_error(e: io::Error) -> ArchiveError {
+    ArchiveError::DecodeFailed {
+        format: "cpio".to_string(),
+        reason: e.to_string(),
+    }
+}
+
+fn invalid(reason: &str) -> ArchiveError {
+    ArchiveError::InvalidArchive(format!("Invalid cpio archive: {}", reason))
+}
+
+/// Parse a fixed-width numeric header field
+fn parse_field(field: &[u8], radix: u32) -> Result<u64> {
+    std::str::from_utf8(field)
+        .ok()
+        .and_then(|text| u64::from_str_radix(text, radix).ok())
+        .ok_or_else(|| invalid("bad header field"))
+}
+
+/// Number of zero bytes aligning `len` to `align`
+#[inline]
+fn padding(len: u64, align: u64) -> u64 {
+    (align - len % align) % align
+}
+
+/// The fields of a member header used by the decoder
+struct Header {
+    dev: u64,
+    ino: u64,
+    mode: u32,
+    uid: u32,
+    gid: u32,
+    nlink: u64,
+    mtime: u64,
+    name_size: u64,
+    size: u64,
+    /// Sum of the content bytes, recorded by the `crc` variant
+    checksum: Option<u32>,
+    /// Alignment of the name and the content, 4 for `newc` and 1 for `odc`
+    align: u64,
+}
+
+/// Streaming reader over the members of a cpio archive
+///
+/// Special files such as device nodes and FIFOs are skipped. The reader
+/// implements [`Read`] for the content of the current member, verifying
+/// the checksum of `crc` archives once the content is read.
+pub(crate) struct CpioReader<R: Read> {
+    reader: R,
+    done: bool,
+    /// Unread content of the current member
+    remaining: u64,
+    /// Zero bytes following the content of the current member
+    padding: u64,
+    /// Expected and running checksum of the current member
+    checksum: Option<(u32, u32)>,
+    /// Device and inode of the current member when it has other hard links
+    link_id: Option<(u64, u64)>,
+}
+
+impl<R: Read> CpioReader<R> {
+    pub(crate) fn new(reader: R) -> Self {
+        CpioReader {
+            reader,
+            done: false,
+            remaining: 0,
+            padding: 0,
+            checksum: None,
+            link_id: None,
+        }
+    }
+
+    /// Fill `buf` completely, failing at the end of input
+    fn read_exact_header(&mut self, buf: &mut [u8]) -> Result<()> {
+        let mut filled = 0;
+        while filled < buf.len() {
+            match self.reader.read(&mut buf[filled..]) {
+                Ok(0) => return Err(invalid("truncated header")),
+                Ok(n) => filled += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(decode_error(e)),
+            }
+        }
+        Ok(())
+    }
+
+    /// Discard `len` bytes of the underlying reader
+    fn discard(&mut self, len: u64) -> Result<()> {
+        let skipped =
+            io::copy(&mut (&mut self.reader).take(len), &mut io::sink()).map_err(decode_error)?;
+        if skipped < len {
+            return Err(invalid("truncated member"));
+        }
+        Ok(())
+    }
+
+    /// Discard the unread content of the current member and its padding
+    fn skip(&mut self) -> Result<()> {
+        io::copy(self, &mut io::sink()).map_err(decode_error)?;
+        self.discard(self.padding)?;
+        self.padding = 0;
+        Ok(())
+    }
+
+    fn read_header(&mut self) -> Result<Header> {
+        let mut magic = [0; 6];
+        self.read_exact_header(&mut magic)?;
+
+        match &magic {
+            NEWC_MAGIC | CRC_MAGIC => {
+                let mut fields = [0; NEWC_HEADER_SIZE - 6];
+                self.read_exact_header(&mut fields)?;
+                let field = |i: usize| parse_field(&fields[i * 8..i * 8 + 8], 16);
+                Ok(Header {
+                    ino: field(0)?,
+                    mode: field(1)? as u32,
+                    uid: field(2)? as u32,
+                    gid: field(3)? as u32,
+                    nlink: field(4)?,
+                    mtime: field(5)?,
+                    size: field(6)?,
+                    dev: (field(7)? << 32) | field(8)?,
+                    name_size: field(11)?,
+                    checksum: match &magic {
+                        CRC_MAGIC => Some(field(12)? as u32),
+                        _ => None,
+                    },
+                    align: 4,
+                })
+            }
+            ODC_MAGIC => {
+                let mut fields = [0; ODC_HEADER_SIZE - 6];
+                self.read_exact_header(&mut fields)?;
+                let field = |start: usize, len: usize| parse_field(&fields[start..start + len], 8);
+                Ok(Header {
+                    dev: field(0, 6)?,
+                    ino: field(6, 6)?,
+                    mode: field(12, 6)? as u32,
+                    uid: field(18, 6)? as u32,
+                    gid: field(24, 6)? as u32,
+                    nlink: field(30, 6)?,
+                    mtime: field(42, 11)?,
+                    name_size: field(53, 6)?,
+                    size: field(59, 11)?,
+                    checksum: None,
+                    align: 1,
+                })
+            }
+            _ => Err(invalid("unsupported header magic")),
+        }
+    }
+
+    /// Advance to the next member of the archive
+    ///
+    /// # Returns
+    /// * `Ok(Some(EntryInfo))` - The next member, readable for its content
+    /// * `Ok(None)` - If the trailer is reached
+    /// * `Err(ArchiveError)` - If the archive is malformed or reading fails
+    pub(crate) fn next_member(&mut self) -> Result<Option<EntryInfo>> {
+        loop {
+            if self.done {
+                return Ok(None);
+            }
+            self.skip()?;
+            self.checksum = None;
+            self.link_id = None;
+
+            let header = self.read_header()?;
+            if header.name_size == 0 || header.name_size > MAX_NAME_SIZE {
+                return Err(invalid("bad name size"));
+            }
+            let mut name = vec![0; header.name_size as usize];
+            self.read_exact_header(&mut name)?;
+            self.discard(padding(
+                NEWC_HEADER_SIZE as u64 + header.name_size,
+                header.align,
+            ))?;
+            let end = name.iter().position(|b| *b == 0).unwrap_or(name.len());
+            let name = String::from_utf8_lossy(&name[..end]).to_string();
+
+            self.remaining = header.size;
+            self.padding = padding(header.size, header.align);
+            if name == TRAILER {
+                self.done = true;
+                return Ok(None);
+            }
+
+            let mut size = header.size;
+            let mut link = None;
+            match header.mode & S_IFMT {
+                S_IFREG => {
+                    self.checksum = header.checksum.map(|checksum| (checksum, 0));
+                    if header.nlink > 1 {
+                        self.link_id = Some((header.dev, header.ino));
+                    }
+                }
+                S_IFDIR => {
+                    self.skip()?;
+                    size = 0;
+                }
+                S_IFLNK => {
+                    // The link target is the content of the member
+                    if size > MAX_NAME_SIZE {
+                        return Err(invalid("symbolic link target is too long"));
+                    }
+                    let mut target = Vec::new();
+                    self.read_to_end(&mut target).map_err(decode_error)?;
+                    link = Some(String::from_utf8_lossy(&target).to_string());
+                    size = 0;
+                }
+                // Device nodes, FIFOs and sockets have no place in the output
+                _ => continue,
+            }
+
+            return Ok(Some(EntryInfo {
+                path: clean(&name),
+                size,
+                mode: Some(header.mode & 0o7777),
+                is_dir: header.mode & S_IFMT == S_IFDIR,
+                last_modified: Some(header.mtime),
+                link,
+                uid: Some(header.uid),
+                gid: Some(header.gid),
+            }));
+        }
+    }
+}
+
+impl<R: Read> Read for CpioReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.remaining == 0 || buf.is_empty() {
+            return Ok(0);
+        }
+
+        let max = (buf.len() as u64).min(self.remaining) as usize;
+        let n = self.reader.read(&mut buf[..max])?;
+        if n == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "unexpected end of cpio member",
+            ));
+        }
+
+        self.remaining -= n as u64;
+        if let Some((expected, sum)) = &mut self.checksum {
+            *sum = buf[..n]
+                .iter()
+                .fold(*sum, |sum, b| sum.wrapping_add(*b as u32));
+            if self.remaining == 0 && sum != expected {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "cpio checksum mismatch",
+                ));
+            }
+        }
+        Ok(n)
+    }
+}
+
+/// Decode every member of a cpio archive within the limits
+///
+/// `newc` archives store the content of hard-linked files only with the
+/// last link, so the earlier links are given the same content.
+///
+/// # Arguments
+/// * `reader` - A reader providing the uncompressed cpio data
+/// * `limits` - The limits on the unpacked data
+/// * `packed` - The size of the (compressed) archive, for the ratio limit
+pub(crate) fn decode_cpio_archive<R: Read>(
+    reader: R,
+    limits: &DecodeLimits,
+    packed: usize,
+) -> Result<Vec<File>> {
+    let mut members = CpioReader::new(reader);
+    let mut tracker = LimitTracker::new(limits, packed);
+    let mut files: Vec<File> = Vec::new();
+    let mut links: HashMap<(u64, u64), Vec<usize>> = HashMap::new();
+
+    while let Some(info) = members.next_member()? {
+        let data = tracker.read(&info.path, info.size, &mut members, |e| {
+            ArchiveError::DecodeFailed {
+                format: "cpio".to_string(),
+                reason: format!("Failed to read file content: {}", e),
+            }
+        })?;
+
+        if let Some(id) = members.link_id {
+            if data.is_empty() {
+                links.entry(id).or_default().push(files.len());
+            } else {
+                for i in links.remove(&id).unwrap_or_default() {
+                    tracker.finish(&files[i].path, data.len() as u64)?;
+                    files[i].buffer = data.clone();
+                }
+            }
+        }
+
+        files.push(File {
+            path: info.path,
+            buffer: data,
+            mode: info.mode,
+            is_dir: info.is_dir,
+            last_modified: info.last_modified,
+            link: info.link,
+        });
+    }
+
+    Ok(files)
+}
+
+impl Cpio {
+    /// Pass every member of an archive to `f`, along with its content
+    ///
+    /// Unlike [`decode_cpio_archive`], the earlier links of a hard-linked
+    /// file are passed with the empty content the archive records for them.
+    pub(crate) fn for_each_entry<R: Read>(
+        reader: R,
+        mut f: impl FnMut(&EntryInfo, &mut dyn Read) -> Result<()>,
+    ) -> Result<()> {
+        let mut members = CpioReader::new(reader);
+        while let Some(info) = members.next_member()? {
+            f(&info, &mut members)?;
+        }
+        Ok(())
+    }
+
+    /// Describe the members of an archive without reading their content
+    pub(crate) fn list<R: Read>(reader: R) -> Result<Vec<EntryInfo>> {
+        let mut members = CpioReader::new(reader);
+        let mut list = Vec::new();
+        while let Some(info) = members.next_member()? {
+            list.push(info);
+        }
+        Ok(list)
+    }
+}
+
+impl Decode for Cpio {
+    fn decode<T: AsRef<[u8]>>(buffer: T) -> Result<Vec<File>> {
+        Self::decode_with(buffer, &DecodeOptions::default())
+    }
+
+    fn decode_with<T: AsRef<[u8]>>(buffer: T, options: &DecodeOptions) -> Result<Vec<File>> {
+        let buffer = buffer.as_ref();
+        decode_cpio_archive(buffer, &options.limits, buffer.len())
+    }
+}
@@ -0,0 +1,176 @@
+/// CPIO encoding implementation
+use crate::{
+    File,
+    error::{ArchiveError, Result},
+    traits::Encode,
+    types::{EncodeOptions, EntryInfo},
+    utils::{check_duplicate_files, reject_password},
+};
+use std::io::{Read, Write};
+
+use super::{Cpio, NEWC_HEADER_SIZE, NEWC_MAGIC, S_IFDIR, S_IFLNK, S_IFMT, S_IFREG, TRAILER};
+
+/// Block size the archive is padded to, like `cpio -o` does
+const BLOCK_SIZE: u64 = 512;
+
+fn encode_error(reason: String) -> ArchiveError {
+    ArchiveError::EncodeFailed {
+        format: "cpio".to_string(),
+        reason,
+    }
+}
+
+/// Streaming writer of `newc` archives
+///
+/// Every member is given its own inode number, so no member is mistaken
+/// for a hard link of another one.
+pub(crate) struct CpioWriter<W: Write> {
+    writer: W,
+    /// Inode number of the next member
+    ino: u32,
+    /// Bytes written so far, for the final block padding
+    written: u64,
+}
+
+impl<W: Write> CpioWriter<W> {
+    /// Create a writer, validating the options
+    pub(crate) fn new(writer: W, options: &EncodeOptions) -> Result<Self> {
+        reject_password("cpio", options)?;
+        Ok(CpioWriter {
+            writer,
+            ino: 1,
+            written: 0,
+        })
+    }
+
+    fn write(&mut self, data: &[u8]) -> Result<()> {
+        self.writer.write_all(data)?;
+        self.written += data.len() as u64;
+        Ok(())
+    }
+
+    /// Write a member header followed by the padded name
+    fn write_header(
+        &mut self,
+        ino: u32,
+        name: &str,
+        mode: u32,
+        info: &EntryInfo,
+        size: u32,
+    ) -> Result<()> {
+        let mtime = u32::try_from(info.last_modified.unwrap_or(0)).map_err(|_| {
+            encode_error(format!(
+                "Modification time of '{}' does not fit a cpio header",
+                name
+            ))
+        })?;
+        let nlink = if mode & S_IFMT == S_IFDIR { 2 } else { 1 };
+        let name_size = name.len() as u32 + 1;
+
+        let mut header = Vec::with_capacity(NEWC_HEADER_SIZE + name.len() + 4);
+        header.extend_from_slice(NEWC_MAGIC);
+        let fields = [
+            ino,
+            mode,
+            info.uid.unwrap_or(0),
+            info.gid.unwrap_or(0),
+            nlink,
+            mtime,
+            size,
+            // Device numbers of the file and, for device nodes, of the device
+            0,
+            0,
+            0,
+            0,
+            name_size,
+            // Checksum, only used by the crc variant
+            0,
+        ];
+        for field in fields {
+            header.extend_from_slice(format!("{:08X}", field).as_bytes());
+        }
+        header.extend_from_slice(name.as_bytes());
+        header.push(0);
+        header.resize(header.len().next_multiple_of(4), 0);
+        self.write(&header)
+    }
+
+    /// Append a single member to the archive
+    ///
+    /// # Arguments
+    /// * `info` - The entry metadata
+    /// * `reader` - The entry content, exactly `info.size` bytes (ignored for
+    ///   directories and symbolic links)
+    pub(crate) fn append<R: Read>(&mut self, info: &EntryInfo, reader: R) -> Result<()> {
+        let path = info.path.trim_end_matches('/');
+        let (mode, size) = if info.is_dir {
+            (S_IFDIR | (info.mode.unwrap_or(0o755) & 0o7777), 0)
+        } else if let Some(link) = &info.link {
+            (
+                S_IFLNK | (info.mode.unwrap_or(0o777) & 0o7777),
+                link.len() as u64,
+            )
+        } else {
+            (S_IFREG | (info.mode.unwrap_or(0o644) & 0o7777), info.size)
+        };
+        let size = u32::try_from(size)
+            .map_err(|_| encode_error(format!("'{}' is too large for a cpio member", path)))?;
+
+        let ino = self.ino;
+        self.ino += 1;
+        self.write_header(ino, path, mode, info, size)?;
+
+        if let Some(link) = &info.link {
+            self.write(link.as_bytes())?;
+        } else if !info.is_dir {
+            let copied = std::io::copy(&mut reader.take(info.size), &mut self.writer)
+                .map_err(|e| encode_error(format!("Failed to write file '{}': {}", path, e)))?;
+            self.written += copied;
+
+            // A short reader would leave the archive with a truncated member
+            if copied < info.size {
+                return Err(encode_error(format!(
+                    "Content of '{}' is shorter than its declared size of {} bytes",
+                    path, info.size
+                )));
+            }
+        }
+
+        let padding = (4 - size % 4) % 4;
+        self.write(&[0; 3][..padding as usize])
+    }
+
+    /// Write the trailer, pad the archive to a whole block and flush it
+    pub(crate) fn finish(mut self) -> Result<W> {
+        self.write_header(0, TRAILER, 0, &EntryInfo::default(), 0)?;
+        let padding = self.written.next_multiple_of(BLOCK_SIZE) - self.written;
+        self.write(&vec![0; padding as usize])?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+impl Encode for Cpio {
+    fn encode(files: Vec<File>) -> Result<Vec<u8>> {
+        Self::encode_with(files, &EncodeOptions::default())
+    }
+
+    fn encode_with(files: Vec<File>, options: &EncodeOptions) -> Result<Vec<u8>> {
+        check_duplicate_files(&files)?;
+        let files = options.prepare_files(files);
+
+        let estimated_size: usize = files
+            .iter()
+            .map(|f| f.buffer.len() + f.path.len() + NEWC_HEADER_SIZE + 4)
+            .sum();
+        let mut writer = CpioWriter::new(
+            Vec::with_capacity(estimated_size + BLOCK_SIZE as usize),
+            options,
+        )?;
+        for file in &files {
+            writer.append(&EntryInfo::from(file), &file.buffer[..])?;
+        }
+
+        writer.finish()
+    }
+}
@@ -0,0 +1,58 @@
+/// cpio archive format, used by initramfs images and RPM payloads
+///
+/// The portable ASCII variants are decoded: `newc`, its checksummed `crc`
+/// sibling and the older `odc`. Archives are written in the `newc` variant,
+/// which the Linux kernel expects for initramfs images.
+#[cfg(feature = "cpio")]
+pub struct Cpio;
+
+#[cfg(all(feature = "cpio", feature = "decode"))]
+pub(crate) mod decode;
+
+#[cfg(all(feature = "cpio", feature = "encode"))]
+pub(crate) mod encode;
+
+#[cfg(all(feature = "cpio", feature = "decode"))]
+mod rpm;
+
+/// Magic of the `newc` variant, with 8-digit hexadecimal fields
+#[cfg(feature = "cpio")]
+pub(crate) const NEWC_MAGIC: &[u8; 6] = b"070701";
+
+/// Magic of the `crc` variant, a `newc` header carrying a content checksum
+#[cfg(feature = "cpio")]
+pub(crate) const CRC_MAGIC: &[u8; 6] = b"070702";
+
+/// Magic of the `odc` variant, with octal fields
+#[cfg(feature = "cpio")]
+pub(crate) const ODC_MAGIC: &[u8; 6] = b"070707";
+
+/// Name of the member that ends the archive
+#[cfg(all(feature = "cpio", any(feature = "decode", feature = "encode")))]
+const TRAILER: &str = "TRAILER!!!";
+
+/// Size of a `newc` or `crc` header in bytes
+#[cfg(all(feature = "cpio", any(feature = "decode", feature = "encode")))]
+const NEWC_HEADER_SIZE: usize = 110;
+
+/// Size of an `odc` header in bytes
+#[cfg(all(feature = "cpio", feature = "decode"))]
+const ODC_HEADER_SIZE: usize = 76;
+
+/// File type bits of the mode field
+#[cfg(all(feature = "cpio", any(feature = "decode", feature = "encode")))]
+const S_IFMT: u32 = 0o170000;
+#[cfg(all(feature = "cpio", any(feature = "decode", feature = "encode")))]
+const S_IFDIR: u32 = 0o040000;
+#[cfg(all(feature = "cpio", any(feature = "decode", feature = "encode")))]
+const S_IFREG: u32 = 0o100000;
+#[cfg(all(feature = "cpio", any(feature = "decode", feature = "encode")))]
+const S_IFLNK: u32 = 0o120000;
+
+/// Check whether a buffer starts with one of the decoded cpio variants
+#[cfg(feature = "cpio")]
+pub(crate) fn is_cpio(buffer: &[u8]) -> bool {
+    [NEWC_MAGIC, CRC_MAGIC, ODC_MAGIC]
+        .iter()
+        .any(|magic| buffer.starts_with(*magic))
+}
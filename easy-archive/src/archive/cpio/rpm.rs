@@ -0,0 +1,171 @@
+/// RPM package helpers
+use crate::{
+    File,
+    error::{ArchiveError, Result},
+    types::DecodeOptions,
+};
+use std::io::Read;
+
+#[cfg(feature = "bz")]
+use crate::archive::Bz;
+#[cfg(feature = "xz")]
+use crate::archive::Xz;
+#[cfg(feature = "zstd")]
+use crate::archive::Zstd;
+
+use super::{Cpio, decode::decode_cpio_archive, is_cpio};
+
+/// Magic of the lead that starts every package
+const LEAD_MAGIC: &[u8; 4] = &[0xED, 0xAB, 0xEE, 0xDB];
+
+/// Size of the lead in bytes
+const LEAD_SIZE: usize = 96;
+
+/// Magic of the signature and main header structures
+const HEADER_MAGIC: &[u8; 3] = &[0x8E, 0xAD, 0xE8];
+
+/// Size of a header structure preamble and of each of its index entries
+const INDEX_ENTRY_SIZE: usize = 16;
+
+/// Tags of the main header describing the payload
+const TAG_PAYLOAD_FORMAT: u32 = 1124;
+const TAG_PAYLOAD_COMPRESSOR: u32 = 1125;
+
+/// Type of NUL-terminated string tags
+const TYPE_STRING: u32 = 6;
+
+fn invalid(reason: &str) -> ArchiveError {
+    ArchiveError::InvalidArchive(format!("Invalid RPM package: {}", reason))
+}
+
+fn be_u32(bytes: &[u8]) -> u32 {
+    u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+/// A header structure: an index of tags and the data they point into
+struct Header<'a> {
+    index: &'a [u8],
+    data: &'a [u8],
+}
+
+impl<'a> Header<'a> {
+    /// Parse the header structure at the start of `buffer`
+    ///
+    /// # Returns
+    /// The header and its size in bytes
+    fn parse(buffer: &'a [u8]) -> Result<(Self, usize)> {
+        if buffer.len() < INDEX_ENTRY_SIZE || !buffer.starts_with(HEADER_MAGIC) {
+            return Err(invalid("bad header magic"));
+        }
+        let count = be_u32(&buffer[8..12]) as usize;
+        let size = be_u32(&buffer[12..16]) as usize;
+        let index_end = count
+            .checked_mul(INDEX_ENTRY_SIZE)
+            .and_then(|len| len.checked_add(INDEX_ENTRY_SIZE))
+            .ok_or_else(|| invalid("bad header size"))?;
+        let end = index_end
+            .checked_add(size)
+            .filter(|end| *end <= buffer.len())
+            .ok_or_else(|| invalid("truncated header"))?;
+
+        let header = Header {
+            index: &buffer[INDEX_ENTRY_SIZE..index_end],
+            data: &buffer[index_end..end],
+        };
+        Ok((header, end))
+    }
+
+    /// Look up the value of a string tag
+    fn string(&self, tag: u32) -> Option<&'a str> {
+        self.index
+            .chunks_exact(INDEX_ENTRY_SIZE)
+            .filter(|entry| be_u32(&entry[0..4]) == tag && be_u32(&entry[4..8]) == TYPE_STRING)
+            .find_map(|entry| {
+                let value = self.data.get(be_u32(&entry[8..12]) as usize..)?;
+                let end = value.iter().position(|b| *b == 0)?;
+                std::str::from_utf8(&value[..end]).ok()
+            })
+    }
+}
+
+impl Cpio {
+    /// Decode the files of an RPM package
+    ///
+    /// The lead, signature and header of the package are skipped, and its
+    /// cpio payload is decompressed according to the payload compressor
+    /// recorded in the header. gzip, xz, lzma, bzip2 and zstd payloads are
+    /// supported when the matching single-file format is enabled.
+    ///
+    /// # Arguments
+    /// * `buffer` - The `.rpm` file
+    /// * `options` - The limits applied to the payload
+    ///
+    /// # Returns
+    /// * `Ok(Vec<File>)` - The files installed by the package
+    /// * `Err(ArchiveError)` - If the package is malformed or its payload compression is not enabled
+    ///
+    /// # Example
+    /// ```no_run
+    /// use easy_archive::{DecodeOptions, archive::Cpio};
+    /// let buffer = std::fs::read("hello-2.12.1-1.x86_64.rpm")?;
+    /// for file in Cpio::decode_rpm(buffer, &DecodeOptions::default())? {
+    ///     println!("{} {}", file.path, file.buffer.len());
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn decode_rpm<T: AsRef<[u8]>>(buffer: T, options: &DecodeOptions) -> Result<Vec<File>> {
+        let buffer = buffer.as_ref();
+        if buffer.len() < LEAD_SIZE || !buffer.starts_with(LEAD_MAGIC) {
+            return Err(invalid("missing lead"));
+        }
+
+        // The signature is padded to a multiple of 8 bytes
+        let (_, size) = Header::parse(&buffer[LEAD_SIZE..])?;
+        let offset = LEAD_SIZE + size.next_multiple_of(8);
+        let header = buffer
+            .get(offset..)
+            .ok_or_else(|| invalid("truncated signature"))?;
+        let (header, size) = Header::parse(header)?;
+        let payload = &buffer[offset + size..];
+
+        let format = header.string(TAG_PAYLOAD_FORMAT).unwrap_or("cpio");
+        if format != "cpio" {
+            return Err(ArchiveError::UnsupportedFormat(format!(
+                "{} RPM payload",
+                format
+            )));
+        }
+
+        // Packages that do not record a compressor use gzip
+        let compressor = header.string(TAG_PAYLOAD_COMPRESSOR).unwrap_or("gzip");
+        let reader: Box<dyn Read + '_> = match compressor {
+            _ if is_cpio(payload) => Box::new(payload),
+            #[cfg(feature = "gz")]
+            "gzip" => Box::new(flate2::read::MultiGzDecoder::new(payload)),
+            #[cfg(feature = "xz")]
+            "xz" => Xz::decompress(payload)?,
+            #[cfg(feature = "xz")]
+            "lzma" => {
+                let stream = liblzma::stream::Stream::new_lzma_decoder(u64::MAX).map_err(|e| {
+                    ArchiveError::DecompressionError(format!(
+                        "Failed to create LZMA decoder: {}",
+                        e
+                    ))
+                })?;
+                Box::new(liblzma::read::XzDecoder::new_stream(payload, stream))
+            }
+            #[cfg(feature = "bz")]
+            "bzip2" => Bz::decompress(payload)?,
+            #[cfg(feature = "zstd")]
+            "zstd" => Zstd::decompress(payload)?,
+            _ => {
+                return Err(ArchiveError::UnsupportedFormat(format!(
+                    "{} compressed RPM payload",
+                    compressor
+                )));
+            }
+        };
+
+        decode_cpio_archive(reader, &options.limits, payload.len())
+    }
+}
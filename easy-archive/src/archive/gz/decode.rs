@@ -0,0 +1,79 @@
+/// GZ decoding implementation
+use crate::{
+    File,
+    archive::single::{SingleStream, count_single, for_each_single, read_single},
+    error::Result,
+    traits::Decode,
+    types::{DecodeOptions, EntryInfo},
+    utils::clean,
+};
+use flate2::read::MultiGzDecoder;
+use std::io::{Read, Seek};
+
+use super::Gz;
+
+impl Gz {
+    /// Wrap a reader with a streaming gzip decompressor
+    ///
+    /// # Returns
+    /// The file described by the gzip header, with a size of zero as it is
+    /// only known once the stream is read, and the decompressed content
+    pub(crate) fn open<'a, R: Read + 'a>(reader: R) -> Result<SingleStream<'a>> {
+        let decoder = MultiGzDecoder::new(reader);
+        let (path, last_modified) = header_info(&decoder);
+        let info = EntryInfo {
+            path,
+            last_modified,
+            ..Default::default()
+        };
+        Ok((info, Box::new(decoder)))
+    }
+
+    /// Describe the compressed file, decompressing it without buffering its content
+    pub(crate) fn list<R: Read>(reader: R) -> Result<Vec<EntryInfo>> {
+        let (mut info, decoder) = Self::open(reader)?;
+        info.size = count_single("gz", decoder)?;
+        Ok(vec![info])
+    }
+
+    /// Pass the compressed file to `f`, decompressing it without buffering its content
+    pub(crate) fn for_each_entry<R: Read + Seek>(
+        reader: R,
+        options: &DecodeOptions,
+        f: &mut dyn FnMut(&EntryInfo, &mut dyn Read) -> Result<()>,
+    ) -> Result<()> {
+        for_each_single("gz", reader, &options.limits, |r| Self::open(r), f)
+    }
+}
+
+/// Read the original file name and modification time from the gzip header
+///
+/// The name is empty when the header does not record one.
+fn header_info<R: Read>(decoder: &MultiGzDecoder<R>) -> (String, Option<u64>) {
+    let header = decoder.header();
+    let path = header
+        .and_then(|header| header.filename())
+        .map(|name| clean(&String::from_utf8_lossy(name)))
+        .unwrap_or_default();
+    // A zero mtime means that no timestamp is available
+    let last_modified = header
+        .map(|header| header.mtime())
+        .filter(|mtime| *mtime != 0)
+        .map(u64::from);
+    (path, last_modified)
+}
+
+impl Decode for Gz {
+    fn decode<T: AsRef<[u8]>>(buffer: T) -> Result<Vec<File>> {
+        Self::decode_with(buffer, &DecodeOptions::default())
+    }
+
+    fn decode_with<T: AsRef<[u8]>>(buffer: T, options: &DecodeOptions) -> Result<Vec<File>> {
+        let buffer = buffer.as_ref();
+        let mut decoder = MultiGzDecoder::new(buffer);
+        let (path, last_modified) = header_info(&decoder);
+        let data = read_single("gz", &path, &mut decoder, &options.limits, buffer.len())?;
+
+        Ok(vec![File::new(path, data, None, false, last_modified)])
+    }
+}
@@ -0,0 +1,69 @@
+/// GZ encoding implementation
+use crate::{
+    File,
+    archive::single::{compress_single, single_file},
+    error::Result,
+    traits::{Compressor, Encode},
+    types::{EncodeOptions, EntryInfo},
+    utils::reject_password,
+};
+use flate2::{Compression, GzBuilder, write::GzEncoder};
+use std::io::Write;
+
+use super::Gz;
+
+impl<W: Write> Compressor for GzEncoder<W> {
+    fn finish(self: Box<Self>) -> std::io::Result<()> {
+        GzEncoder::finish(*self).map(|_| ())
+    }
+}
+
+impl Gz {
+    /// Wrap a writer with a gzip compressor
+    ///
+    /// The file name and modification time of `info`, if given, are recorded
+    /// in the gzip header, so that `gunzip -N` can restore them.
+    pub(crate) fn compressor<'a, W: Write + 'a>(
+        writer: W,
+        options: &EncodeOptions,
+        info: Option<&EntryInfo>,
+    ) -> Result<Box<dyn Compressor + 'a>> {
+        reject_password("gz", options)?;
+
+        // Use default compression level (6) for balanced speed/compression
+        let level = options.level.map_or(Compression::default(), |level| {
+            Compression::new(level.min(9))
+        });
+
+        let mut builder = GzBuilder::new();
+        if let Some(info) = info {
+            let name = info.path.rsplit('/').next().unwrap_or_default();
+            if !name.is_empty() && !name.contains('\0') {
+                builder = builder.filename(name);
+            }
+            if let Some(mtime) = info.last_modified {
+                builder = builder.mtime(mtime.min(u32::MAX as u64) as u32);
+            }
+        }
+        Ok(Box::new(builder.write(writer, level)))
+    }
+}
+
+impl Encode for Gz {
+    fn encode(files: Vec<File>) -> Result<Vec<u8>> {
+        Self::encode_with(files, &EncodeOptions::default())
+    }
+
+    fn encode_with(files: Vec<File>, options: &EncodeOptions) -> Result<Vec<u8>> {
+        let file = single_file("gz", options.prepare_files(files))?;
+        let mut compressed = Vec::with_capacity(file.buffer.len() / 3);
+
+        let info = EntryInfo::from(&file);
+        compress_single(
+            Self::compressor(&mut compressed, options, Some(&info))?,
+            &file.buffer[..],
+        )?;
+
+        Ok(compressed)
+    }
+}
@@ -0,0 +1,9 @@
+/// Gzip-compressed single file format
+#[cfg(feature = "gz")]
+pub struct Gz;
+
+#[cfg(all(feature = "gz", feature = "decode"))]
+mod decode;
+
+#[cfg(all(feature = "gz", feature = "encode"))]
+mod encode;
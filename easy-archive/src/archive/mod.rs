@@ -13,12 +13,45 @@ pub mod tar_bz;
 #[cfg(feature = "tar-zstd")]
 pub mod tar_zstd;
 
+#[cfg(feature = "tar-lz4")]
+pub mod tar_lz4;
+
+#[cfg(feature = "tar-lzma")]
+pub mod tar_lzma;
+
+#[cfg(feature = "tar-lz")]
+pub mod tar_lz;
+
+#[cfg(feature = "tar-z")]
+pub mod tar_z;
+
+#[cfg(feature = "ar")]
+pub mod ar;
+
+#[cfg(feature = "cpio")]
+pub mod cpio;
+
 #[cfg(feature = "zip")]
 pub mod zip;
 
 #[cfg(feature = "7z")]
 pub mod seven_zip;
 
+#[cfg(feature = "gz")]
+pub mod gz;
+
+#[cfg(feature = "xz")]
+pub mod xz;
+
+#[cfg(feature = "bz")]
+pub mod bz;
+
+#[cfg(feature = "zstd")]
+pub mod zstd;
+
+#[cfg(any(feature = "gz", feature = "xz", feature = "bz", feature = "zstd"))]
+pub(crate) mod single;
+
 #[cfg(feature = "tar")]
 pub use tar::Tar;
 
@@ -34,8 +67,38 @@ pub use tar_bz::TarBz;
 #[cfg(feature = "tar-zstd")]
 pub use tar_zstd::TarZstd;
 
+#[cfg(feature = "tar-lz4")]
+pub use tar_lz4::TarLz4;
+
+#[cfg(feature = "tar-lzma")]
+pub use tar_lzma::TarLzma;
+
+#[cfg(feature = "tar-lz")]
+pub use tar_lz::TarLz;
+
+#[cfg(feature = "tar-z")]
+pub use tar_z::TarZ;
+
+#[cfg(feature = "ar")]
+pub use ar::Ar;
+
+#[cfg(feature = "cpio")]
+pub use cpio::Cpio;
+
 #[cfg(feature = "zip")]
 pub use zip::Zip;
 
 #[cfg(feature = "7z")]
 pub use seven_zip::SevenZip;
+
+#[cfg(feature = "gz")]
+pub use gz::Gz;
+
+#[cfg(feature = "xz")]
+pub use xz::Xz;
+
+#[cfg(feature = "bz")]
+pub use bz::Bz;
+
+#[cfg(feature = "zstd")]
+pub use zstd::Zstd;
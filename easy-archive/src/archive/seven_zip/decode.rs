@@ -1,17 +1,23 @@
-use sevenz_rust2::{ArchiveReader, Password};
-
-/// ZIP decoding implementation
+/// 7z decoding implementation
 use crate::{
     File,
     error::{ArchiveError, Result},
     traits::Decode,
+    types::{DecodeOptions, EntryInfo},
+    utils::{LimitTracker, clean},
 };
-use std::io::{Cursor, Seek, SeekFrom, Write};
+use sevenz_rust2::{ArchiveEntry, ArchiveReader, Password};
+use std::io::{Cursor, Read, Seek, SeekFrom, Write};
+use std::time::SystemTime;
 
-use super::SevenZip;
+use super::{FILE_ATTRIBUTE_UNIX_EXTENSION, S_IFLNK, S_IFMT, SevenZip};
 
 impl Decode for SevenZip {
     fn decode<T: AsRef<[u8]>>(buffer: T) -> Result<Vec<File>> {
+        Self::decode_with(buffer, &DecodeOptions::default())
+    }
+
+    fn decode_with<T: AsRef<[u8]>>(buffer: T, options: &DecodeOptions) -> Result<Vec<File>> {
         let buffer = buffer.as_ref();
 
         // Pre-allocate cursor buffer to avoid reallocation
@@ -34,23 +40,177 @@ impl Decode for SevenZip {
         // Pre-allocate files vector (typical zip has 10-100 files)
         let mut files = Vec::with_capacity(32);
 
-        let mut seven = ArchiveReader::new(cursor, Password::empty()).unwrap();
+        let mut seven = ArchiveReader::new(cursor, password(options.password.as_deref()))
+            .map_err(|e| decode_error(e, "Failed to open 7z archive"))?;
+
+        // The header declares the entry count up front
+        let mut tracker = LimitTracker::new(&options.limits, buffer.len());
+        tracker.check_count(seven.archive().files.len() as u64)?;
+
+        // Limit errors can't pass through the callback, they stop the iteration instead
+        let mut error = None;
         seven
             .for_each_entries(|entry, reader| {
-                let mut buffer = Vec::with_capacity(entry.size as usize);
-                reader.read_to_end(&mut buffer)?;
+                let mut buffer =
+                    match tracker.read(&entry.name, entry.size, reader, ArchiveError::Io) {
+                        Ok(buffer) => buffer,
+                        // Read errors go back to the reader, which flags bad passwords
+                        Err(ArchiveError::Io(e)) => return Err(e.into()),
+                        Err(e) => {
+                            error = Some(e);
+                            return Ok(false);
+                        }
+                    };
+                let link = is_symlink(entry).then(|| String::from_utf8_lossy(&buffer).to_string());
+                if link.is_some() {
+                    buffer.clear();
+                }
+                let info = entry_info(entry);
                 let file = File {
                     buffer,
                     path: entry.name.to_string(),
-                    mode: None,
+                    mode: info.mode,
                     is_dir: entry.is_directory,
-                    last_modified: None,
+                    last_modified: info.last_modified,
+                    link,
                 };
                 files.push(file);
                 Ok(true)
             })
-            .unwrap();
+            .map_err(|e| decode_error(e, "Failed to read entries"))?;
+
+        match error {
+            Some(e) => Err(e),
+            None => Ok(files),
+        }
+    }
+}
+
+/// Convert an optional password into the 7z representation
+fn password(password: Option<&str>) -> Password {
+    password.map(Password::new).unwrap_or_else(Password::empty)
+}
+
+/// Convert a 7z error into an archive error, keeping password failures distinct
+fn decode_error(e: sevenz_rust2::Error, context: &str) -> ArchiveError {
+    match e {
+        sevenz_rust2::Error::PasswordRequired => ArchiveError::PasswordRequired,
+        sevenz_rust2::Error::MaybeBadPassword(_) => ArchiveError::InvalidPassword,
+        e => ArchiveError::DecodeFailed {
+            format: "7z".to_string(),
+            reason: format!("{}: {}", context, e),
+        },
+    }
+}
+
+/// Get the Unix mode stored in the attributes of a 7z entry, if any
+fn unix_mode(entry: &ArchiveEntry) -> Option<u32> {
+    (entry.has_windows_attributes && entry.windows_attributes & FILE_ATTRIBUTE_UNIX_EXTENSION != 0)
+        .then_some(entry.windows_attributes >> 16)
+}
+
+/// Check whether a 7z entry is a symbolic link, whose content is the target
+fn is_symlink(entry: &ArchiveEntry) -> bool {
+    unix_mode(entry).is_some_and(|mode| mode & S_IFMT == S_IFLNK)
+}
+
+/// Extract the metadata of a 7z entry
+///
+/// The link target of symlinks is stored as content and is not filled in.
+fn entry_info(entry: &ArchiveEntry) -> EntryInfo {
+    let mode = unix_mode(entry).map(|mode| mode & 0o7777);
+    let last_modified = entry
+        .has_last_modified_date
+        .then(|| SystemTime::from(entry.last_modified_date))
+        .and_then(|time| time.duration_since(SystemTime::UNIX_EPOCH).ok())
+        .map(|duration| duration.as_secs());
+
+    EntryInfo {
+        path: clean(&entry.name),
+        size: if is_symlink(entry) { 0 } else { entry.size },
+        mode,
+        is_dir: entry.is_directory,
+        last_modified,
+        link: None,
+        uid: None,
+        gid: None,
+    }
+}
+
+impl SevenZip {
+    /// Pass every entry of a 7z archive to `f`, along with its content
+    ///
+    /// Solid blocks are decompressed once, in archive order, while `f`
+    /// reads the entries.
+    pub(crate) fn for_each_entry<R: Read + Seek>(
+        reader: R,
+        options: &DecodeOptions,
+        mut f: impl FnMut(&EntryInfo, &mut dyn Read) -> Result<()>,
+    ) -> Result<()> {
+        let mut seven = ArchiveReader::new(reader, password(options.password.as_deref()))
+            .map_err(|e| decode_error(e, "Failed to open 7z archive"))?;
+
+        // Errors of `f` can't pass through the callback, they stop the iteration instead
+        let mut error = None;
+        seven
+            .for_each_entries(|entry, reader| {
+                let mut info = entry_info(entry);
+                let result = if is_symlink(entry) {
+                    let mut target = Vec::new();
+                    reader.read_to_end(&mut target)?;
+                    info.link = Some(String::from_utf8_lossy(&target).to_string());
+                    f(&info, &mut std::io::empty())
+                } else {
+                    f(&info, reader)
+                };
+                match result {
+                    Ok(()) => Ok(true),
+                    Err(e) => {
+                        error = Some(e);
+                        Ok(false)
+                    }
+                }
+            })
+            .map_err(|e| decode_error(e, "Failed to read entries"))?;
+
+        match error {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+
+    /// List the entries of a 7z archive without reading their content
+    ///
+    /// Only the archive header is read and parsed; encrypted headers need
+    /// the password.
+    pub(crate) fn list<R: Read + Seek>(
+        reader: R,
+        options: &DecodeOptions,
+    ) -> Result<Vec<EntryInfo>> {
+        let mut seven = ArchiveReader::new(reader, password(options.password.as_deref()))
+            .map_err(|e| decode_error(e, "Failed to open 7z archive"))?;
+
+        let entries: Vec<ArchiveEntry> = seven
+            .archive()
+            .files
+            .iter()
+            .filter(|entry| !entry.is_anti_item)
+            .cloned()
+            .collect();
+
+        let mut list = Vec::with_capacity(entries.len());
+        for entry in &entries {
+            let mut info = entry_info(entry);
+            // Link targets are stored as content, only those entries are decompressed
+            if is_symlink(entry) {
+                let target = seven.read_file(&entry.name).map_err(|e| {
+                    decode_error(e, &format!("Failed to read symlink '{}'", entry.name))
+                })?;
+                info.link = Some(String::from_utf8_lossy(&target).to_string());
+            }
+            list.push(info);
+        }
 
-        Ok(files)
+        Ok(list)
     }
 }
@@ -1,33 +1,113 @@
-/// ZIP encoding implementation
-use crate::{ArchiveError, File, error::Result, traits::Encode};
-use sevenz_rust2::{ArchiveEntry, ArchiveWriter, NtTime, SourceReader};
-use std::io::Cursor;
+/// 7z encoding implementation
+use crate::{
+    ArchiveError, File,
+    error::Result,
+    traits::Encode,
+    types::{EncodeOptions, EntryInfo},
+};
+use sevenz_rust2::{
+    ArchiveEntry, ArchiveWriter, NtTime, Password, SourceReader,
+    encoder_options::{AesEncoderOptions, Lzma2Options},
+};
+use std::io::{Cursor, Seek, Write};
+use std::time::{Duration, SystemTime};
 
-use super::SevenZip;
+use super::{
+    FILE_ATTRIBUTE_DIRECTORY, FILE_ATTRIBUTE_UNIX_EXTENSION, S_IFDIR, S_IFLNK, S_IFREG, SevenZip,
+};
+
+/// Build the 7z entry header for the given metadata
+pub(crate) fn archive_entry(info: &EntryInfo) -> ArchiveEntry {
+    let last_modified = info
+        .last_modified
+        .and_then(|secs| NtTime::try_from(SystemTime::UNIX_EPOCH + Duration::from_secs(secs)).ok());
+
+    // Unix file type and permissions go into the high 16 bits of the attributes
+    let attributes = match (info.is_dir, &info.link) {
+        (_, Some(_)) => Some(S_IFLNK | info.mode.unwrap_or(0o777)),
+        (true, None) => info.mode.map(|mode| S_IFDIR | mode),
+        (false, None) => info.mode.map(|mode| S_IFREG | mode),
+    }
+    .map(|mode| {
+        let dir = if info.is_dir {
+            FILE_ATTRIBUTE_DIRECTORY
+        } else {
+            0
+        };
+        (mode << 16) | FILE_ATTRIBUTE_UNIX_EXTENSION | dir
+    });
+
+    ArchiveEntry {
+        name: info.path.replace("\\", "/"),
+        has_stream: !info.is_dir,
+        is_directory: info.is_dir,
+        has_last_modified_date: last_modified.is_some(),
+        last_modified_date: last_modified.unwrap_or_default(),
+        has_windows_attributes: attributes.is_some(),
+        windows_attributes: attributes.unwrap_or_default(),
+        ..Default::default()
+    }
+}
+
+/// Size of the independently compressed chunks in multi-threaded mode
+const CHUNK_SIZE: u64 = 24 << 20;
+
+/// Create a 7z writer applying the level, thread and password options
+pub(crate) fn archive_writer<W: Write + Seek>(
+    writer: W,
+    options: &EncodeOptions,
+) -> Result<ArchiveWriter<W>> {
+    let mut w = ArchiveWriter::new(writer).map_err(|e| ArchiveError::EncodeFailed {
+        format: "7z".to_string(),
+        reason: format!("Failed to create ArchiveWriter: {}", e),
+    })?;
+
+    if options.level.is_some() || options.threads.is_some() || options.password.is_some() {
+        // LZMA2 at level 6, the same as the writer default
+        let level = options.level.unwrap_or(6).min(9);
+        let lzma2 = match options.threads {
+            #[cfg(not(target_arch = "wasm32"))]
+            Some(threads) if threads > 1 => Lzma2Options::from_level_mt(level, threads, CHUNK_SIZE),
+            _ => Lzma2Options::from_level(level),
+        };
+
+        // AES-256 runs after compression, and the header is encrypted too
+        let mut methods = vec![];
+        if let Some(password) = &options.password {
+            methods.push(AesEncoderOptions::new(Password::new(password)).into());
+            w.set_encrypt_header(true);
+        }
+        methods.push(lzma2.into());
+        w.set_content_methods(methods);
+    }
+
+    Ok(w)
+}
 
 impl Encode for SevenZip {
     fn encode(files: Vec<File>) -> Result<Vec<u8>> {
+        Self::encode_with(files, &EncodeOptions::default())
+    }
+
+    fn encode_with(files: Vec<File>, options: &EncodeOptions) -> Result<Vec<u8>> {
         let mut output = vec![];
         let cursor = Cursor::new(&mut output);
 
-        let mut w = ArchiveWriter::new(cursor).map_err(|e| ArchiveError::EncodeFailed {
-            format: "7z".to_string(),
-            reason: format!("Failed to create ArchiveWriter: {}", e),
-        })?;
+        let mut w = archive_writer(cursor, options)?;
 
         let mut entries = vec![];
         let mut readers = vec![];
 
-        for file in files {
-            entries.push(ArchiveEntry {
-                name: file.path.replace("\\", "/"),
-                has_stream: true,
-                is_directory: file.is_dir,
-                has_last_modified_date: file.last_modified.is_some(),
-                last_modified_date: NtTime::new(file.last_modified.unwrap_or_default()),
-                ..Default::default()
-            });
-            readers.push(SourceReader::new(Cursor::new(file.buffer)));
+        for file in options.prepare_files(files) {
+            let mut entry = archive_entry(&EntryInfo::from(&file));
+            entry.has_stream = true;
+            entries.push(entry);
+            // Symlinks store their target as content
+            let buffer = match file.link {
+                Some(link) => link.into_bytes(),
+                None => file.buffer,
+            };
+            readers.push(SourceReader::new(Cursor::new(buffer)));
         }
         w.push_archive_entries(entries, readers)
             .map_err(|e| ArchiveError::EncodeFailed {
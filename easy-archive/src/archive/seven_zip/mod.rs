@@ -6,4 +6,22 @@ pub struct SevenZip;
 mod decode;
 
 #[cfg(all(feature = "7z", feature = "encode"))]
-mod encode;
+pub(crate) mod encode;
+
+/// Windows attribute of directories
+#[cfg(all(feature = "7z", feature = "encode"))]
+pub(crate) const FILE_ATTRIBUTE_DIRECTORY: u32 = 0x10;
+
+/// Windows attribute flag marking a Unix mode in the high 16 bits
+#[cfg(all(feature = "7z", any(feature = "decode", feature = "encode")))]
+pub(crate) const FILE_ATTRIBUTE_UNIX_EXTENSION: u32 = 0x8000;
+
+/// Unix file type mask and types, as stored in the high 16 bits
+#[cfg(all(feature = "7z", feature = "decode"))]
+pub(crate) const S_IFMT: u32 = 0o170000;
+#[cfg(all(feature = "7z", any(feature = "decode", feature = "encode")))]
+pub(crate) const S_IFLNK: u32 = 0o120000;
+#[cfg(all(feature = "7z", feature = "encode"))]
+pub(crate) const S_IFREG: u32 = 0o100000;
+#[cfg(all(feature = "7z", feature = "encode"))]
+pub(crate) const S_IFDIR: u32 = 0o040000;
@@ -0,0 +1,116 @@
+/// Helpers shared by the single-file compressed formats (gz, xz, bz2, zst)
+use crate::error::{ArchiveError, Result};
+use std::io::Read;
+
+#[cfg(feature = "encode")]
+use crate::{File, traits::Compressor};
+#[cfg(feature = "decode")]
+use crate::{
+    types::{DecodeLimits, EntryInfo},
+    utils::LimitTracker,
+};
+#[cfg(feature = "decode")]
+use std::io::{Seek, SeekFrom};
+
+/// Decompress the content of a single-file stream within the limits
+///
+/// The uncompressed size is not declared by these formats, so the limits
+/// are enforced on the data actually unpacked.
+///
+/// # Arguments
+/// * `format` - The format name, for error reporting
+/// * `path` - The name of the file, for error reporting
+/// * `reader` - The decompressing reader
+/// * `limits` - The limits on the unpacked data
+/// * `packed` - The size of the compressed stream, for the ratio limit
+#[cfg(feature = "decode")]
+pub(crate) fn read_single<R: Read>(
+    format: &str,
+    path: &str,
+    reader: R,
+    limits: &DecodeLimits,
+    packed: usize,
+) -> Result<Vec<u8>> {
+    let mut tracker = LimitTracker::new(limits, packed);
+    tracker.read(path, 0, reader, |e| decompress_failed(format, e))
+}
+
+/// Count the uncompressed bytes of a single-file stream without buffering them
+#[cfg(feature = "decode")]
+pub(crate) fn count_single<R: Read>(format: &str, mut reader: R) -> Result<u64> {
+    std::io::copy(&mut reader, &mut std::io::sink()).map_err(|e| decompress_failed(format, e))
+}
+
+/// The file held by a single-file stream, along with its decompressed content
+#[cfg(feature = "decode")]
+pub(crate) type SingleStream<'a> = (EntryInfo, Box<dyn Read + 'a>);
+
+/// Pass the file of a single-file stream to `f`, without buffering its content
+///
+/// The uncompressed size is only known once the whole stream is read, so
+/// the stream is decompressed twice: once to count its size within the
+/// limits, then again while `f` reads the content.
+///
+/// # Arguments
+/// * `format` - The format name, for error reporting
+/// * `reader` - A reader providing the compressed stream
+/// * `limits` - The limits on the unpacked data
+/// * `open` - Wraps the reader with the decompressor of the format, see `Gz::open`
+/// * `f` - Receives the file and its content
+#[cfg(feature = "decode")]
+pub(crate) fn for_each_single<R: Read + Seek>(
+    format: &str,
+    mut reader: R,
+    limits: &DecodeLimits,
+    open: fn(&mut R) -> Result<SingleStream<'_>>,
+    f: &mut dyn FnMut(&EntryInfo, &mut dyn Read) -> Result<()>,
+) -> Result<()> {
+    let start = reader.stream_position()?;
+    let mut tracker = LimitTracker::for_stream(&mut reader, limits)?;
+    let (mut info, decoder) = open(&mut reader)?;
+    info.size = tracker.skip(&info.path, 0, decoder, |e| decompress_failed(format, e))?;
+
+    reader.seek(SeekFrom::Start(start))?;
+    let (_, mut decoder) = open(&mut reader)?;
+    f(&info, &mut decoder)
+}
+
+/// Convert a read failure of a decompressor into a decode error
+#[cfg(feature = "decode")]
+fn decompress_failed(format: &str, e: std::io::Error) -> ArchiveError {
+    ArchiveError::DecodeFailed {
+        format: format.to_string(),
+        reason: format!("Failed to decompress: {}", e),
+    }
+}
+
+/// Take the only file of a single-file format
+///
+/// # Returns
+/// * `Ok(File)` - The file to compress
+/// * `Err(ArchiveError)` - Unless `files` holds exactly one regular file
+#[cfg(feature = "encode")]
+pub(crate) fn single_file(format: &str, mut files: Vec<File>) -> Result<File> {
+    let reason = match files.as_slice() {
+        [file] if !file.is_dir && file.link.is_none() => return Ok(files.remove(0)),
+        [file] => format!("'{}' is not a regular file", file.path),
+        _ => format!("Expected exactly one file, got {}", files.len()),
+    };
+    Err(ArchiveError::EncodeFailed {
+        format: format.to_string(),
+        reason,
+    })
+}
+
+/// Write data through a compressor and finish the stream
+#[cfg(feature = "encode")]
+pub(crate) fn compress_single(
+    mut compressor: Box<dyn Compressor + '_>,
+    mut reader: impl Read,
+) -> Result<()> {
+    std::io::copy(&mut reader, &mut compressor)
+        .map_err(|e| ArchiveError::CompressionError(format!("Failed to compress: {}", e)))?;
+    compressor
+        .finish()
+        .map_err(|e| ArchiveError::CompressionError(format!("Finalization failed: {}", e)))
+}
@@ -3,12 +3,12 @@ use crate::{
     File,
     error::{ArchiveError, Result},
     traits::Decode,
-    utils::clean,
+    types::{DecodeLimits, DecodeOptions},
+    utils::LimitTracker,
 };
 use std::io::{Cursor, Read};
-use tar::Archive;
 
-use super::Tar;
+use super::{Entries, Tar};
 
 /// Common helper function for decoding TAR archives
 ///
@@ -18,63 +18,82 @@ use super::Tar;
 /// # Performance Notes
 /// - Uses streaming processing to minimize memory usage
 /// - Skips PAX headers to avoid unnecessary processing
-/// - Pre-allocates file buffer for each entry
+/// - Pre-allocates file buffer for each entry, bounded by the limits
 ///
 /// # Arguments
 /// * `reader` - A reader providing the TAR data
+/// * `limits` - The limits on the unpacked data
+/// * `packed` - The size of the (compressed) archive, for the ratio limit
 ///
 /// # Returns
 /// * `Ok(Vec<File>)` - The extracted files
-/// * `Err(ArchiveError)` - If decoding fails
-pub(crate) fn decode_tar_archive<R: Read>(reader: R) -> Result<Vec<File>> {
+/// * `Err(ArchiveError)` - If decoding fails or a limit is exceeded
+pub(crate) fn decode_tar_archive<R: Read>(
+    reader: R,
+    limits: &DecodeLimits,
+    packed: usize,
+) -> Result<Vec<File>> {
     // Pre-allocate with estimated capacity (typical archives have 10-100 files)
     let mut files = Vec::with_capacity(32);
-    let mut archive = Archive::new(reader);
+    let mut entries = Entries::new(reader);
+    let mut tracker = LimitTracker::new(limits, packed);
 
-    let entries = archive.entries().map_err(|e| ArchiveError::DecodeFailed {
-        format: "tar".to_string(),
-        reason: format!("Failed to read tar entries: {}", e),
-    })?;
-
-    for entry in entries {
-        let mut file = entry.map_err(|e| ArchiveError::DecodeFailed {
-            format: "tar".to_string(),
-            reason: format!("Failed to read tar entry: {}", e),
-        })?;
-
-        let path = file
-            .header()
-            .path()
-            .map_err(|e| ArchiveError::InvalidArchive(format!("Invalid path in tar: {}", e)))?
-            .to_string_lossy()
-            .to_string();
-
-        // Skip PAX global headers
-        if path == "pax_global_header" {
-            continue;
-        }
-
-        let mut buffer = Vec::new();
-        file.read_to_end(&mut buffer)
-            .map_err(|e| ArchiveError::DecodeFailed {
+    while let Some(mut entry) = entries.next_entry()? {
+        let info = entry.info().clone();
+        let buffer = tracker.read(&info.path, info.size, &mut entry, |e| {
+            ArchiveError::DecodeFailed {
                 format: "tar".to_string(),
                 reason: format!("Failed to read file content: {}", e),
-            })?;
-
-        let mode = file.header().mode().ok();
-        let is_dir = path.ends_with("/");
-        let path = clean(&path);
-        let mtime = file.header().mtime().ok();
+            }
+        })?;
 
-        files.push(File::new(path, buffer, mode, is_dir, mtime));
+        files.push(File {
+            path: info.path,
+            buffer,
+            mode: info.mode,
+            is_dir: info.is_dir,
+            last_modified: info.last_modified,
+            link: info.link,
+        });
     }
 
     Ok(files)
 }
 
+/// Attach the name of a compressed TAR format to a decode error
+///
+/// Limit violations are passed through unchanged, so callers can match on them.
+#[cfg(any(
+    feature = "tar-gz",
+    feature = "tar-xz",
+    feature = "tar-bz",
+    feature = "tar-zstd",
+    feature = "tar-lz4",
+    feature = "tar-lzma",
+    feature = "tar-lz",
+    feature = "tar-z"
+))]
+pub(crate) fn decode_failed(format: &str, e: ArchiveError) -> ArchiveError {
+    match e {
+        ArchiveError::TooManyEntries { .. }
+        | ArchiveError::EntryTooLarge { .. }
+        | ArchiveError::TotalSizeExceeded { .. }
+        | ArchiveError::RatioExceeded { .. } => e,
+        e => ArchiveError::DecodeFailed {
+            format: format.to_string(),
+            reason: e.to_string(),
+        },
+    }
+}
+
 impl Decode for Tar {
     fn decode<T: AsRef<[u8]>>(buffer: T) -> Result<Vec<File>> {
+        Self::decode_with(buffer, &DecodeOptions::default())
+    }
+
+    fn decode_with<T: AsRef<[u8]>>(buffer: T, options: &DecodeOptions) -> Result<Vec<File>> {
+        let packed = buffer.as_ref().len();
         let cur = Cursor::new(buffer);
-        decode_tar_archive(cur)
+        decode_tar_archive(cur, &options.limits, packed)
     }
 }
@@ -2,12 +2,127 @@
 use crate::{
     File,
     error::{ArchiveError, Result},
-    traits::Encode,
-    utils::check_duplicate_files,
+    traits::{Compressor, Encode},
+    types::{EncodeOptions, EntryInfo},
+    utils::{check_duplicate_files, reject_password},
 };
+use std::io::{Read, Write};
 
 use super::Tar;
 
+/// Pass-through "compressor" for plain TAR
+struct Plain<W: Write>(W);
+
+impl<W: Write> Write for Plain<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.0.flush()
+    }
+}
+
+impl<W: Write> Compressor for Plain<W> {
+    fn finish(mut self: Box<Self>) -> std::io::Result<()> {
+        self.0.flush()
+    }
+}
+
+impl Tar {
+    /// Wrap a writer without any compression
+    pub(crate) fn compressor<'a, W: Write + 'a>(
+        writer: W,
+        options: &EncodeOptions,
+    ) -> Result<Box<dyn Compressor + 'a>> {
+        reject_password("tar", options)?;
+        Ok(Box::new(Plain(writer)))
+    }
+}
+
+/// Append a single entry to a TAR builder
+///
+/// The reader must provide exactly `info.size` bytes; directories are
+/// written without content.
+///
+/// # Arguments
+/// * `builder` - The TAR builder to append to
+/// * `info` - The entry metadata
+/// * `reader` - The entry content
+pub(crate) fn append_tar_entry<W: Write, R: Read>(
+    builder: &mut tar::Builder<W>,
+    info: &EntryInfo,
+    reader: R,
+) -> Result<()> {
+    let size = if info.is_dir || info.link.is_some() {
+        0
+    } else {
+        info.size
+    };
+    let default_mode = match (info.is_dir, &info.link) {
+        (_, Some(_)) => 0o777,
+        (true, None) => 0o755,
+        (false, None) => 0o644,
+    };
+
+    let mut header = tar::Header::new_gnu();
+    if info.is_dir {
+        header.set_entry_type(tar::EntryType::Directory);
+    }
+    header.set_size(size);
+    header.set_mode(info.mode.unwrap_or(default_mode));
+    header.set_uid(info.uid.unwrap_or(0).into());
+    header.set_gid(info.gid.unwrap_or(0).into());
+    header.set_mtime(info.last_modified.unwrap_or(0));
+
+    if let Some(link) = &info.link {
+        header.set_entry_type(tar::EntryType::Symlink);
+        return builder
+            .append_link(&mut header, &info.path, link)
+            .map_err(|e| ArchiveError::EncodeFailed {
+                format: "tar".to_string(),
+                reason: format!("Failed to append symlink '{}': {}", info.path, e),
+            });
+    }
+
+    header.set_cksum();
+
+    let mut data = reader.take(size);
+    builder
+        .append_data(&mut header, &info.path, &mut data)
+        .map_err(|e| ArchiveError::EncodeFailed {
+            format: "tar".to_string(),
+            reason: format!("Failed to append file '{}': {}", info.path, e),
+        })?;
+
+    // A short reader would leave the archive with a truncated entry
+    if data.limit() != 0 {
+        return Err(ArchiveError::EncodeFailed {
+            format: "tar".to_string(),
+            reason: format!(
+                "Content of '{}' is shorter than its declared size of {} bytes",
+                info.path, info.size
+            ),
+        });
+    }
+
+    Ok(())
+}
+
+/// Finish a TAR builder and finalize its compressor
+pub(crate) fn finish_tar_archive(builder: tar::Builder<Box<dyn Compressor + '_>>) -> Result<()> {
+    let compressor = builder
+        .into_inner()
+        .map_err(|e| ArchiveError::EncodeFailed {
+            format: "tar".to_string(),
+            reason: format!("Failed to finalize tar archive: {}", e),
+        })?;
+
+    compressor
+        .finish()
+        .map_err(|e| ArchiveError::CompressionError(format!("Finalization failed: {}", e)))
+}
+
 /// Common helper function for encoding TAR archives
 ///
 /// This function handles the core TAR encoding logic that is shared across
@@ -15,53 +130,45 @@ use super::Tar;
 ///
 /// # Performance Notes
 /// - Checks for duplicates before encoding to fail fast
-/// - Uses buffered writing for better I/O performance
-/// - Pre-allocates buffer based on estimated archive size
+/// - Streams directly into the compressor, without an intermediate TAR buffer
 ///
 /// # Arguments
 /// * `files` - The files to include in the archive
+/// * `options` - The encode options, of which only reproducibility applies here
+/// * `writer` - The compressor of the TAR variant to write into
 ///
 /// # Returns
-/// * `Ok(Vec<u8>)` - The encoded TAR data
+/// * `Ok(())` - If all files were written and the compressor was finalized
 /// * `Err(ArchiveError)` - If encoding fails or duplicates are detected
-pub(crate) fn encode_tar_archive(files: Vec<File>) -> Result<Vec<u8>> {
+pub(crate) fn encode_tar_archive(
+    files: Vec<File>,
+    options: &EncodeOptions,
+    writer: Box<dyn Compressor + '_>,
+) -> Result<()> {
     // Check for duplicate files before encoding (fail fast)
     check_duplicate_files(&files)?;
+    let files = options.prepare_files(files);
 
-    // Pre-allocate buffer with estimated size (sum of file sizes + 512 bytes per file for headers)
-    let estimated_size: usize = files.iter().map(|f| f.buffer.len() + 512).sum();
-    let mut buffer: Vec<u8> = Vec::with_capacity(estimated_size);
-    {
-        let mut builder = tar::Builder::new(&mut buffer);
-
-        for file in files {
-            let mut header = tar::Header::new_gnu();
-            header.set_size(file.buffer.len() as u64);
-            header.set_mode(file.mode.unwrap_or(0o644));
-            header.set_uid(0);
-            header.set_gid(0);
-            header.set_mtime(file.last_modified.unwrap_or(0));
-            header.set_cksum();
-
-            builder
-                .append_data(&mut header, &file.path, &file.buffer[..])
-                .map_err(|e| ArchiveError::EncodeFailed {
-                    format: "tar".to_string(),
-                    reason: format!("Failed to append file '{}': {}", file.path, e),
-                })?;
-        }
-
-        builder.finish().map_err(|e| ArchiveError::EncodeFailed {
-            format: "tar".to_string(),
-            reason: format!("Failed to finalize tar archive: {}", e),
-        })?;
+    let mut builder = tar::Builder::new(writer);
+    for file in &files {
+        append_tar_entry(&mut builder, &EntryInfo::from(file), &file.buffer[..])?;
     }
 
-    Ok(buffer)
+    finish_tar_archive(builder)
 }
 
 impl Encode for Tar {
     fn encode(files: Vec<File>) -> Result<Vec<u8>> {
-        encode_tar_archive(files)
+        Self::encode_with(files, &EncodeOptions::default())
+    }
+
+    fn encode_with(files: Vec<File>, options: &EncodeOptions) -> Result<Vec<u8>> {
+        // Pre-allocate buffer with estimated size (sum of file sizes + 512 bytes per file for headers)
+        let estimated_size: usize = files.iter().map(|f| f.buffer.len() + 512).sum();
+        let mut buffer: Vec<u8> = Vec::with_capacity(estimated_size);
+
+        encode_tar_archive(files, options, Self::compressor(&mut buffer, options)?)?;
+
+        Ok(buffer)
     }
 }
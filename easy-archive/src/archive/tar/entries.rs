@@ -0,0 +1,558 @@
+/// Streaming TAR entry reader
+use crate::{
+    File,
+    error::{ArchiveError, Result},
+    types::EntryInfo,
+    utils::clean,
+};
+use std::collections::VecDeque;
+use std::io::{self, Read};
+use tar::{GnuExtSparseHeader, GnuSparseHeader, Header};
+
+use super::header_checksum;
+
+const BLOCK_SIZE: u64 = super::BLOCK_SIZE as u64;
+
+/// Maximum size of GNU long name and PAX extension headers, which are buffered
+const MAX_EXTENSION_SIZE: u64 = 1 << 20;
+
+/// The layout of a GNU sparse entry, whose holes are not stored in the archive
+struct Sparse {
+    /// The stored data segments as `(offset, length)`, in increasing order
+    segments: VecDeque<(u64, u64)>,
+    /// The position in the expanded content
+    position: u64,
+    /// The size of the expanded content
+    size: u64,
+}
+
+impl Sparse {
+    /// Check a sparse map against the expanded size and the stored data
+    fn new(segments: Vec<(u64, u64)>, size: u64, stored: u64) -> Result<Self> {
+        let mut end = 0;
+        let mut total = 0u64;
+        for &(offset, length) in &segments {
+            let valid = offset >= end && offset.checked_add(length).is_some_and(|e| e <= size);
+            if !valid {
+                return Err(ArchiveError::InvalidArchive(
+                    "Tar sparse map is out of order or out of bounds".to_string(),
+                ));
+            }
+            end = offset + length;
+            total += length;
+        }
+        if total != stored {
+            return Err(ArchiveError::InvalidArchive(format!(
+                "Tar sparse map covers {} bytes, but the entry stores {}",
+                total, stored
+            )));
+        }
+
+        Ok(Sparse {
+            segments: segments.into(),
+            position: 0,
+            size,
+        })
+    }
+
+    /// Find the part of the expanded content at the current position
+    ///
+    /// # Returns
+    /// Whether the part is a hole, and the number of bytes left in it
+    fn current(&mut self) -> (bool, u64) {
+        while let Some(&(offset, length)) = self.segments.front()
+            && self.position >= offset + length
+        {
+            self.segments.pop_front();
+        }
+        match self.segments.front() {
+            Some(&(offset, length)) if self.position >= offset => {
+                (false, offset + length - self.position)
+            }
+            Some(&(offset, _)) => (true, offset - self.position),
+            None => (true, self.size - self.position),
+        }
+    }
+}
+
+/// Collect the segments of an old GNU sparse map, up to its first empty slot
+fn push_segments(segments: &mut Vec<(u64, u64)>, slots: &[GnuSparseHeader]) -> Result<()> {
+    for slot in slots.iter().take_while(|slot| !slot.is_empty()) {
+        let offset = slot.offset().map_err(decode_error)?;
+        let length = slot.length().map_err(decode_error)?;
+        segments.push((offset, length));
+    }
+    Ok(())
+}
+
+/// Bounded reader over the content of the current entry
+///
+/// The holes of sparse entries are filled with zeros while reading.
+struct Body<'a> {
+    reader: Box<dyn Read + 'a>,
+    remaining: u64,
+    padding: u64,
+    sparse: Option<Sparse>,
+}
+
+impl Body<'_> {
+    /// Discard the unread content of the current entry and its block padding
+    ///
+    /// The holes of a sparse entry are not expanded, only its stored data is read.
+    fn skip(&mut self) -> io::Result<()> {
+        self.sparse = None;
+        io::copy(self, &mut io::sink())?;
+        io::copy(&mut (&mut self.reader).take(self.padding), &mut io::sink())?;
+        self.padding = 0;
+        Ok(())
+    }
+
+    /// Read the whole content of the current entry
+    fn read_data(&mut self, size: u64) -> io::Result<Vec<u8>> {
+        self.remaining = size;
+        self.padding = padding(size);
+        let mut data = Vec::new();
+        self.read_to_end(&mut data)?;
+        self.skip()?;
+        Ok(data)
+    }
+
+    /// Read the data stored in the archive for the current entry
+    fn read_stored(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.remaining == 0 || buf.is_empty() {
+            return Ok(0);
+        }
+
+        let max = (buf.len() as u64).min(self.remaining) as usize;
+        let n = self.reader.read(&mut buf[..max])?;
+        if n == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "unexpected end of tar entry",
+            ));
+        }
+
+        self.remaining -= n as u64;
+        Ok(n)
+    }
+}
+
+impl Read for Body<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let Some((hole, left)) = self.sparse.as_mut().map(Sparse::current) else {
+            return self.read_stored(buf);
+        };
+
+        let max = (buf.len() as u64).min(left) as usize;
+        let n = if hole {
+            buf[..max].fill(0);
+            max
+        } else {
+            self.read_stored(&mut buf[..max])?
+        };
+        if let Some(sparse) = &mut self.sparse {
+            sparse.position += n as u64;
+        }
+        Ok(n)
+    }
+}
+
+/// Extended attributes collected from PAX headers
+#[derive(Default)]
+struct PaxExtensions {
+    path: Option<String>,
+    linkpath: Option<String>,
+    size: Option<u64>,
+    mtime: Option<u64>,
+    /// The expanded size of a GNU sparse entry, which marks it as sparse
+    sparse_size: Option<u64>,
+    /// The real name of a GNU sparse entry, from format 0.1 on
+    sparse_name: Option<String>,
+    /// The major version of the GNU sparse format, 1 storing the map with the data
+    sparse_major: Option<u64>,
+    /// The offsets and lengths of the sparse map of formats 0.0 and 0.1
+    sparse_map: Vec<u64>,
+}
+
+impl PaxExtensions {
+    /// Parse PAX records of the form `"<len> <key>=<value>\n"`
+    fn parse(data: &[u8]) -> Self {
+        let mut pax = PaxExtensions::default();
+        let mut rest = data;
+
+        while !rest.is_empty() {
+            let Some(space) = rest.iter().position(|b| *b == b' ') else {
+                break;
+            };
+            let Some(len) = std::str::from_utf8(&rest[..space])
+                .ok()
+                .and_then(|s| s.parse::<usize>().ok())
+            else {
+                break;
+            };
+            if len <= space || len > rest.len() {
+                break;
+            }
+
+            let record = &rest[space + 1..len];
+            let record = record.strip_suffix(b"\n").unwrap_or(record);
+            rest = &rest[len..];
+
+            let Some(eq) = record.iter().position(|b| *b == b'=') else {
+                continue;
+            };
+            let value = String::from_utf8_lossy(&record[eq + 1..]);
+            match &record[..eq] {
+                b"path" => pax.path = Some(value.to_string()),
+                b"linkpath" => pax.linkpath = Some(value.to_string()),
+                b"size" => pax.size = value.parse().ok(),
+                // mtime may carry a fractional part, e.g. "1700000000.123"
+                b"mtime" => pax.mtime = value.split('.').next().and_then(|secs| secs.parse().ok()),
+                b"GNU.sparse.size" | b"GNU.sparse.realsize" => pax.sparse_size = value.parse().ok(),
+                b"GNU.sparse.name" => pax.sparse_name = Some(value.to_string()),
+                b"GNU.sparse.major" => pax.sparse_major = value.parse().ok(),
+                // Format 0.0 repeats these records, once per segment
+                b"GNU.sparse.offset" | b"GNU.sparse.numbytes" => {
+                    pax.sparse_map.extend(value.parse::<u64>().ok())
+                }
+                b"GNU.sparse.map" => pax
+                    .sparse_map
+                    .extend(value.split(',').filter_map(|n| n.parse::<u64>().ok())),
+                _ => {}
+            }
+        }
+
+        pax
+    }
+}
+
+/// Round a content size up to the next block boundary
+#[inline]
+fn padding(size: u64) -> u64 {
+    (BLOCK_SIZE - size % BLOCK_SIZE) % BLOCK_SIZE
+}
+
+/// Convert an I/O error into a tar decode error
+fn decode_error(e: io::Error) -> ArchiveError {
+    ArchiveError::DecodeFailed {
+        format: "tar".to_string(),
+        reason: e.to_string(),
+    }
+}
+
+/// Streaming iterator over the entries of a TAR archive
+///
+/// Entries are yielded one at a time, together with a reader for their
+/// content, so memory usage stays flat regardless of the archive size.
+/// Content that is not read before requesting the next entry is skipped.
+/// GNU sparse entries are expanded, with their holes read as zeros.
+///
+/// # Example
+/// ```no_run
+/// use easy_archive::Fmt;
+/// use std::io::Read;
+///
+/// let reader = std::fs::File::open("archive.tar.gz")?;
+/// let mut entries = Fmt::TarGz.entries(reader)?;
+/// while let Some(mut entry) = entries.next_entry()? {
+///     if entry.info().path == "README.md" {
+///         let mut content = String::new();
+///         entry.read_to_string(&mut content)?;
+///         println!("{}", content);
+///         break;
+///     }
+/// }
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub struct Entries<'a> {
+    body: Body<'a>,
+    done: bool,
+}
+
+impl<'a> Entries<'a> {
+    /// Create an entry iterator over uncompressed TAR data
+    pub fn new<R: Read + 'a>(reader: R) -> Self {
+        Entries {
+            body: Body {
+                reader: Box::new(reader),
+                remaining: 0,
+                padding: 0,
+                sparse: None,
+            },
+            done: false,
+        }
+    }
+
+    /// Read the next header block, returning `None` at the end of the archive
+    fn read_header(&mut self) -> Result<Option<Header>> {
+        let mut header = Header::new_old();
+        let block = header.as_mut_bytes();
+
+        let mut filled = 0;
+        while filled < block.len() {
+            match self.body.reader.read(&mut block[filled..]) {
+                Ok(0) => break,
+                Ok(n) => filled += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(decode_error(e)),
+            }
+        }
+
+        // A missing or all-zero block marks the end of the archive
+        if filled == 0 || block.iter().all(|b| *b == 0) {
+            return Ok(None);
+        }
+        if filled < block.len() {
+            return Err(ArchiveError::InvalidArchive(
+                "Truncated tar header".to_string(),
+            ));
+        }
+
+        let sum = header_checksum(block);
+        if header.cksum().map_err(decode_error)? != sum {
+            return Err(ArchiveError::InvalidArchive(
+                "Tar header checksum mismatch".to_string(),
+            ));
+        }
+
+        Ok(Some(header))
+    }
+
+    /// Read the sparse map of an old GNU sparse entry, from its header and
+    /// the extension blocks following it
+    ///
+    /// # Returns
+    /// The data segments and the expanded size of the entry
+    fn read_gnu_sparse_map(&mut self, header: &Header) -> Result<(Vec<(u64, u64)>, u64)> {
+        let gnu = header.as_gnu().ok_or_else(|| {
+            ArchiveError::InvalidArchive("Tar sparse entry without a GNU header".to_string())
+        })?;
+
+        let mut segments = Vec::new();
+        push_segments(&mut segments, &gnu.sparse)?;
+        let mut extended = gnu.is_extended();
+        while extended {
+            let mut block = GnuExtSparseHeader::new();
+            self.body
+                .reader
+                .read_exact(block.as_mut_bytes())
+                .map_err(decode_error)?;
+            push_segments(&mut segments, block.sparse())?;
+            extended = block.is_extended();
+        }
+
+        Ok((segments, gnu.real_size().map_err(decode_error)?))
+    }
+
+    /// Read the sparse map stored ahead of the data of a PAX 1.0 sparse entry
+    ///
+    /// The map holds the number of segments, then the offset and length of
+    /// each, as decimal numbers ending with a newline, padded to a block.
+    fn read_sparse_map(&mut self, size: u64) -> Result<Vec<(u64, u64)>> {
+        let invalid = || ArchiveError::InvalidArchive("Invalid tar sparse map".to_string());
+
+        let mut numbers = Vec::new();
+        let mut count = None;
+        let mut line = Vec::new();
+        let mut block = [0; BLOCK_SIZE as usize];
+        loop {
+            self.body.read_exact(&mut block).map_err(decode_error)?;
+            for byte in block {
+                if byte != b'\n' {
+                    line.push(byte);
+                    if line.len() > 20 {
+                        return Err(invalid());
+                    }
+                    continue;
+                }
+
+                let number = std::str::from_utf8(&line)
+                    .ok()
+                    .and_then(|s| s.parse::<u64>().ok())
+                    .ok_or_else(invalid)?;
+                line.clear();
+                match count {
+                    // Every segment takes at least four bytes of the map
+                    None if number > size / 4 => return Err(invalid()),
+                    None => count = Some(number as usize),
+                    Some(_) => numbers.push(number),
+                }
+                // The rest of the block is padding
+                if count.is_some_and(|count| numbers.len() == count * 2) {
+                    return Ok(numbers.chunks(2).map(|pair| (pair[0], pair[1])).collect());
+                }
+            }
+        }
+    }
+
+    /// Advance to the next entry of the archive
+    ///
+    /// # Returns
+    /// * `Ok(Some(Entry))` - The next entry, readable for its content
+    /// * `Ok(None)` - If the end of the archive is reached
+    /// * `Err(ArchiveError)` - If the archive is malformed or reading fails
+    pub fn next_entry(&mut self) -> Result<Option<Entry<'_>>> {
+        if self.done {
+            return Ok(None);
+        }
+
+        // Skip whatever the caller left unread from the previous entry
+        self.body.skip().map_err(decode_error)?;
+
+        let mut long_name = None;
+        let mut long_link = None;
+        let mut pax = PaxExtensions::default();
+
+        loop {
+            let Some(header) = self.read_header()? else {
+                self.done = true;
+                return Ok(None);
+            };
+
+            let entry_type = header.entry_type();
+            let size = header.entry_size().map_err(decode_error)?;
+
+            let is_extension = entry_type.is_gnu_longname()
+                || entry_type.is_gnu_longlink()
+                || entry_type.is_pax_local_extensions()
+                || entry_type.is_pax_global_extensions();
+            if is_extension && size > MAX_EXTENSION_SIZE {
+                return Err(ArchiveError::InvalidArchive(format!(
+                    "Tar extension header of {} bytes is too large",
+                    size
+                )));
+            }
+
+            if entry_type.is_gnu_longname() || entry_type.is_gnu_longlink() {
+                let mut name = self.body.read_data(size).map_err(decode_error)?;
+                while name.last() == Some(&0) {
+                    name.pop();
+                }
+                let name = Some(String::from_utf8_lossy(&name).to_string());
+                if entry_type.is_gnu_longname() {
+                    long_name = name;
+                } else {
+                    long_link = name;
+                }
+                continue;
+            }
+
+            if entry_type.is_pax_local_extensions() {
+                let data = self.body.read_data(size).map_err(decode_error)?;
+                pax = PaxExtensions::parse(&data);
+                continue;
+            }
+
+            if entry_type.is_pax_global_extensions() {
+                self.body.read_data(size).map_err(decode_error)?;
+                continue;
+            }
+
+            let path = pax
+                .sparse_name
+                .or(long_name)
+                .or(pax.path)
+                .unwrap_or_else(|| String::from_utf8_lossy(&header.path_bytes()).to_string());
+            let size = pax.size.unwrap_or(size);
+            let is_dir = entry_type.is_dir() || path.ends_with('/');
+            let link = if entry_type.is_symlink() {
+                long_link.or(pax.linkpath).or_else(|| {
+                    header
+                        .link_name_bytes()
+                        .map(|name| String::from_utf8_lossy(&name).to_string())
+                })
+            } else {
+                None
+            };
+
+            let mut info = EntryInfo {
+                path: clean(&path),
+                size,
+                mode: header.mode().ok(),
+                is_dir,
+                last_modified: pax.mtime.or_else(|| header.mtime().ok()),
+                link,
+                uid: header.uid().ok().map(|uid| uid as u32),
+                gid: header.gid().ok().map(|gid| gid as u32),
+            };
+
+            // Sparse entries only store their data segments, along with a map
+            let mut sparse = None;
+            if entry_type.is_gnu_sparse() {
+                sparse = Some(self.read_gnu_sparse_map(&header)?);
+            }
+            self.body.remaining = size;
+            self.body.padding = padding(size);
+            if let Some(expanded) = pax.sparse_size {
+                let segments = if pax.sparse_major == Some(1) {
+                    self.read_sparse_map(size)?
+                } else {
+                    let map = pax.sparse_map.chunks_exact(2);
+                    map.map(|pair| (pair[0], pair[1])).collect()
+                };
+                sparse = Some((segments, expanded));
+            }
+            if let Some((segments, expanded)) = sparse {
+                let stored = self.body.remaining;
+                self.body.sparse = Some(Sparse::new(segments, expanded, stored)?);
+                info.size = expanded;
+            }
+
+            return Ok(Some(Entry {
+                info,
+                body: &mut self.body,
+            }));
+        }
+    }
+
+    /// Read the rest of the stream after the end of the archive
+    ///
+    /// Compressed streams check their trailing checksum once fully read.
+    pub(crate) fn finish(&mut self) -> Result<()> {
+        self.body.skip().map_err(decode_error)?;
+        io::copy(&mut self.body.reader, &mut io::sink()).map_err(decode_error)?;
+        Ok(())
+    }
+}
+
+/// A single entry yielded by [`Entries`]
+///
+/// The entry implements [`Read`] for its content.
+pub struct Entry<'a> {
+    info: EntryInfo,
+    body: &'a mut dyn Read,
+}
+
+impl Entry<'_> {
+    /// Get the metadata of this entry
+    pub fn info(&self) -> &EntryInfo {
+        &self.info
+    }
+
+    /// Read the remaining content and convert this entry into a [`File`]
+    pub fn into_file(self) -> Result<File> {
+        let mut buffer = Vec::with_capacity(self.info.size.min(1 << 20) as usize);
+        self.body
+            .read_to_end(&mut buffer)
+            .map_err(|e| ArchiveError::DecodeFailed {
+                format: "tar".to_string(),
+                reason: format!("Failed to read file content: {}", e),
+            })?;
+
+        let info = self.info;
+        Ok(File {
+            path: info.path,
+            buffer,
+            mode: info.mode,
+            is_dir: info.is_dir,
+            last_modified: info.last_modified,
+            link: info.link,
+        })
+    }
+}
+
+impl Read for Entry<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.body.read(buf)
+    }
+}
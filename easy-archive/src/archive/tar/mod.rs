@@ -7,3 +7,43 @@ pub(crate) mod decode;
 
 #[cfg(all(feature = "tar", feature = "encode"))]
 pub(crate) mod encode;
+
+#[cfg(all(feature = "tar", feature = "decode"))]
+mod entries;
+
+#[cfg(all(feature = "tar", feature = "decode"))]
+pub use entries::{Entries, Entry};
+
+/// Size of a TAR header block in bytes
+#[cfg(feature = "tar")]
+pub(crate) const BLOCK_SIZE: usize = 512;
+
+/// Compute the checksum of a TAR header block
+///
+/// The checksum field itself is counted as eight spaces.
+#[cfg(feature = "tar")]
+pub(crate) fn header_checksum(block: &[u8]) -> u32 {
+    block[..148]
+        .iter()
+        .chain(&block[156..BLOCK_SIZE])
+        .fold(8 * 32, |sum, b| sum + *b as u32)
+}
+
+/// Check whether a block looks like a TAR header
+///
+/// Accepts blocks carrying the `ustar` magic as well as pre-POSIX headers
+/// whose checksum matches.
+#[cfg(feature = "tar")]
+pub(crate) fn is_tar_header(block: &[u8]) -> bool {
+    if block.len() < BLOCK_SIZE || block[..BLOCK_SIZE].iter().all(|b| *b == 0) {
+        return false;
+    }
+    if &block[257..262] == b"ustar" {
+        return true;
+    }
+
+    let header = ::tar::Header::from_byte_slice(&block[..BLOCK_SIZE]);
+    header
+        .cksum()
+        .is_ok_and(|sum| sum == header_checksum(block))
+}
@@ -1,31 +1,35 @@
 /// TAR.BZ2 decoding implementation
 use crate::{
     File,
-    archive::tar::decode::decode_tar_archive,
-    error::{ArchiveError, Result},
+    archive::{
+        Bz,
+        tar::decode::{decode_failed, decode_tar_archive},
+    },
+    error::Result,
     traits::Decode,
+    types::DecodeOptions,
 };
-use bzip2_rs::DecoderReader;
-use std::io::{BufReader, Cursor, Read};
+use std::io::Read;
 
 use super::TarBz;
 
+impl TarBz {
+    /// Wrap a reader with a streaming bzip2 decompressor
+    pub(crate) fn decompress<'a, R: Read + 'a>(reader: R) -> Result<Box<dyn Read + 'a>> {
+        Bz::decompress(reader)
+    }
+}
+
 impl Decode for TarBz {
     fn decode<T: AsRef<[u8]>>(buffer: T) -> Result<Vec<File>> {
-        let cur = Cursor::new(buffer);
-        let reader = BufReader::new(DecoderReader::new(cur));
-        let decompressed: Result<Vec<u8>> = reader
-            .bytes()
-            .collect::<std::io::Result<Vec<u8>>>()
-            .map_err(|e| {
-                ArchiveError::DecompressionError(format!("BZ2 decompression failed: {}", e))
-            });
+        Self::decode_with(buffer, &DecodeOptions::default())
+    }
 
-        let decompressed = decompressed?;
+    fn decode_with<T: AsRef<[u8]>>(buffer: T, options: &DecodeOptions) -> Result<Vec<File>> {
+        let buffer = buffer.as_ref();
+        let decoder = Self::decompress(buffer)?;
 
-        decode_tar_archive(Cursor::new(decompressed)).map_err(|e| ArchiveError::DecodeFailed {
-            format: "tar.bz2".to_string(),
-            reason: e.to_string(),
-        })
+        decode_tar_archive(decoder, &options.limits, buffer.len())
+            .map_err(|e| decode_failed("tar.bz2", e))
     }
 }
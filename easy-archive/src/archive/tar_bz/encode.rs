@@ -1,16 +1,37 @@
 /// TAR.BZ2 encoding implementation
 use crate::{
     File,
-    error::{ArchiveError, Result},
-    traits::Encode,
+    archive::{Bz, tar::encode::encode_tar_archive},
+    error::Result,
+    traits::{Compressor, Encode},
+    types::EncodeOptions,
+    utils::reject_password,
 };
+use std::io::Write;
 
 use super::TarBz;
 
+impl TarBz {
+    /// Wrap a writer with a bzip2 compressor
+    pub(crate) fn compressor<'a, W: Write + 'a>(
+        writer: W,
+        options: &EncodeOptions,
+    ) -> Result<Box<dyn Compressor + 'a>> {
+        reject_password("tar.bz2", options)?;
+        Bz::compressor(writer, options)
+    }
+}
+
 impl Encode for TarBz {
-    fn encode(_files: Vec<File>) -> Result<Vec<u8>> {
-        Err(ArchiveError::UnsupportedFormat(
-            "TAR.BZ2 encoding is not supported (bzip2-rs lacks encoder)".to_string(),
-        ))
+    fn encode(files: Vec<File>) -> Result<Vec<u8>> {
+        Self::encode_with(files, &EncodeOptions::default())
+    }
+
+    fn encode_with(files: Vec<File>, options: &EncodeOptions) -> Result<Vec<u8>> {
+        let mut compressed = Vec::new();
+
+        encode_tar_archive(files, options, Self::compressor(&mut compressed, options)?)?;
+
+        Ok(compressed)
     }
 }
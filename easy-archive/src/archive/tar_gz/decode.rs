@@ -1,35 +1,34 @@
 /// TAR.GZ decoding implementation
 use crate::{
     File,
-    archive::tar::decode::decode_tar_archive,
-    error::{ArchiveError, Result},
+    archive::tar::decode::{decode_failed, decode_tar_archive},
+    error::Result,
     traits::Decode,
+    types::DecodeOptions,
 };
 use flate2::read::GzDecoder;
 use std::io::{BufReader, Read};
 
 use super::TarGz;
 
+impl TarGz {
+    /// Wrap a reader with a streaming gzip decompressor
+    pub(crate) fn decompress<'a, R: Read + 'a>(reader: R) -> Result<Box<dyn Read + 'a>> {
+        // Use BufReader for better I/O performance
+        Ok(Box::new(BufReader::new(GzDecoder::new(reader))))
+    }
+}
+
 impl Decode for TarGz {
     fn decode<T: AsRef<[u8]>>(buffer: T) -> Result<Vec<File>> {
-        let buffer = buffer.as_ref();
-        let decoder = GzDecoder::new(buffer);
-
-        // Pre-allocate decompression buffer (gzip typically achieves 2-3x compression)
-        let estimated_size = buffer.len() * 3;
-        let mut decompressed = Vec::with_capacity(estimated_size);
+        Self::decode_with(buffer, &DecodeOptions::default())
+    }
 
-        // Use BufReader for better I/O performance
-        let mut buf_reader = BufReader::new(decoder);
-        buf_reader.read_to_end(&mut decompressed).map_err(|e| {
-            ArchiveError::DecompressionError(format!("GZ decompression failed: {}", e))
-        })?;
+    fn decode_with<T: AsRef<[u8]>>(buffer: T, options: &DecodeOptions) -> Result<Vec<File>> {
+        let buffer = buffer.as_ref();
+        let decoder = Self::decompress(buffer)?;
 
-        decode_tar_archive(std::io::Cursor::new(decompressed)).map_err(|e| {
-            ArchiveError::DecodeFailed {
-                format: "tar.gz".to_string(),
-                reason: e.to_string(),
-            }
-        })
+        decode_tar_archive(decoder, &options.limits, buffer.len())
+            .map_err(|e| decode_failed("tar.gz", e))
     }
 }
@@ -1,29 +1,38 @@
 /// TAR.GZ encoding implementation
 use crate::{
-    File, Fmt,
-    error::{ArchiveError, Result},
-    traits::Encode,
+    File,
+    archive::{Gz, tar::encode::encode_tar_archive},
+    error::Result,
+    traits::{Compressor, Encode},
+    types::EncodeOptions,
+    utils::reject_password,
 };
-use flate2::{Compression, bufread::GzEncoder};
-use std::io::{Cursor, Read};
+use std::io::Write;
 
 use super::TarGz;
 
+impl TarGz {
+    /// Wrap a writer with a gzip compressor
+    pub(crate) fn compressor<'a, W: Write + 'a>(
+        writer: W,
+        options: &EncodeOptions,
+    ) -> Result<Box<dyn Compressor + 'a>> {
+        reject_password("tar.gz", options)?;
+        Gz::compressor(writer, options, None)
+    }
+}
+
 impl Encode for TarGz {
     fn encode(files: Vec<File>) -> Result<Vec<u8>> {
-        let tar = Fmt::Tar.encode(files)?;
+        Self::encode_with(files, &EncodeOptions::default())
+    }
 
+    fn encode_with(files: Vec<File>, options: &EncodeOptions) -> Result<Vec<u8>> {
         // Pre-allocate compressed buffer (estimate 30-40% of original size)
-        let estimated_size = tar.len() / 3;
-        let mut cursor = Cursor::new(tar);
-
-        // Use default compression level (6) for balanced speed/compression
-        let mut encoder = GzEncoder::new(&mut cursor, Compression::default());
+        let estimated_size = files.iter().map(|f| f.buffer.len()).sum::<usize>() / 3;
         let mut compressed = Vec::with_capacity(estimated_size);
 
-        encoder
-            .read_to_end(&mut compressed)
-            .map_err(|e| ArchiveError::CompressionError(format!("GZ compression failed: {}", e)))?;
+        encode_tar_archive(files, options, Self::compressor(&mut compressed, options)?)?;
 
         Ok(compressed)
     }
@@ -0,0 +1,42 @@
+/// TAR.LZ decoding implementation
+use crate::{
+    File,
+    archive::tar::decode::{decode_failed, decode_tar_archive},
+    error::{ArchiveError, Result},
+    traits::Decode,
+    types::DecodeOptions,
+};
+use liblzma::{
+    read::XzDecoder,
+    stream::{CONCATENATED, Stream},
+};
+use std::io::Read;
+
+use super::TarLz;
+
+impl TarLz {
+    /// Wrap a reader with a streaming lzip decompressor
+    ///
+    /// Multi-member files, as written by `plzip` or by concatenating `.lz`
+    /// files, are decompressed as one stream, like `lzip -d` does.
+    pub(crate) fn decompress<'a, R: Read + 'a>(reader: R) -> Result<Box<dyn Read + 'a>> {
+        let stream = Stream::new_lzip_decoder(u64::MAX, CONCATENATED).map_err(|e| {
+            ArchiveError::DecompressionError(format!("Failed to create lzip decoder: {}", e))
+        })?;
+        Ok(Box::new(XzDecoder::new_stream(reader, stream)))
+    }
+}
+
+impl Decode for TarLz {
+    fn decode<T: AsRef<[u8]>>(buffer: T) -> Result<Vec<File>> {
+        Self::decode_with(buffer, &DecodeOptions::default())
+    }
+
+    fn decode_with<T: AsRef<[u8]>>(buffer: T, options: &DecodeOptions) -> Result<Vec<File>> {
+        let buffer = buffer.as_ref();
+        let decoder = Self::decompress(buffer)?;
+
+        decode_tar_archive(decoder, &options.limits, buffer.len())
+            .map_err(|e| decode_failed("tar.lz", e))
+    }
+}
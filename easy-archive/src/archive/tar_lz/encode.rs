@@ -0,0 +1,129 @@
+/// TAR.LZ encoding implementation
+use crate::{
+    File,
+    archive::tar::encode::encode_tar_archive,
+    error::{ArchiveError, Result},
+    traits::{Compressor, Encode},
+    types::EncodeOptions,
+    utils::reject_password,
+};
+use liblzma::{
+    stream::{Filters, LzmaOptions, Stream},
+    write::XzEncoder,
+};
+use std::io::Write;
+
+use super::TarLz;
+
+/// Dictionary sizes of the xz presets 0-9, all powers of two
+const DICT_SIZES: [u32; 10] = [
+    1 << 18,
+    1 << 20,
+    1 << 21,
+    1 << 22,
+    1 << 22,
+    1 << 23,
+    1 << 23,
+    1 << 24,
+    1 << 25,
+    1 << 26,
+];
+
+/// Size of the lzip member header: magic, version and coded dictionary size
+const HEADER_SIZE: u64 = 6;
+
+/// Size of the lzip member trailer: CRC32, data size and member size
+const TRAILER_SIZE: u64 = 20;
+
+/// Writes a single lzip member around a raw LZMA stream
+///
+/// liblzma can only decode lzip, so the header and trailer are written here
+/// and the payload comes from its raw LZMA1 encoder, which ends the data with
+/// the end-of-stream marker lzip requires.
+struct LzipEncoder<W: Write> {
+    encoder: XzEncoder<W>,
+    crc: crc32fast::Hasher,
+    size: u64,
+}
+
+impl<W: Write> Write for LzipEncoder<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let n = self.encoder.write(buf)?;
+        self.crc.update(&buf[..n]);
+        self.size += n as u64;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.encoder.flush()
+    }
+}
+
+impl<W: Write> Compressor for LzipEncoder<W> {
+    fn finish(self: Box<Self>) -> std::io::Result<()> {
+        let LzipEncoder {
+            mut encoder,
+            crc,
+            size,
+        } = *self;
+        encoder.try_finish()?;
+        let member_size = HEADER_SIZE + encoder.total_out() + TRAILER_SIZE;
+        let mut writer = encoder.finish()?;
+
+        writer.write_all(&crc.finalize().to_le_bytes())?;
+        writer.write_all(&size.to_le_bytes())?;
+        writer.write_all(&member_size.to_le_bytes())?;
+        writer.flush()
+    }
+}
+
+impl TarLz {
+    /// Wrap a writer with an lzip compressor
+    ///
+    /// The output is a single lzip member; the thread option is ignored.
+    pub(crate) fn compressor<'a, W: Write + 'a>(
+        mut writer: W,
+        options: &EncodeOptions,
+    ) -> Result<Box<dyn Compressor + 'a>> {
+        reject_password("tar.lz", options)?;
+
+        let level = options.level.unwrap_or(6).min(9);
+        let dict_size = DICT_SIZES[level as usize];
+        let stream = LzmaOptions::new_preset(level)
+            .and_then(|mut lzma| {
+                // lzip only supports the default literal and position bits
+                lzma.dict_size(dict_size)
+                    .literal_context_bits(3)
+                    .literal_position_bits(0)
+                    .position_bits(2);
+                Stream::new_raw_encoder(Filters::new().lzma1(&lzma))
+            })
+            .map_err(|e| {
+                ArchiveError::CompressionError(format!("Failed to create lzip encoder: {}", e))
+            })?;
+
+        // A power-of-two dictionary size is coded as its base-2 logarithm
+        writer.write_all(b"LZIP\x01")?;
+        writer.write_all(&[dict_size.trailing_zeros() as u8])?;
+
+        Ok(Box::new(LzipEncoder {
+            encoder: XzEncoder::new_stream(writer, stream),
+            crc: crc32fast::Hasher::new(),
+            size: 0,
+        }))
+    }
+}
+
+impl Encode for TarLz {
+    fn encode(files: Vec<File>) -> Result<Vec<u8>> {
+        Self::encode_with(files, &EncodeOptions::default())
+    }
+
+    fn encode_with(files: Vec<File>, options: &EncodeOptions) -> Result<Vec<u8>> {
+        let mut compressed = Vec::new();
+
+        encode_tar_archive(files, options, Self::compressor(&mut compressed, options)?)?;
+
+        Ok(compressed)
+    }
+}
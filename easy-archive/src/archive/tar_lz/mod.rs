@@ -0,0 +1,9 @@
+/// Lzip-compressed TAR archive format
+#[cfg(feature = "tar-lz")]
+pub struct TarLz;
+
+#[cfg(all(feature = "tar-lz", feature = "decode"))]
+mod decode;
+
+#[cfg(all(feature = "tar-lz", feature = "encode"))]
+mod encode;
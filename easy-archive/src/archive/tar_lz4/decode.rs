@@ -0,0 +1,33 @@
+/// TAR.LZ4 decoding implementation
+use crate::{
+    File,
+    archive::tar::decode::{decode_failed, decode_tar_archive},
+    error::Result,
+    traits::Decode,
+    types::DecodeOptions,
+};
+use lz4_flex::frame::FrameDecoder;
+use std::io::Read;
+
+use super::TarLz4;
+
+impl TarLz4 {
+    /// Wrap a reader with a streaming LZ4 frame decompressor
+    pub(crate) fn decompress<'a, R: Read + 'a>(reader: R) -> Result<Box<dyn Read + 'a>> {
+        Ok(Box::new(FrameDecoder::new(reader)))
+    }
+}
+
+impl Decode for TarLz4 {
+    fn decode<T: AsRef<[u8]>>(buffer: T) -> Result<Vec<File>> {
+        Self::decode_with(buffer, &DecodeOptions::default())
+    }
+
+    fn decode_with<T: AsRef<[u8]>>(buffer: T, options: &DecodeOptions) -> Result<Vec<File>> {
+        let buffer = buffer.as_ref();
+        let decoder = Self::decompress(buffer)?;
+
+        decode_tar_archive(decoder, &options.limits, buffer.len())
+            .map_err(|e| decode_failed("tar.lz4", e))
+    }
+}
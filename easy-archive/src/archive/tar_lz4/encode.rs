@@ -0,0 +1,51 @@
+/// TAR.LZ4 encoding implementation
+use crate::{
+    File,
+    archive::tar::encode::encode_tar_archive,
+    error::Result,
+    traits::{Compressor, Encode},
+    types::EncodeOptions,
+    utils::reject_password,
+};
+use lz4_flex::frame::FrameEncoder;
+use std::io::Write;
+
+use super::TarLz4;
+
+impl<W: Write> Compressor for FrameEncoder<W> {
+    fn finish(self: Box<Self>) -> std::io::Result<()> {
+        FrameEncoder::finish(*self)
+            .map(|_| ())
+            .map_err(std::io::Error::from)
+    }
+}
+
+impl TarLz4 {
+    /// Wrap a writer with an LZ4 frame compressor
+    ///
+    /// LZ4 trades ratio for speed and has no compression levels, so the
+    /// level and thread options are ignored.
+    pub(crate) fn compressor<'a, W: Write + 'a>(
+        writer: W,
+        options: &EncodeOptions,
+    ) -> Result<Box<dyn Compressor + 'a>> {
+        reject_password("tar.lz4", options)?;
+        Ok(Box::new(FrameEncoder::new(writer)))
+    }
+}
+
+impl Encode for TarLz4 {
+    fn encode(files: Vec<File>) -> Result<Vec<u8>> {
+        Self::encode_with(files, &EncodeOptions::default())
+    }
+
+    fn encode_with(files: Vec<File>, options: &EncodeOptions) -> Result<Vec<u8>> {
+        // Pre-allocate compressed buffer (LZ4 typically achieves ~50% on text)
+        let estimated_size = files.iter().map(|f| f.buffer.len()).sum::<usize>() / 2;
+        let mut compressed = Vec::with_capacity(estimated_size);
+
+        encode_tar_archive(files, options, Self::compressor(&mut compressed, options)?)?;
+
+        Ok(compressed)
+    }
+}
@@ -0,0 +1,9 @@
+/// LZ4-compressed TAR archive format
+#[cfg(feature = "tar-lz4")]
+pub struct TarLz4;
+
+#[cfg(all(feature = "tar-lz4", feature = "decode"))]
+mod decode;
+
+#[cfg(all(feature = "tar-lz4", feature = "encode"))]
+mod encode;
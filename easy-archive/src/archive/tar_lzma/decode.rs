@@ -0,0 +1,36 @@
+/// TAR.LZMA decoding implementation
+use crate::{
+    File,
+    archive::tar::decode::{decode_failed, decode_tar_archive},
+    error::{ArchiveError, Result},
+    traits::Decode,
+    types::DecodeOptions,
+};
+use liblzma::{read::XzDecoder, stream::Stream};
+use std::io::Read;
+
+use super::TarLzma;
+
+impl TarLzma {
+    /// Wrap a reader with a streaming LZMA-alone decompressor
+    pub(crate) fn decompress<'a, R: Read + 'a>(reader: R) -> Result<Box<dyn Read + 'a>> {
+        let stream = Stream::new_lzma_decoder(u64::MAX).map_err(|e| {
+            ArchiveError::DecompressionError(format!("Failed to create LZMA decoder: {}", e))
+        })?;
+        Ok(Box::new(XzDecoder::new_stream(reader, stream)))
+    }
+}
+
+impl Decode for TarLzma {
+    fn decode<T: AsRef<[u8]>>(buffer: T) -> Result<Vec<File>> {
+        Self::decode_with(buffer, &DecodeOptions::default())
+    }
+
+    fn decode_with<T: AsRef<[u8]>>(buffer: T, options: &DecodeOptions) -> Result<Vec<File>> {
+        let buffer = buffer.as_ref();
+        let decoder = Self::decompress(buffer)?;
+
+        decode_tar_archive(decoder, &options.limits, buffer.len())
+            .map_err(|e| decode_failed("tar.lzma", e))
+    }
+}
@@ -0,0 +1,50 @@
+/// TAR.LZMA encoding implementation
+use crate::{
+    File,
+    archive::tar::encode::encode_tar_archive,
+    error::{ArchiveError, Result},
+    traits::{Compressor, Encode},
+    types::EncodeOptions,
+    utils::reject_password,
+};
+use liblzma::{
+    stream::{LzmaOptions, Stream},
+    write::XzEncoder,
+};
+use std::io::Write;
+
+use super::TarLzma;
+
+impl TarLzma {
+    /// Wrap a writer with an LZMA-alone compressor
+    ///
+    /// The container has no multi-threaded mode, so the thread option is ignored.
+    pub(crate) fn compressor<'a, W: Write + 'a>(
+        writer: W,
+        options: &EncodeOptions,
+    ) -> Result<Box<dyn Compressor + 'a>> {
+        reject_password("tar.lzma", options)?;
+
+        let level = options.level.unwrap_or(6).min(9);
+        let stream = LzmaOptions::new_preset(level)
+            .and_then(|lzma| Stream::new_lzma_encoder(&lzma))
+            .map_err(|e| {
+                ArchiveError::CompressionError(format!("Failed to create LZMA encoder: {}", e))
+            })?;
+        Ok(Box::new(XzEncoder::new_stream(writer, stream)))
+    }
+}
+
+impl Encode for TarLzma {
+    fn encode(files: Vec<File>) -> Result<Vec<u8>> {
+        Self::encode_with(files, &EncodeOptions::default())
+    }
+
+    fn encode_with(files: Vec<File>, options: &EncodeOptions) -> Result<Vec<u8>> {
+        let mut compressed = Vec::new();
+
+        encode_tar_archive(files, options, Self::compressor(&mut compressed, options)?)?;
+
+        Ok(compressed)
+    }
+}
@@ -0,0 +1,9 @@
+/// LZMA-compressed TAR archive format (legacy LZMA-alone container)
+#[cfg(feature = "tar-lzma")]
+pub struct TarLzma;
+
+#[cfg(all(feature = "tar-lzma", feature = "decode"))]
+mod decode;
+
+#[cfg(all(feature = "tar-lzma", feature = "encode"))]
+mod encode;
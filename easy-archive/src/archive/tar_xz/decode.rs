@@ -1,30 +1,35 @@
 /// TAR.XZ decoding implementation
 use crate::{
     File,
-    archive::tar::decode::decode_tar_archive,
-    error::{ArchiveError, Result},
+    archive::{
+        Xz,
+        tar::decode::{decode_failed, decode_tar_archive},
+    },
+    error::Result,
     traits::Decode,
+    types::DecodeOptions,
 };
-use liblzma::bufread::XzDecoder;
 use std::io::Read;
 
 use super::TarXz;
 
+impl TarXz {
+    /// Wrap a reader with a streaming XZ decompressor
+    pub(crate) fn decompress<'a, R: Read + 'a>(reader: R) -> Result<Box<dyn Read + 'a>> {
+        Xz::decompress(reader)
+    }
+}
+
 impl Decode for TarXz {
     fn decode<T: AsRef<[u8]>>(buffer: T) -> Result<Vec<File>> {
-        let buffer = buffer.as_ref();
-        let mut decoder = XzDecoder::new(buffer);
-        let mut decompressed = Vec::new();
+        Self::decode_with(buffer, &DecodeOptions::default())
+    }
 
-        decoder.read_to_end(&mut decompressed).map_err(|e| {
-            ArchiveError::DecompressionError(format!("XZ decompression failed: {}", e))
-        })?;
+    fn decode_with<T: AsRef<[u8]>>(buffer: T, options: &DecodeOptions) -> Result<Vec<File>> {
+        let buffer = buffer.as_ref();
+        let decoder = Self::decompress(buffer)?;
 
-        decode_tar_archive(std::io::Cursor::new(decompressed)).map_err(|e| {
-            ArchiveError::DecodeFailed {
-                format: "tar.xz".to_string(),
-                reason: e.to_string(),
-            }
-        })
+        decode_tar_archive(decoder, &options.limits, buffer.len())
+            .map_err(|e| decode_failed("tar.xz", e))
     }
 }
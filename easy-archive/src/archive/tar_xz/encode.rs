@@ -1,30 +1,37 @@
 /// TAR.XZ encoding implementation
 use crate::{
-    File, Fmt,
-    error::{ArchiveError, Result},
-    traits::Encode,
+    File,
+    archive::{Xz, tar::encode::encode_tar_archive},
+    error::Result,
+    traits::{Compressor, Encode},
+    types::EncodeOptions,
+    utils::reject_password,
 };
-use liblzma::write::XzEncoder;
-use std::io::{Cursor, Write};
+use std::io::Write;
 
 use super::TarXz;
 
+impl TarXz {
+    /// Wrap a writer with an XZ compressor
+    pub(crate) fn compressor<'a, W: Write + 'a>(
+        writer: W,
+        options: &EncodeOptions,
+    ) -> Result<Box<dyn Compressor + 'a>> {
+        reject_password("tar.xz", options)?;
+        Xz::compressor(writer, options)
+    }
+}
+
 impl Encode for TarXz {
     fn encode(files: Vec<File>) -> Result<Vec<u8>> {
-        let tar = Fmt::Tar.encode(files)?;
-
-        // Encode with compression level 6 (balanced speed/compression)
-        let cursor = Cursor::new(Vec::new());
-        let mut encoder = XzEncoder::new(cursor, 6);
+        Self::encode_with(files, &EncodeOptions::default())
+    }
 
-        encoder
-            .write_all(&tar)
-            .map_err(|e| ArchiveError::CompressionError(format!("XZ compression failed: {}", e)))?;
+    fn encode_with(files: Vec<File>, options: &EncodeOptions) -> Result<Vec<u8>> {
+        let mut compressed = Vec::new();
 
-        let cursor = encoder.finish().map_err(|e| {
-            ArchiveError::CompressionError(format!("XZ finalization failed: {}", e))
-        })?;
+        encode_tar_archive(files, options, Self::compressor(&mut compressed, options)?)?;
 
-        Ok(cursor.into_inner())
+        Ok(compressed)
     }
 }
@@ -0,0 +1,32 @@
+/// TAR.Z decoding implementation
+use crate::{
+    File,
+    archive::tar::decode::{decode_failed, decode_tar_archive},
+    error::Result,
+    traits::Decode,
+    types::DecodeOptions,
+};
+use std::io::Read;
+
+use super::{TarZ, lzw::LzwDecoder};
+
+impl TarZ {
+    /// Wrap a reader with a streaming LZW decompressor
+    pub(crate) fn decompress<'a, R: Read + 'a>(reader: R) -> Result<Box<dyn Read + 'a>> {
+        Ok(Box::new(LzwDecoder::new(reader)))
+    }
+}
+
+impl Decode for TarZ {
+    fn decode<T: AsRef<[u8]>>(buffer: T) -> Result<Vec<File>> {
+        Self::decode_with(buffer, &DecodeOptions::default())
+    }
+
+    fn decode_with<T: AsRef<[u8]>>(buffer: T, options: &DecodeOptions) -> Result<Vec<File>> {
+        let buffer = buffer.as_ref();
+        let decoder = Self::decompress(buffer)?;
+
+        decode_tar_archive(decoder, &options.limits, buffer.len())
+            .map_err(|e| decode_failed("tar.Z", e))
+    }
+}
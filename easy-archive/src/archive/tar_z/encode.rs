@@ -0,0 +1,34 @@
+/// TAR.Z encoding, which is not supported
+use crate::{
+    File,
+    error::{ArchiveError, Result},
+    traits::{Compressor, Encode},
+    types::EncodeOptions,
+};
+use std::io::Write;
+
+use super::TarZ;
+
+fn unsupported() -> ArchiveError {
+    ArchiveError::UnsupportedFormat("tar.Z encoding".to_string())
+}
+
+impl TarZ {
+    /// Fail to create a compressor, LZW compression is not implemented
+    pub(crate) fn compressor<'a, W: Write + 'a>(
+        _writer: W,
+        _options: &EncodeOptions,
+    ) -> Result<Box<dyn Compressor + 'a>> {
+        Err(unsupported())
+    }
+}
+
+impl Encode for TarZ {
+    fn encode(files: Vec<File>) -> Result<Vec<u8>> {
+        Self::encode_with(files, &EncodeOptions::default())
+    }
+
+    fn encode_with(_files: Vec<File>, _options: &EncodeOptions) -> Result<Vec<u8>> {
+        Err(unsupported())
+    }
+}
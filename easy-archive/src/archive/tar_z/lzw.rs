@@ -0,0 +1,247 @@
+/// Streaming decompressor for the LZW format of Unix `compress`
+use std::io::{self, BufRead, BufReader, Read};
+
+/// Magic number of a `compress` stream
+pub(crate) const MAGIC: [u8; 2] = [0x1F, 0x9D];
+
+/// Header flag holding the maximum code width
+const BITS_MASK: u8 = 0x1F;
+
+/// Header flag enabling the CLEAR code
+const BLOCK_MODE: u8 = 0x80;
+
+/// Code width at the start of the stream and after a CLEAR code
+const INIT_BITS: u32 = 9;
+
+/// Largest code width supported by `compress`
+const MAX_BITS: u32 = 16;
+
+/// Code resetting the table in block mode
+const CLEAR: u32 = 256;
+
+fn corrupt(reason: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("corrupt LZW data: {}", reason),
+    )
+}
+
+/// Reader yielding the decompressed content of a `compress` stream
+///
+/// Codes are packed LSB first and written in groups of eight, so a group
+/// spans exactly `n_bits` bytes. When the code width grows or the table is
+/// cleared, `compress` discards the rest of the current group; the same
+/// padding is skipped here.
+pub(crate) struct LzwDecoder<R: Read> {
+    reader: BufReader<R>,
+    /// Pending input bits, LSB first
+    bits: u64,
+    /// Number of valid bits in `bits`
+    bit_count: u32,
+    /// Bits consumed since the start of the current code group
+    group_bits: u64,
+    /// Whether the header has been read
+    started: bool,
+    /// Whether the end of the stream has been reached
+    done: bool,
+    block_mode: bool,
+    max_bits: u32,
+    n_bits: u32,
+    max_code: u32,
+    free_ent: u32,
+    old_code: Option<u32>,
+    fin_char: u8,
+    prefix: Vec<u16>,
+    suffix: Vec<u8>,
+    /// Decoded bytes of the last code, in reverse order
+    stack: Vec<u8>,
+}
+
+impl<R: Read> LzwDecoder<R> {
+    pub(crate) fn new(reader: R) -> Self {
+        LzwDecoder {
+            reader: BufReader::new(reader),
+            bits: 0,
+            bit_count: 0,
+            group_bits: 0,
+            started: false,
+            done: false,
+            block_mode: false,
+            max_bits: MAX_BITS,
+            n_bits: INIT_BITS,
+            max_code: (1 << INIT_BITS) - 1,
+            free_ent: 256,
+            old_code: None,
+            fin_char: 0,
+            prefix: vec![0; 1 << MAX_BITS],
+            suffix: (0..1u32 << MAX_BITS).map(|code| code as u8).collect(),
+            stack: Vec::new(),
+        }
+    }
+
+    /// Parse the three-byte header
+    fn read_header(&mut self) -> io::Result<()> {
+        let mut header = [0; 3];
+        self.reader.read_exact(&mut header).map_err(|e| {
+            if e.kind() == io::ErrorKind::UnexpectedEof {
+                corrupt("truncated header")
+            } else {
+                e
+            }
+        })?;
+        if header[..2] != MAGIC {
+            return Err(corrupt("bad magic number"));
+        }
+
+        self.max_bits = (header[2] & BITS_MASK) as u32;
+        if !(INIT_BITS..=MAX_BITS).contains(&self.max_bits) {
+            return Err(corrupt(&format!(
+                "unsupported code width of {} bits",
+                self.max_bits
+            )));
+        }
+        self.block_mode = header[2] & BLOCK_MODE != 0;
+        self.free_ent = if self.block_mode { CLEAR + 1 } else { 256 };
+        self.started = true;
+        Ok(())
+    }
+
+    /// Make at least `count` bits available, returning false at the end of input
+    fn fill(&mut self, count: u32) -> io::Result<bool> {
+        while self.bit_count < count {
+            let byte = match self.reader.fill_buf()? {
+                [] => return Ok(false),
+                buf => buf[0],
+            };
+            self.reader.consume(1);
+            self.bits |= (byte as u64) << self.bit_count;
+            self.bit_count += 8;
+        }
+        Ok(true)
+    }
+
+    /// Take `count` bits from the input
+    fn take(&mut self, count: u32) -> u32 {
+        let value = (self.bits & ((1 << count) - 1)) as u32;
+        self.bits >>= count;
+        self.bit_count -= count;
+        self.group_bits += count as u64;
+        value
+    }
+
+    /// Skip the unused rest of the current code group
+    fn align(&mut self) -> io::Result<()> {
+        let group = self.n_bits as u64 * 8;
+        let mut skip = (group - self.group_bits % group) % group;
+        while skip > 0 {
+            let count = skip.min(32) as u32;
+            if !self.fill(count)? {
+                // Padding may be cut short at the end of the stream
+                self.bits = 0;
+                self.bit_count = 0;
+                break;
+            }
+            self.take(count);
+            skip -= count as u64;
+        }
+        self.group_bits = 0;
+        Ok(())
+    }
+
+    /// Read the next code, or `None` at the end of the stream
+    fn next_code(&mut self) -> io::Result<Option<u32>> {
+        if self.free_ent > self.max_code {
+            self.align()?;
+            self.n_bits += 1;
+            self.max_code = if self.n_bits == self.max_bits {
+                1 << self.max_bits
+            } else {
+                (1 << self.n_bits) - 1
+            };
+        }
+        if !self.fill(self.n_bits)? {
+            // Trailing bits shorter than a code are padding
+            return Ok(None);
+        }
+        Ok(Some(self.take(self.n_bits)))
+    }
+
+    /// Decode the next code into the stack, returning false at the end of the stream
+    fn decode_next(&mut self) -> io::Result<bool> {
+        loop {
+            let Some(code) = self.next_code()? else {
+                return Ok(false);
+            };
+
+            let Some(old_code) = self.old_code else {
+                if code >= 256 {
+                    return Err(corrupt("first code is not a literal"));
+                }
+                self.old_code = Some(code);
+                self.fin_char = code as u8;
+                self.stack.push(code as u8);
+                return Ok(true);
+            };
+
+            if code == CLEAR && self.block_mode {
+                self.align()?;
+                self.n_bits = INIT_BITS;
+                self.max_code = (1 << INIT_BITS) - 1;
+                // The code following CLEAR fills slot 256, like compress does
+                self.free_ent = CLEAR;
+                continue;
+            }
+
+            let mut current = code;
+            if current >= self.free_ent {
+                if current > self.free_ent {
+                    return Err(corrupt("code out of range"));
+                }
+                // The code being defined: the previous string plus its first byte
+                self.stack.push(self.fin_char);
+                current = old_code;
+            }
+            while current >= 256 {
+                self.stack.push(self.suffix[current as usize]);
+                current = self.prefix[current as usize] as u32;
+            }
+            self.fin_char = current as u8;
+            self.stack.push(self.fin_char);
+
+            if self.free_ent < 1 << self.max_bits {
+                self.prefix[self.free_ent as usize] = old_code as u16;
+                self.suffix[self.free_ent as usize] = self.fin_char;
+                self.free_ent += 1;
+            }
+            self.old_code = Some(code);
+            return Ok(true);
+        }
+    }
+}
+
+impl<R: Read> Read for LzwDecoder<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if !self.started {
+            self.read_header()?;
+        }
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        while self.stack.is_empty() {
+            if self.done || !self.decode_next()? {
+                self.done = true;
+                return Ok(0);
+            }
+        }
+
+        let mut written = 0;
+        while written < buf.len() {
+            let Some(byte) = self.stack.pop() else {
+                break;
+            };
+            buf[written] = byte;
+            written += 1;
+        }
+        Ok(written)
+    }
+}
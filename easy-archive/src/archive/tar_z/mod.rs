@@ -0,0 +1,15 @@
+/// TAR archive compressed with Unix `compress` (LZW, .tar.Z)
+///
+/// Only decoding is supported: `compress` has long been superseded and
+/// archives are not written in this format anymore.
+#[cfg(feature = "tar-z")]
+pub struct TarZ;
+
+#[cfg(all(feature = "tar-z", feature = "decode"))]
+mod decode;
+
+#[cfg(all(feature = "tar-z", feature = "encode"))]
+mod encode;
+
+#[cfg(all(feature = "tar-z", feature = "decode"))]
+mod lzw;
@@ -1,30 +1,35 @@
 /// TAR.ZSTD decoding implementation
 use crate::{
     File,
-    archive::tar::decode::decode_tar_archive,
-    error::{ArchiveError, Result},
+    archive::{
+        Zstd,
+        tar::decode::{decode_failed, decode_tar_archive},
+    },
+    error::Result,
     traits::Decode,
+    types::DecodeOptions,
 };
-use ruzstd::decoding::StreamingDecoder;
-use std::io::{Cursor, Read};
+use std::io::Read;
 
 use super::TarZstd;
 
+impl TarZstd {
+    /// Wrap a reader with a streaming Zstd decompressor
+    pub(crate) fn decompress<'a, R: Read + 'a>(reader: R) -> Result<Box<dyn Read + 'a>> {
+        Zstd::decompress(reader)
+    }
+}
+
 impl Decode for TarZstd {
     fn decode<T: AsRef<[u8]>>(buffer: T) -> Result<Vec<File>> {
-        let cur = Cursor::new(buffer);
-        let mut decoder = StreamingDecoder::new(cur).map_err(|e| {
-            ArchiveError::DecompressionError(format!("Failed to create Zstd decoder: {}", e))
-        })?;
+        Self::decode_with(buffer, &DecodeOptions::default())
+    }
 
-        let mut decompressed = Vec::new();
-        decoder.read_to_end(&mut decompressed).map_err(|e| {
-            ArchiveError::DecompressionError(format!("Zstd decompression failed: {}", e))
-        })?;
+    fn decode_with<T: AsRef<[u8]>>(buffer: T, options: &DecodeOptions) -> Result<Vec<File>> {
+        let buffer = buffer.as_ref();
+        let decoder = Self::decompress(buffer)?;
 
-        decode_tar_archive(Cursor::new(decompressed)).map_err(|e| ArchiveError::DecodeFailed {
-            format: "tar.zst".to_string(),
-            reason: e.to_string(),
-        })
+        decode_tar_archive(decoder, &options.limits, buffer.len())
+            .map_err(|e| decode_failed("tar.zst", e))
     }
 }
@@ -1,33 +1,36 @@
 /// TAR.ZSTD encoding implementation
 use crate::{
-    File, Fmt,
-    error::{ArchiveError, Result},
-    traits::Encode,
+    File,
+    archive::{Zstd, tar::encode::encode_tar_archive},
+    error::Result,
+    traits::{Compressor, Encode},
+    types::EncodeOptions,
+    utils::reject_password,
 };
-use std::io::Cursor;
+use std::io::Write;
 
 use super::TarZstd;
 
+impl TarZstd {
+    /// Wrap a writer with a Zstd compressor
+    pub(crate) fn compressor<'a, W: Write + 'a>(
+        writer: W,
+        options: &EncodeOptions,
+    ) -> Result<Box<dyn Compressor + 'a>> {
+        reject_password("tar.zst", options)?;
+        Zstd::compressor(writer, options)
+    }
+}
+
 impl Encode for TarZstd {
     fn encode(files: Vec<File>) -> Result<Vec<u8>> {
-        let tar = Fmt::Tar.encode(files)?;
-        let mut cursor = Cursor::new(tar);
-        let mut compressed = Vec::new();
-
-        {
-            // Use compression level 6 for balanced speed/compression
-            let mut encoder = zstd::Encoder::new(&mut compressed, 6).map_err(|e| {
-                ArchiveError::CompressionError(format!("Failed to create Zstd encoder: {}", e))
-            })?;
+        Self::encode_with(files, &EncodeOptions::default())
+    }
 
-            std::io::copy(&mut cursor, &mut encoder).map_err(|e| {
-                ArchiveError::CompressionError(format!("Zstd compression failed: {}", e))
-            })?;
+    fn encode_with(files: Vec<File>, options: &EncodeOptions) -> Result<Vec<u8>> {
+        let mut compressed = Vec::new();
 
-            encoder.finish().map_err(|e| {
-                ArchiveError::CompressionError(format!("Zstd finalization failed: {}", e))
-            })?;
-        }
+        encode_tar_archive(files, options, Self::compressor(&mut compressed, options)?)?;
 
         Ok(compressed)
     }
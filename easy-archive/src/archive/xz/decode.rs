@@ -0,0 +1,60 @@
+/// XZ decoding implementation
+use crate::{
+    File,
+    archive::single::{SingleStream, count_single, for_each_single, read_single},
+    error::Result,
+    traits::Decode,
+    types::{DecodeOptions, EntryInfo},
+};
+use liblzma::read::XzDecoder;
+use std::io::{Read, Seek};
+
+use super::Xz;
+
+impl Xz {
+    /// Wrap a reader with a streaming XZ decompressor
+    ///
+    /// Concatenated XZ streams are decompressed as one stream, like `xz -d` does.
+    pub(crate) fn decompress<'a, R: Read + 'a>(reader: R) -> Result<Box<dyn Read + 'a>> {
+        Ok(Box::new(XzDecoder::new_multi_decoder(reader)))
+    }
+
+    /// Wrap a reader with a streaming decompressor, along with the file it holds
+    ///
+    /// The format records no metadata, so the file is unnamed, and its size
+    /// is left at zero as it is only known once the stream is read.
+    pub(crate) fn open<'a, R: Read + 'a>(reader: R) -> Result<SingleStream<'a>> {
+        Ok((EntryInfo::default(), Self::decompress(reader)?))
+    }
+
+    /// Describe the compressed file, decompressing it without buffering its content
+    pub(crate) fn list<R: Read>(reader: R) -> Result<Vec<EntryInfo>> {
+        let (mut info, decoder) = Self::open(reader)?;
+        info.size = count_single("xz", decoder)?;
+        Ok(vec![info])
+    }
+
+    /// Pass the compressed file to `f`, decompressing it without buffering its content
+    pub(crate) fn for_each_entry<R: Read + Seek>(
+        reader: R,
+        options: &DecodeOptions,
+        f: &mut dyn FnMut(&EntryInfo, &mut dyn Read) -> Result<()>,
+    ) -> Result<()> {
+        for_each_single("xz", reader, &options.limits, |r| Self::open(r), f)
+    }
+}
+
+impl Decode for Xz {
+    fn decode<T: AsRef<[u8]>>(buffer: T) -> Result<Vec<File>> {
+        Self::decode_with(buffer, &DecodeOptions::default())
+    }
+
+    fn decode_with<T: AsRef<[u8]>>(buffer: T, options: &DecodeOptions) -> Result<Vec<File>> {
+        let buffer = buffer.as_ref();
+        let decoder = Self::decompress(buffer)?;
+        // XZ does not record the file name
+        let data = read_single("xz", "", decoder, &options.limits, buffer.len())?;
+
+        Ok(vec![File::new(String::new(), data, None, false, None)])
+    }
+}
@@ -0,0 +1,70 @@
+/// XZ encoding implementation
+use crate::{
+    File,
+    archive::single::{compress_single, single_file},
+    error::{ArchiveError, Result},
+    traits::{Compressor, Encode},
+    types::EncodeOptions,
+    utils::reject_password,
+};
+use liblzma::write::XzEncoder;
+use std::io::Write;
+
+use super::Xz;
+
+impl<W: Write> Compressor for XzEncoder<W> {
+    fn finish(self: Box<Self>) -> std::io::Result<()> {
+        XzEncoder::finish(*self).map(|_| ())
+    }
+}
+
+impl Xz {
+    /// Wrap a writer with an XZ compressor
+    pub(crate) fn compressor<'a, W: Write + 'a>(
+        writer: W,
+        options: &EncodeOptions,
+    ) -> Result<Box<dyn Compressor + 'a>> {
+        reject_password("xz", options)?;
+
+        // Encode with compression level 6 (balanced speed/compression)
+        let level = options.level.unwrap_or(6).min(9);
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(threads) = options.threads.filter(|threads| *threads > 1) {
+            let stream = liblzma::stream::MtStreamBuilder::new()
+                .preset(level)
+                .check(liblzma::stream::Check::Crc64)
+                .threads(threads)
+                .encoder()
+                .map_err(|e| {
+                    ArchiveError::CompressionError(format!("Failed to create XZ encoder: {}", e))
+                })?;
+            return Ok(Box::new(XzEncoder::new_stream(writer, stream)));
+        }
+
+        let stream =
+            liblzma::stream::Stream::new_easy_encoder(level, liblzma::stream::Check::Crc64)
+                .map_err(|e| {
+                    ArchiveError::CompressionError(format!("Failed to create XZ encoder: {}", e))
+                })?;
+        Ok(Box::new(XzEncoder::new_stream(writer, stream)))
+    }
+}
+
+impl Encode for Xz {
+    fn encode(files: Vec<File>) -> Result<Vec<u8>> {
+        Self::encode_with(files, &EncodeOptions::default())
+    }
+
+    fn encode_with(files: Vec<File>, options: &EncodeOptions) -> Result<Vec<u8>> {
+        let file = single_file("xz", files)?;
+        let mut compressed = Vec::new();
+
+        compress_single(
+            Self::compressor(&mut compressed, options)?,
+            &file.buffer[..],
+        )?;
+
+        Ok(compressed)
+    }
+}
@@ -0,0 +1,9 @@
+/// XZ-compressed single file format
+#[cfg(feature = "xz")]
+pub struct Xz;
+
+#[cfg(all(feature = "xz", feature = "decode"))]
+mod decode;
+
+#[cfg(all(feature = "xz", feature = "encode"))]
+mod encode;
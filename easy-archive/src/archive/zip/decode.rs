@@ -3,7 +3,8 @@ use crate::{
     File,
     error::{ArchiveError, Result},
     traits::Decode,
-    utils::clean,
+    types::{DecodeOptions, EntryInfo},
+    utils::{LimitTracker, MAX_PREALLOCATION, clean},
 };
 use std::io::{Cursor, Read, Seek, SeekFrom, Write};
 
@@ -11,6 +12,10 @@ use super::Zip;
 
 impl Decode for Zip {
     fn decode<T: AsRef<[u8]>>(buffer: T) -> Result<Vec<File>> {
+        Self::decode_with(buffer, &DecodeOptions::default())
+    }
+
+    fn decode_with<T: AsRef<[u8]>>(buffer: T, options: &DecodeOptions) -> Result<Vec<File>> {
         let buffer = buffer.as_ref();
 
         // Pre-allocate cursor buffer to avoid reallocation
@@ -30,43 +35,197 @@ impl Decode for Zip {
                 reason: format!("Failed to seek: {}", e),
             })?;
 
-        // Pre-allocate files vector (typical zip has 10-100 files)
-        let mut files = Vec::with_capacity(32);
         let mut archive = zip::ZipArchive::new(cursor).map_err(|e| ArchiveError::DecodeFailed {
             format: "zip".to_string(),
             reason: format!("Failed to open zip archive: {}", e),
         })?;
 
+        // The central directory declares the entry count up front
+        let mut tracker = LimitTracker::new(&options.limits, buffer.len());
+        tracker.check_count(archive.len() as u64)?;
+
+        let mut files = Vec::with_capacity(archive.len().min(MAX_PREALLOCATION as usize));
+
         for i in 0..archive.len() {
-            let mut file = archive
-                .by_index(i)
-                .map_err(|e| ArchiveError::DecodeFailed {
-                    format: "zip".to_string(),
-                    reason: format!("Failed to read entry {}: {}", i, e),
-                })?;
+            let mut file = open_entry(&mut archive, i, options.password.as_deref())?;
 
             let path = file.name().to_string();
             let is_dir = file.is_dir() || path.ends_with("/");
+            let encrypted = file.encrypted();
+
+            // Read file content (empty for directories, the target for symlinks)
+            let size = if is_dir { 0 } else { file.size() };
+            let mut buffer = tracker.read(&path, size, &mut file, |e| {
+                read_error(e, encrypted, &format!("Failed to read file '{}'", path))
+            })?;
 
-            // Read file content (empty for directories)
-            let mut buffer = Vec::new();
-            if file.is_file() {
-                file.read_to_end(&mut buffer)
-                    .map_err(|e| ArchiveError::DecodeFailed {
-                        format: "zip".to_string(),
-                        reason: format!("Failed to read file '{}': {}", path, e),
-                    })?;
+            let link = file
+                .is_symlink()
+                .then(|| String::from_utf8_lossy(&buffer).to_string());
+            if link.is_some() {
+                buffer.clear();
             }
 
             let path = clean(&path);
-            let last_modified = file
-                .last_modified()
-                .and_then(|dt| time::PrimitiveDateTime::try_from(dt).ok())
-                .map(|pt| pt.assume_utc().unix_timestamp() as u64);
+            let mode = file.unix_mode().map(|mode| mode & 0o7777);
+            let last_modified = last_modified(&file);
 
-            files.push(File::new(path, buffer, None, is_dir, last_modified));
+            files.push(File {
+                link,
+                ..File::new(path, buffer, mode, is_dir, last_modified)
+            });
         }
 
         Ok(files)
     }
 }
+
+/// Open an entry for reading, decrypting it with the password if it is encrypted
+///
+/// Both ZipCrypto and WinZip AES (128, 192 and 256 bit) entries are supported.
+pub(super) fn open_entry<'a, R: Read + Seek>(
+    archive: &'a mut zip::ZipArchive<R>,
+    index: usize,
+    password: Option<&str>,
+) -> Result<zip::read::ZipFile<'a, R>> {
+    let options = zip::ZipReadOptions::new().password(password.map(str::as_bytes));
+    archive
+        .by_index_with_options(index, options)
+        .map_err(|e| match e {
+            zip::result::ZipError::UnsupportedArchive(reason)
+                if reason == zip::result::ZipError::PASSWORD_REQUIRED =>
+            {
+                ArchiveError::PasswordRequired
+            }
+            zip::result::ZipError::InvalidPassword => ArchiveError::InvalidPassword,
+            e => ArchiveError::DecodeFailed {
+                format: "zip".to_string(),
+                reason: format!("Failed to read entry {}: {}", index, e),
+            },
+        })
+}
+
+/// Convert an error while reading entry content
+///
+/// The password check in the header of an encrypted entry only rejects most
+/// wrong passwords; the others are caught by the checksum (ZipCrypto) or the
+/// authentication code (AES) once the content is read.
+fn read_error(e: std::io::Error, encrypted: bool, context: &str) -> ArchiveError {
+    if encrypted && e.kind() == std::io::ErrorKind::InvalidData {
+        return ArchiveError::InvalidPassword;
+    }
+    ArchiveError::DecodeFailed {
+        format: "zip".to_string(),
+        reason: format!("{}: {}", context, e),
+    }
+}
+
+/// Read the target of a symlink entry, which zip stores as its content
+pub(super) fn read_link<R: Read + ?Sized>(file: &mut zip::read::ZipFile<'_, R>) -> Result<String> {
+    let mut target = String::new();
+    let encrypted = file.encrypted();
+    file.read_to_string(&mut target).map_err(|e| {
+        read_error(
+            e,
+            encrypted,
+            &format!("Failed to read symlink '{}'", file.name()),
+        )
+    })?;
+    Ok(target)
+}
+
+/// Convert the DOS timestamp of a zip entry into a Unix timestamp
+fn last_modified<R: Read + ?Sized>(file: &zip::read::ZipFile<'_, R>) -> Option<u64> {
+    file.last_modified()
+        .and_then(|dt| time::PrimitiveDateTime::try_from(dt).ok())
+        .map(|pt| pt.assume_utc().unix_timestamp() as u64)
+}
+
+/// Extract the metadata of a zip entry
+///
+/// The link target of symlinks is stored as content and is not filled in.
+pub(super) fn entry_info<R: Read + ?Sized>(file: &zip::read::ZipFile<'_, R>) -> EntryInfo {
+    let is_dir = file.is_dir();
+    EntryInfo {
+        path: clean(file.name()),
+        size: if is_dir || file.is_symlink() {
+            0
+        } else {
+            file.size()
+        },
+        mode: file.unix_mode().map(|mode| mode & 0o7777),
+        is_dir,
+        last_modified: last_modified(file),
+        link: None,
+        uid: None,
+        gid: None,
+    }
+}
+
+/// Open a zip archive for reading
+pub(super) fn open_archive<R: Read + Seek>(reader: R) -> Result<zip::ZipArchive<R>> {
+    zip::ZipArchive::new(reader).map_err(|e| ArchiveError::DecodeFailed {
+        format: "zip".to_string(),
+        reason: format!("Failed to open zip archive: {}", e),
+    })
+}
+
+impl Zip {
+    /// Pass every entry of a zip archive to `f`, along with its content
+    ///
+    /// Entries are decompressed, and decrypted with the password, while `f`
+    /// reads them.
+    pub(crate) fn for_each_entry<R: Read + Seek>(
+        reader: R,
+        options: &DecodeOptions,
+        mut f: impl FnMut(&EntryInfo, &mut dyn Read) -> Result<()>,
+    ) -> Result<()> {
+        let mut archive = open_archive(reader)?;
+        for i in 0..archive.len() {
+            let mut file = open_entry(&mut archive, i, options.password.as_deref())?;
+            let mut info = entry_info(&file);
+            if file.is_symlink() {
+                info.link = Some(read_link(&mut file)?);
+                f(&info, &mut std::io::empty())?;
+            } else {
+                f(&info, &mut file)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// List the entries of a zip archive without reading their content
+    ///
+    /// Only the central directory and the local headers are read. The
+    /// password is needed for encrypted symlinks, whose target is their content.
+    pub(crate) fn list<R: Read + Seek>(
+        reader: R,
+        options: &DecodeOptions,
+    ) -> Result<Vec<EntryInfo>> {
+        let mut archive = open_archive(reader)?;
+
+        let mut list = Vec::with_capacity(archive.len());
+        for i in 0..archive.len() {
+            let file = archive
+                .by_index_raw(i)
+                .map_err(|e| ArchiveError::DecodeFailed {
+                    format: "zip".to_string(),
+                    reason: format!("Failed to read entry {}: {}", i, e),
+                })?;
+
+            let is_symlink = file.is_symlink();
+            let mut info = entry_info(&file);
+            drop(file);
+
+            // The link target is the (tiny) content of the entry
+            if is_symlink {
+                let mut file = open_entry(&mut archive, i, options.password.as_deref())?;
+                info.link = Some(read_link(&mut file)?);
+            }
+
+            list.push(info);
+        }
+
+        Ok(list)
+    }
+}
@@ -0,0 +1,520 @@
+/// Editing of existing ZIP archives
+use crate::{
+    File,
+    error::{ArchiveError, Result},
+    types::{EncodeOptions, EntryInfo},
+};
+use std::collections::HashSet;
+use std::io::{Read, Seek, SeekFrom, Write};
+
+use super::{
+    Zip,
+    decode::{entry_info, open_archive, open_entry, read_link},
+    encode::append_entry,
+    split::{
+        CENTRAL_HEADER, CENTRAL_HEADER_SIZE, END_OF_CENTRAL_DIRECTORY,
+        ZIP64_END_OF_CENTRAL_DIRECTORY, ZIP64_LOCATOR, le_u16,
+    },
+};
+
+/// Where the content of an entry of the edited archive comes from
+enum Source<'a> {
+    /// An entry of the original archive, copied without recompression
+    Original(usize),
+    /// A new entry, compressed while the archive is saved
+    Added(EntryInfo, Box<dyn Read + 'a>),
+}
+
+/// An entry of the edited archive
+struct Pending<'a> {
+    name: String,
+    source: Source<'a>,
+}
+
+/// The part of `name` below the entry `path`, empty for `path` itself
+///
+/// Directory names end with a slash in zip archives, so `dir` matches both
+/// `dir/` and `dir/file.txt` but not `directory`.
+fn relative<'n>(name: &'n str, path: &str) -> Option<&'n str> {
+    let rest = name.strip_prefix(path.trim_end_matches('/'))?;
+    (rest.is_empty() || rest.starts_with('/')).then_some(rest)
+}
+
+/// Check whether two entry names refer to the same path
+fn same(a: &str, b: &str) -> bool {
+    a.trim_end_matches('/') == b.trim_end_matches('/')
+}
+
+fn encode_failed(reason: String) -> ArchiveError {
+    ArchiveError::EncodeFailed {
+        format: "zip".to_string(),
+        reason,
+    }
+}
+
+/// Split a central directory into `count` headers, including their name,
+/// extra field and comment
+fn central_headers(directory: &[u8], count: usize) -> Result<Vec<&[u8]>> {
+    let invalid = || ArchiveError::InvalidArchive("Invalid zip central directory".to_string());
+    let mut headers = Vec::with_capacity(count);
+    let mut at = 0;
+    for _ in 0..count {
+        let header = &directory[at..];
+        if header.len() < CENTRAL_HEADER_SIZE || !header.starts_with(CENTRAL_HEADER) {
+            return Err(invalid());
+        }
+        let size = CENTRAL_HEADER_SIZE
+            + le_u16(header, 28) as usize
+            + le_u16(header, 30) as usize
+            + le_u16(header, 32) as usize;
+        headers.push(header.get(..size).ok_or_else(invalid)?);
+        at += size;
+    }
+    Ok(headers)
+}
+
+/// Build the records ending an archive whose central directory holds
+/// `count` headers, spanning `size` bytes from `start`
+///
+/// Zip64 records are added when the values overflow the classic record.
+fn end_records(count: usize, start: u64, size: u64, comment: &[u8]) -> Vec<u8> {
+    let mut end = Vec::new();
+    let count = count as u64;
+    if count >= u16::MAX as u64 || start >= u32::MAX as u64 || size >= u32::MAX as u64 {
+        end.extend_from_slice(ZIP64_END_OF_CENTRAL_DIRECTORY);
+        // Size of the rest of the record, versions made by and needed, disks
+        end.extend_from_slice(&44u64.to_le_bytes());
+        end.extend_from_slice(&45u16.to_le_bytes());
+        end.extend_from_slice(&45u16.to_le_bytes());
+        end.extend_from_slice(&[0; 8]);
+        end.extend_from_slice(&count.to_le_bytes());
+        end.extend_from_slice(&count.to_le_bytes());
+        end.extend_from_slice(&size.to_le_bytes());
+        end.extend_from_slice(&start.to_le_bytes());
+
+        end.extend_from_slice(ZIP64_LOCATOR);
+        end.extend_from_slice(&0u32.to_le_bytes());
+        end.extend_from_slice(&(start + size).to_le_bytes());
+        end.extend_from_slice(&1u32.to_le_bytes());
+    }
+
+    let count = count.min(u16::MAX as u64) as u16;
+    end.extend_from_slice(END_OF_CENTRAL_DIRECTORY);
+    end.extend_from_slice(&[0; 4]);
+    end.extend_from_slice(&count.to_le_bytes());
+    end.extend_from_slice(&count.to_le_bytes());
+    end.extend_from_slice(&(size.min(u32::MAX as u64) as u32).to_le_bytes());
+    end.extend_from_slice(&(start.min(u32::MAX as u64) as u32).to_le_bytes());
+    end.extend_from_slice(&(comment.len() as u16).to_le_bytes());
+    end.extend_from_slice(comment);
+    end
+}
+
+/// An existing zip archive being edited
+///
+/// Entries can be added, deleted and renamed. Nothing is written until
+/// [`ZipEditor::save`], which rewrites the whole archive: it copies the
+/// compressed data of the kept entries as is, compresses only the added
+/// ones and writes a new central directory. Edits that only add and delete
+/// entries can instead be written into the original file with
+/// [`ZipEditor::save_in_place`], which leaves the kept entries where they
+/// are. Leading data, such as a self-extracting stub, and the archive
+/// comment are kept.
+///
+/// # Example
+/// ```no_run
+/// use easy_archive::{EntryInfo, archive::Zip};
+/// use std::fs::File;
+/// use std::io::BufWriter;
+///
+/// let config = std::fs::read("config.toml")?;
+/// let info = EntryInfo {
+///     path: "app/config.toml".to_string(),
+///     size: config.len() as u64,
+///     mode: Some(0o644),
+///     is_dir: false,
+///     last_modified: None,
+///     link: None,
+///     uid: None,
+///     gid: None,
+/// };
+/// let mut editor = Zip::edit(File::open("app.zip")?)?;
+/// // Replaces the existing entry of the same path
+/// editor.add(&info, &config[..])?;
+/// editor.delete("app/cache")?;
+/// editor.rename("app/README", "app/README.md")?;
+/// editor.save(BufWriter::new(File::create("app-patched.zip")?))?;
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub struct ZipEditor<'a, R> {
+    archive: zip::ZipArchive<R>,
+    /// Data before the archive, such as a self-extracting stub
+    prefix: Vec<u8>,
+    entries: Vec<Pending<'a>>,
+    options: EncodeOptions,
+}
+
+impl Zip {
+    /// Open a zip archive for editing
+    ///
+    /// Added entries are compressed with the default [`EncodeOptions`].
+    ///
+    /// # Arguments
+    /// * `reader` - The archive to edit
+    ///
+    /// # Returns
+    /// * `Ok(ZipEditor)` - The editor, holding every entry of the archive
+    /// * `Err(ArchiveError)` - If the archive cannot be read
+    pub fn edit<'a, R: Read + Seek>(reader: R) -> Result<ZipEditor<'a, R>> {
+        Self::edit_with(reader, &EncodeOptions::default())
+    }
+
+    /// Open a zip archive for editing, compressing added entries with `options`
+    ///
+    /// # Arguments
+    /// * `reader` - The archive to edit
+    /// * `options` - The compression method, level and password of added entries
+    ///
+    /// # Returns
+    /// * `Ok(ZipEditor)` - The editor, holding every entry of the archive
+    /// * `Err(ArchiveError)` - If the archive cannot be read
+    pub fn edit_with<'a, R: Read + Seek>(
+        reader: R,
+        options: &EncodeOptions,
+    ) -> Result<ZipEditor<'a, R>> {
+        let mut archive = open_archive(reader)?;
+
+        // Entries follow the prefix, whether their offsets count it or not
+        let offset = (0..archive.len())
+            .filter_map(|index| Some(archive.by_index_raw(index).ok()?.header_start()))
+            .min()
+            .unwrap_or(archive.offset());
+        let mut prefix = Vec::new();
+        if offset > 0 {
+            let mut reader = archive.into_inner();
+            reader.seek(SeekFrom::Start(0))?;
+            (&mut reader).take(offset).read_to_end(&mut prefix)?;
+            archive = open_archive(reader)?;
+        }
+
+        let entries = (0..archive.len())
+            .map(|index| Pending {
+                name: archive
+                    .name_for_index(index)
+                    .unwrap_or_default()
+                    .to_string(),
+                source: Source::Original(index),
+            })
+            .collect();
+
+        Ok(ZipEditor {
+            archive,
+            prefix,
+            entries,
+            options: options.clone(),
+        })
+    }
+}
+
+impl<'a, R: Read + Seek> ZipEditor<'a, R> {
+    /// The paths of the entries the saved archive will hold, in order
+    ///
+    /// Directory paths end with a slash.
+    pub fn paths(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().map(|entry| entry.name.as_str())
+    }
+
+    /// Check whether the saved archive will hold an entry at `path`
+    pub fn contains(&self, path: &str) -> bool {
+        self.entries.iter().any(|entry| same(&entry.name, path))
+    }
+
+    /// Add an entry, streaming its content from `reader` when the archive is saved
+    ///
+    /// An entry of the original archive with the same path is replaced.
+    ///
+    /// # Arguments
+    /// * `info` - The metadata of the entry
+    /// * `reader` - The content of the entry, ignored for directories and symlinks
+    ///
+    /// # Returns
+    /// * `Ok(())` - If the entry was queued
+    /// * `Err(ArchiveError)` - If an entry with the same path was already added
+    pub fn add(&mut self, info: &EntryInfo, reader: impl Read + 'a) -> Result<()> {
+        let added = |entry: &Pending| matches!(entry.source, Source::Added(..));
+        if self
+            .entries
+            .iter()
+            .any(|entry| added(entry) && same(&entry.name, &info.path))
+        {
+            return Err(ArchiveError::DuplicateFiles {
+                paths: vec![info.path.clone()],
+            });
+        }
+
+        self.entries.retain(|entry| !same(&entry.name, &info.path));
+        self.entries.push(Pending {
+            name: info.path.clone(),
+            source: Source::Added(info.clone(), Box::new(reader)),
+        });
+        Ok(())
+    }
+
+    /// Add an in-memory [`File`], replacing an entry with the same path
+    pub fn add_file(&mut self, file: &'a File) -> Result<()> {
+        self.add(&EntryInfo::from(file), &file.buffer[..])
+    }
+
+    /// Delete an entry, along with everything below it for a directory
+    ///
+    /// # Arguments
+    /// * `path` - The path of the entry
+    ///
+    /// # Returns
+    /// * `Ok(usize)` - The number of deleted entries
+    /// * `Err(ArchiveError)` - If no entry has that path
+    pub fn delete(&mut self, path: &str) -> Result<usize> {
+        let len = self.entries.len();
+        self.entries
+            .retain(|entry| relative(&entry.name, path).is_none());
+
+        match len - self.entries.len() {
+            0 => Err(ArchiveError::EntryNotFound {
+                path: path.to_string(),
+            }),
+            deleted => Ok(deleted),
+        }
+    }
+
+    /// Rename an entry, along with everything below it for a directory
+    ///
+    /// Renamed entries are copied without recompression, only their headers
+    /// change.
+    ///
+    /// # Arguments
+    /// * `from` - The current path of the entry
+    /// * `to` - The new path of the entry
+    ///
+    /// # Returns
+    /// * `Ok(usize)` - The number of renamed entries
+    /// * `Err(ArchiveError)` - If no entry has the path `from`, or another
+    ///   entry already has a new path
+    pub fn rename(&mut self, from: &str, to: &str) -> Result<usize> {
+        let to = to.trim_end_matches('/');
+        let renamed: Vec<(usize, String)> = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter_map(|(i, entry)| {
+                relative(&entry.name, from).map(|rest| (i, format!("{}{}", to, rest)))
+            })
+            .collect();
+        if renamed.is_empty() {
+            return Err(ArchiveError::EntryNotFound {
+                path: from.to_string(),
+            });
+        }
+
+        let duplicates: Vec<String> = self
+            .entries
+            .iter()
+            .filter(|entry| relative(&entry.name, from).is_none())
+            .filter(|entry| renamed.iter().any(|(_, name)| same(name, &entry.name)))
+            .map(|entry| entry.name.clone())
+            .collect();
+        if !duplicates.is_empty() {
+            return Err(ArchiveError::DuplicateFiles { paths: duplicates });
+        }
+
+        let count = renamed.len();
+        for (i, name) in renamed {
+            self.entries[i].name = name;
+        }
+        Ok(count)
+    }
+
+    /// Check whether the edits can be written with [`ZipEditor::save_in_place`]
+    ///
+    /// This is the case when no entry was renamed, and the offsets of the
+    /// archive count any leading data.
+    pub fn can_save_in_place(&self) -> bool {
+        self.archive.offset() == 0
+            && self.entries.iter().all(|entry| match entry.source {
+                Source::Original(index) => {
+                    self.archive.name_for_index(index) == Some(entry.name.as_str())
+                }
+                Source::Added(..) => true,
+            })
+    }
+
+    /// Write the edits into the file of the original archive
+    ///
+    /// The file is truncated at its central directory, the added entries are
+    /// appended and a new central directory lists them along with the kept
+    /// entries, whose data is not touched. The data of deleted and replaced
+    /// entries stays in the file, unreferenced, until the archive is next
+    /// rewritten with [`ZipEditor::save`]. If writing fails, the original
+    /// central directory is restored.
+    ///
+    /// # Arguments
+    /// * `file` - The file of the original archive, opened for reading and writing
+    ///
+    /// # Returns
+    /// * `Ok(())` - If the archive was updated
+    /// * `Err(ArchiveError)` - If an entry was renamed (see
+    ///   [`ZipEditor::can_save_in_place`]), or reading or writing fails
+    pub fn save_in_place(self, mut file: std::fs::File) -> Result<()> {
+        if !self.can_save_in_place() {
+            return Err(encode_failed(
+                "Renamed entries need a full rewrite of the archive".to_string(),
+            ));
+        }
+
+        let start = self.archive.central_directory_start();
+        let len = file.seek(SeekFrom::End(0))?;
+        let mut tail = Vec::new();
+        file.seek(SeekFrom::Start(start))?;
+        (&mut file).take(len - start).read_to_end(&mut tail)?;
+
+        let result = self.append_in_place(&mut file, start, &tail);
+        if result.is_err() {
+            let _ = file
+                .seek(SeekFrom::Start(start))
+                .and_then(|_| file.write_all(&tail))
+                .and_then(|_| file.set_len(len));
+        }
+        result
+    }
+
+    /// Append the added entries at `start` and write the new central directory
+    fn append_in_place(self, file: &mut std::fs::File, start: u64, directory: &[u8]) -> Result<()> {
+        let mut kept = HashSet::new();
+        let mut added = Vec::new();
+        for entry in self.entries {
+            match entry.source {
+                Source::Original(index) => {
+                    kept.insert(index);
+                }
+                Source::Added(info, reader) => added.push((info, reader)),
+            }
+        }
+        let mut headers: Vec<u8> = central_headers(directory, self.archive.len())?
+            .into_iter()
+            .enumerate()
+            .filter(|(index, _)| kept.contains(index))
+            .flat_map(|(_, header)| header.iter().copied())
+            .collect();
+        let count = kept.len() + added.len();
+
+        // New entries replace the old central directory, and the headers the
+        // zip writer lists them with are merged into the final one
+        let mut end = start;
+        if !added.is_empty() {
+            file.seek(SeekFrom::Start(start))?;
+            let mut zip = zip::ZipWriter::new(&mut *file);
+            for (info, reader) in &mut added {
+                let info = match &self.options.reproducible {
+                    Some(reproducible) => reproducible.normalize(info),
+                    None => info.clone(),
+                };
+                append_entry(&mut zip, &self.options, &info, reader)?;
+            }
+            zip.finish()
+                .map_err(|e| encode_failed(format!("Failed to finalize zip archive: {}", e)))?;
+
+            end = open_archive(&mut *file)?.central_directory_start();
+            let mut appended = Vec::new();
+            file.seek(SeekFrom::Start(end))?;
+            file.read_to_end(&mut appended)?;
+            for header in central_headers(&appended, added.len())? {
+                headers.extend_from_slice(header);
+            }
+        }
+
+        file.seek(SeekFrom::Start(end))?;
+        file.write_all(&headers)?;
+        file.write_all(&end_records(
+            count,
+            end,
+            headers.len() as u64,
+            self.archive.comment(),
+        ))?;
+        let len = file.stream_position()?;
+        file.set_len(len)?;
+        file.flush()?;
+        Ok(())
+    }
+
+    /// Write the edited archive
+    ///
+    /// The kept entries are copied as is, compressed and, if they were,
+    /// encrypted, so no password is needed to edit an encrypted archive.
+    /// Directories and symlinks are the exception: they are written anew, and
+    /// encrypted symlinks are decrypted with the password of the options.
+    ///
+    /// # Arguments
+    /// * `writer` - Where to write the archive, which cannot be the reader
+    ///   of the original archive
+    ///
+    /// # Returns
+    /// * `Ok(())` - If the archive was written
+    /// * `Err(ArchiveError)` - If reading an entry or writing fails
+    pub fn save<W: Write + Seek>(mut self, mut writer: W) -> Result<()> {
+        // The offsets written after the prefix count it, like `zip -A`
+        writer.write_all(&self.prefix)?;
+        let mut zip = zip::ZipWriter::new(writer);
+        zip.set_raw_comment(self.archive.comment().into())
+            .map_err(|e| encode_failed(format!("Failed to set the archive comment: {}", e)))?;
+
+        for entry in self.entries {
+            match entry.source {
+                Source::Original(index) => {
+                    let file = self.archive.by_index_raw(index).map_err(|e| {
+                        ArchiveError::DecodeFailed {
+                            format: "zip".to_string(),
+                            reason: format!("Failed to read entry '{}': {}", entry.name, e),
+                        }
+                    })?;
+
+                    // Raw copies are written as regular files, so directories
+                    // and symlinks, which have no data worth copying, are added anew
+                    if file.is_dir() || file.is_symlink() {
+                        let mut info = EntryInfo {
+                            path: entry.name,
+                            ..entry_info(&file)
+                        };
+                        drop(file);
+                        if !info.is_dir {
+                            let password = self.options.password.as_deref();
+                            let mut file = open_entry(&mut self.archive, index, password)?;
+                            info.link = Some(read_link(&mut file)?);
+                        }
+                        append_entry(&mut zip, &self.options, &info, std::io::empty())?;
+                        continue;
+                    }
+
+                    zip.raw_copy_file_rename(file, &entry.name).map_err(|e| {
+                        encode_failed(format!("Failed to copy entry '{}': {}", entry.name, e))
+                    })?;
+                }
+                Source::Added(info, reader) => {
+                    let info = EntryInfo {
+                        path: entry.name,
+                        ..info
+                    };
+                    let info = match &self.options.reproducible {
+                        Some(reproducible) => reproducible.normalize(&info),
+                        None => info,
+                    };
+                    append_entry(&mut zip, &self.options, &info, reader)?;
+                }
+            }
+        }
+
+        let mut writer = zip
+            .finish()
+            .map_err(|e| encode_failed(format!("Failed to finalize zip archive: {}", e)))?;
+        writer.flush()?;
+        Ok(())
+    }
+}
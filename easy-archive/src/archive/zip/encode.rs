@@ -3,47 +3,178 @@ use crate::{
     File,
     error::{ArchiveError, Result},
     traits::Encode,
+    types::{EncodeOptions, EntryInfo, ZipMethod},
     utils::check_duplicate_files,
 };
 use std::collections::HashSet;
-use std::io::{Cursor, Write};
+use std::io::{Cursor, Read, Seek, Write};
 use time::OffsetDateTime;
 use zip::DateTime;
 
 use super::Zip;
 
+/// Create the options for a zip entry with the given timestamp
+///
+/// Entries are encrypted with AES-256 when the options carry a password.
+///
+/// Performance: Zstd (the default method) provides excellent compression speed and ratio
+pub(crate) fn file_options<'a>(
+    last_modified: Option<u64>,
+    options: &'a EncodeOptions,
+) -> Result<zip::write::FullFileOptions<'a>> {
+    let (method, range) = match options.method {
+        ZipMethod::Stored => (zip::CompressionMethod::Stored, None),
+        ZipMethod::Deflate => (zip::CompressionMethod::Deflated, Some(0..=9)),
+        ZipMethod::Bzip2 => (zip::CompressionMethod::Bzip2, Some(1..=9)),
+        ZipMethod::Zstd => (zip::CompressionMethod::Zstd, Some(1..=22)),
+        ZipMethod::Xz => (zip::CompressionMethod::Xz, Some(0..=9)),
+        ZipMethod::Lzma => {
+            return Err(ArchiveError::UnsupportedFormat(
+                "LZMA compression is not supported when writing zip archives".to_string(),
+            ));
+        }
+    };
+    let level = range
+        .zip(options.level)
+        .map(|(range, level)| level.clamp(*range.start(), *range.end()) as i64);
+
+    let mut file_options = zip::write::FullFileOptions::default()
+        .compression_method(method)
+        .compression_level(level);
+
+    if let Some(password) = &options.password {
+        file_options = file_options.with_aes_encryption(zip::AesMode::Aes256, password);
+    }
+
+    if let Some(timestamp) = last_modified
+        && let Ok(offset_time) = OffsetDateTime::from_unix_timestamp(timestamp as i64)
+        && let Ok(datetime) = DateTime::try_from(time::PrimitiveDateTime::new(
+            offset_time.date(),
+            offset_time.time(),
+        ))
+    {
+        file_options = file_options.last_modified_time(datetime);
+    } else if options.reproducible.is_some() {
+        // The zip crate would record the current time instead
+        file_options = file_options.last_modified_time(DateTime::default());
+    }
+
+    Ok(file_options)
+}
+
+/// Add a symlink entry, stored with the S_IFLNK Unix mode
+pub(crate) fn add_symlink<W: Write + Seek>(
+    zip: &mut zip::ZipWriter<W>,
+    path: &str,
+    link: &str,
+    mode: Option<u32>,
+    options: zip::write::FullFileOptions<'_>,
+) -> Result<()> {
+    let options = options
+        .compression_level(None)
+        .unix_permissions(mode.unwrap_or(0o777));
+    zip.add_symlink(path, link, options)
+        .map_err(|e| ArchiveError::EncodeFailed {
+            format: "zip".to_string(),
+            reason: format!("Failed to add symlink '{}': {}", path, e),
+        })
+}
+
+/// Append a single entry to a zip writer, streaming its content from `reader`
+pub(crate) fn append_entry<W: Write + Seek, R: Read>(
+    zip: &mut zip::ZipWriter<W>,
+    options: &EncodeOptions,
+    info: &EntryInfo,
+    mut reader: R,
+) -> Result<()> {
+    let options = file_options(info.last_modified, options)?;
+
+    if let Some(link) = &info.link {
+        return add_symlink(zip, &info.path, link, info.mode, options);
+    }
+
+    if info.is_dir {
+        return zip
+            .add_directory(&info.path, options)
+            .map_err(|e| ArchiveError::EncodeFailed {
+                format: "zip".to_string(),
+                reason: format!("Failed to add directory '{}': {}", info.path, e),
+            });
+    }
+
+    let options = options
+        .unix_permissions(info.mode.unwrap_or(0o755))
+        .large_file(info.size >= u32::MAX as u64);
+
+    zip.start_file(&info.path, options)
+        .map_err(|e| ArchiveError::EncodeFailed {
+            format: "zip".to_string(),
+            reason: format!("Failed to start file '{}': {}", info.path, e),
+        })?;
+
+    std::io::copy(&mut reader, zip).map_err(|e| ArchiveError::EncodeFailed {
+        format: "zip".to_string(),
+        reason: format!("Failed to write file '{}': {}", info.path, e),
+    })?;
+
+    Ok(())
+}
+
 impl Encode for Zip {
     fn encode(files: Vec<File>) -> Result<Vec<u8>> {
+        Self::encode_with(files, &EncodeOptions::default())
+    }
+
+    fn encode_with(files: Vec<File>, options: &EncodeOptions) -> Result<Vec<u8>> {
+        Self::encode_with_prefix(&[], files, options)
+    }
+}
+
+impl Zip {
+    /// Encode a zip archive placed after arbitrary leading data
+    ///
+    /// The prefix is typically a self-extracting stub or an installer
+    /// script. The offsets recorded in the archive count the prefix, so the
+    /// output is a valid zip file as a whole, like one adjusted by `zip -A`.
+    ///
+    /// # Arguments
+    /// * `prefix` - The data written before the archive
+    /// * `files` - The files to archive
+    /// * `options` - The compression options
+    ///
+    /// # Returns
+    /// * `Ok(Vec<u8>)` - The prefix followed by the archive
+    /// * `Err(ArchiveError)` - If encoding fails
+    ///
+    /// # Example
+    /// ```no_run
+    /// use easy_archive::{EncodeOptions, File, archive::Zip};
+    /// let script = std::fs::read("install.sh")?;
+    /// let files = vec![File::new("bin/tool".to_string(), vec![], Some(0o755), false, None)];
+    /// let bundle = Zip::encode_with_prefix(&script, files, &EncodeOptions::default())?;
+    /// std::fs::write("install-bundle.sh", bundle)?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn encode_with_prefix(
+        prefix: &[u8],
+        files: Vec<File>,
+        options: &EncodeOptions,
+    ) -> Result<Vec<u8>> {
         // Check for duplicate files before encoding (fail fast)
         check_duplicate_files(&files)?;
+        let files = options.prepare_files(files);
 
         // Pre-allocate output buffer with estimated size
         // ZIP typically achieves 40-60% compression with Zstd
         let estimated_size: usize = files.iter().map(|f| f.buffer.len()).sum::<usize>() / 2;
-        let mut output = Vec::with_capacity(estimated_size);
-        let cursor = Cursor::new(&mut output);
+        let mut output = Vec::with_capacity(prefix.len() + estimated_size);
+        output.extend_from_slice(prefix);
+        // The writer records its stream position as the offset of each entry
+        let mut cursor = Cursor::new(&mut output);
+        cursor.set_position(prefix.len() as u64);
         let mut zip = zip::ZipWriter::new(cursor);
         let mut dir_set = HashSet::with_capacity(files.len() / 4); // Estimate directory count
 
-        // Helper function to create file options with timestamp
-        // Performance: Zstd provides excellent compression speed and ratio
-        let create_options = |last_modified: Option<u64>| -> zip::write::FullFileOptions {
-            let mut options = zip::write::FullFileOptions::default()
-                // Use Zstd for better compression/speed balance (faster than LZMA, better than Deflate)
-                .compression_method(zip::CompressionMethod::Zstd);
-
-            if let Some(timestamp) = last_modified
-                && let Ok(offset_time) = OffsetDateTime::from_unix_timestamp(timestamp as i64)
-                && let Ok(datetime) = DateTime::try_from(
-                    time::PrimitiveDateTime::new(offset_time.date(), offset_time.time())
-                )
-            {
-                options = options.last_modified_time(datetime);
-            }
-
-            options
-        };
-
         // First pass: Create all explicit directories
         for file in files.iter().filter(|f| f.is_dir) {
             if dir_set.contains(&file.path) {
@@ -51,9 +182,9 @@ impl Encode for Zip {
             }
 
             dir_set.insert(file.path.clone());
-            let options = create_options(file.last_modified);
+            let dir_options = file_options(file.last_modified, options)?;
 
-            zip.add_directory(&file.path, options)
+            zip.add_directory(&file.path, dir_options)
                 .map_err(|e| ArchiveError::EncodeFailed {
                     format: "zip".to_string(),
                     reason: format!("Failed to add directory '{}': {}", file.path, e),
@@ -70,9 +201,14 @@ impl Encode for Zip {
                 let parent_path = parent.to_string_lossy().to_string();
                 if !parent_path.is_empty() && !dir_set.contains(&parent_path) {
                     dir_set.insert(parent_path.clone());
-                    let options = create_options(file.last_modified);
-
-                    zip.add_directory(&parent_path, options).map_err(|e| {
+                    // Rather than the time of whichever file comes first
+                    let last_modified = match &options.reproducible {
+                        Some(reproducible) => reproducible.mtime,
+                        None => file.last_modified,
+                    };
+                    let dir_options = file_options(last_modified, options)?;
+
+                    zip.add_directory(&parent_path, dir_options).map_err(|e| {
                         ArchiveError::EncodeFailed {
                             format: "zip".to_string(),
                             reason: format!(
@@ -87,10 +223,16 @@ impl Encode for Zip {
 
         // Third pass: Add all files
         for file in files.iter().filter(|f| !f.is_dir) {
+            if let Some(link) = &file.link {
+                let entry_options = file_options(file.last_modified, options)?;
+                add_symlink(&mut zip, &file.path, link, file.mode, entry_options)?;
+                continue;
+            }
+
             let mode = file.mode.unwrap_or(0o755);
-            let options = create_options(file.last_modified).unix_permissions(mode);
+            let entry_options = file_options(file.last_modified, options)?.unix_permissions(mode);
 
-            zip.start_file(&file.path, options)
+            zip.start_file(&file.path, entry_options)
                 .map_err(|e| ArchiveError::EncodeFailed {
                     format: "zip".to_string(),
                     reason: format!("Failed to start file '{}': {}", file.path, e),
@@ -3,7 +3,16 @@
 pub struct Zip;
 
 #[cfg(all(feature = "zip", feature = "decode"))]
-mod decode;
+pub(crate) mod decode;
 
 #[cfg(all(feature = "zip", feature = "encode"))]
-mod encode;
+pub(crate) mod encode;
+
+#[cfg(all(feature = "zip", feature = "decode"))]
+pub(crate) mod split;
+
+#[cfg(all(feature = "zip", feature = "decode", feature = "encode"))]
+mod edit;
+
+#[cfg(all(feature = "zip", feature = "decode", feature = "encode"))]
+pub use edit::ZipEditor;
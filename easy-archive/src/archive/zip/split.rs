@@ -0,0 +1,254 @@
+/// Split (spanned) ZIP archive support
+///
+/// Split archives, such as `archive.z01`, `archive.z02`, …, `archive.zip`
+/// written by `zip -s`, record every offset relative to the volume it
+/// points into. Once the volumes are concatenated, only the central
+/// directory and the records behind it need rebasing to absolute offsets
+/// for the archive to read like a single one.
+use crate::error::{ArchiveError, Result};
+use std::io::{Read, Seek, SeekFrom};
+
+/// Signatures of the records rewritten when joining volumes
+pub(super) const CENTRAL_HEADER: &[u8; 4] = b"PK\x01\x02";
+pub(super) const END_OF_CENTRAL_DIRECTORY: &[u8; 4] = b"PK\x05\x06";
+pub(super) const ZIP64_END_OF_CENTRAL_DIRECTORY: &[u8; 4] = b"PK\x06\x06";
+pub(super) const ZIP64_LOCATOR: &[u8; 4] = b"PK\x06\x07";
+
+/// Fixed sizes of the records
+pub(super) const CENTRAL_HEADER_SIZE: usize = 46;
+const END_OF_CENTRAL_DIRECTORY_SIZE: usize = 22;
+const ZIP64_END_OF_CENTRAL_DIRECTORY_SIZE: usize = 56;
+const ZIP64_LOCATOR_SIZE: usize = 20;
+
+/// Header id of the zip64 extended information extra field
+const ZIP64_EXTRA: u16 = 0x0001;
+
+fn invalid(reason: &str) -> ArchiveError {
+    ArchiveError::InvalidArchive(format!("Invalid split zip archive: {}", reason))
+}
+
+pub(super) fn le_u16(bytes: &[u8], at: usize) -> u16 {
+    u16::from_le_bytes([bytes[at], bytes[at + 1]])
+}
+
+fn le_u32(bytes: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap())
+}
+
+fn le_u64(bytes: &[u8], at: usize) -> u64 {
+    u64::from_le_bytes(bytes[at..at + 8].try_into().unwrap())
+}
+
+fn read_at<R: Read + Seek>(reader: &mut R, offset: u64, len: usize) -> Result<Vec<u8>> {
+    let mut buffer = vec![0; len];
+    reader.seek(SeekFrom::Start(offset))?;
+    reader
+        .read_exact(&mut buffer)
+        .map_err(|_| invalid("truncated volume"))?;
+    Ok(buffer)
+}
+
+/// Offsets of the volumes in the joined stream
+struct Disks<'a>(&'a [u64]);
+
+impl Disks<'_> {
+    /// Absolute offset of `offset` within volume `disk`
+    fn absolute(&self, disk: u32, offset: u64) -> Result<u64> {
+        // The last offset is the end of the stream, not a volume
+        self.0[..self.0.len() - 1]
+            .get(disk as usize)
+            .and_then(|start| start.checked_add(offset))
+            .ok_or_else(|| invalid("reference to a missing volume"))
+    }
+}
+
+/// Find the end of central directory record at the end of the stream
+fn find_end_record<R: Read + Seek>(reader: &mut R, len: u64) -> Result<Option<(u64, Vec<u8>)>> {
+    let search = len.min((END_OF_CENTRAL_DIRECTORY_SIZE + u16::MAX as usize) as u64);
+    let buffer = read_at(reader, len - search, search as usize)?;
+    let Some(last) = buffer.len().checked_sub(END_OF_CENTRAL_DIRECTORY_SIZE) else {
+        return Ok(None);
+    };
+    let found = (0..=last).rev().find(|&i| {
+        buffer[i..].starts_with(END_OF_CENTRAL_DIRECTORY)
+            && le_u16(&buffer, i + 20) as usize == last - i
+    });
+    Ok(found.map(|i| (len - search + i as u64, buffer[i..].to_vec())))
+}
+
+/// Rebase the offsets of one central directory header in place
+///
+/// # Returns
+/// The size of the header, including its name, extra field and comment
+fn rebase_central_header(header: &mut [u8], disks: &Disks) -> Result<usize> {
+    if header.len() < CENTRAL_HEADER_SIZE || !header.starts_with(CENTRAL_HEADER) {
+        return Err(invalid("bad central directory header"));
+    }
+    let name_len = le_u16(header, 28) as usize;
+    let extra_len = le_u16(header, 30) as usize;
+    let comment_len = le_u16(header, 32) as usize;
+    let size = CENTRAL_HEADER_SIZE + name_len + extra_len + comment_len;
+    if header.len() < size {
+        return Err(invalid("truncated central directory"));
+    }
+
+    // Values saturated in the header are stored in the zip64 extra field,
+    // in the order uncompressed size, compressed size, offset, disk
+    let mut disk = le_u16(header, 34) as u32;
+    let mut offset = le_u32(header, 42) as u64;
+    let mut offset_at = None;
+    let mut disk_at = None;
+    if disk == u16::MAX as u32 || offset == u32::MAX as u64 {
+        let extra_start = CENTRAL_HEADER_SIZE + name_len;
+        let mut at = extra_start;
+        while at + 4 <= extra_start + extra_len {
+            let id = le_u16(header, at);
+            let len = le_u16(header, at + 2) as usize;
+            let end = at + 4 + len;
+            if end > extra_start + extra_len {
+                break;
+            }
+            if id == ZIP64_EXTRA {
+                let mut field = at + 4;
+                for at in [24, 20] {
+                    if le_u32(header, at) == u32::MAX {
+                        field += 8;
+                    }
+                }
+                if offset == u32::MAX as u64 && field + 8 <= end {
+                    offset = le_u64(header, field);
+                    offset_at = Some(field);
+                    field += 8;
+                }
+                if disk == u16::MAX as u32 && field + 4 <= end {
+                    disk = le_u32(header, field);
+                    disk_at = Some(field);
+                }
+                break;
+            }
+            at = end;
+        }
+    }
+
+    let absolute = disks.absolute(disk, offset)?;
+    match offset_at {
+        Some(at) => header[at..at + 8].copy_from_slice(&absolute.to_le_bytes()),
+        None => {
+            let absolute = u32::try_from(absolute)
+                .ok()
+                .filter(|offset| *offset != u32::MAX)
+                .ok_or_else(|| invalid("joined archive is too large"))?;
+            header[42..46].copy_from_slice(&absolute.to_le_bytes());
+        }
+    }
+    match disk_at {
+        Some(at) => header[at..at + 4].copy_from_slice(&0u32.to_le_bytes()),
+        None => header[34..36].copy_from_slice(&0u16.to_le_bytes()),
+    }
+
+    Ok(size)
+}
+
+/// Rebase the records of a split zip archive to the joined volumes
+///
+/// # Arguments
+/// * `reader` - The concatenated volumes
+/// * `starts` - The offset of each volume in `reader`, followed by the total size
+///
+/// # Returns
+/// * `Ok(Some((offset, tail)))` - The bytes replacing everything from
+///   `offset` on, i.e. the central directory and the records behind it
+/// * `Ok(None)` - If the volumes do not end with a split zip archive
+/// * `Err(ArchiveError)` - If the archive is split but malformed
+pub(crate) fn join_split_zip<R: Read + Seek>(
+    reader: &mut R,
+    starts: &[u64],
+) -> Result<Option<(u64, Vec<u8>)>> {
+    let len = starts.last().copied().unwrap_or(0);
+    if starts.len() <= 2 {
+        return Ok(None);
+    }
+    let Some((end_offset, end_record)) = find_end_record(reader, len)? else {
+        return Ok(None);
+    };
+    // Archives written to a single volume have nothing to rebase
+    if le_u16(&end_record, 4) == 0 {
+        return Ok(None);
+    }
+
+    let disks = Disks(starts);
+    let mut cd_disk = le_u16(&end_record, 6) as u32;
+    let mut cd_size = le_u32(&end_record, 12) as u64;
+    let mut cd_offset = le_u32(&end_record, 16) as u64;
+
+    let mut zip64 = None;
+    if let Some(locator_offset) = end_offset.checked_sub(ZIP64_LOCATOR_SIZE as u64) {
+        let locator = read_at(reader, locator_offset, ZIP64_LOCATOR_SIZE)?;
+        if locator.starts_with(ZIP64_LOCATOR) {
+            let record_offset = disks.absolute(le_u32(&locator, 4), le_u64(&locator, 8))?;
+            let record = read_at(reader, record_offset, ZIP64_END_OF_CENTRAL_DIRECTORY_SIZE)?;
+            if !record.starts_with(ZIP64_END_OF_CENTRAL_DIRECTORY) {
+                return Err(invalid("bad zip64 end of central directory record"));
+            }
+            cd_disk = le_u32(&record, 20);
+            cd_size = le_u64(&record, 40);
+            cd_offset = le_u64(&record, 48);
+            zip64 = Some((locator_offset, record_offset));
+        }
+    }
+
+    let cd_start = disks.absolute(cd_disk, cd_offset)?;
+    if cd_start
+        .checked_add(cd_size)
+        .is_none_or(|end| end > end_offset)
+    {
+        return Err(invalid("central directory out of bounds"));
+    }
+    let mut tail = read_at(reader, cd_start, (len - cd_start) as usize)?;
+
+    let mut at = 0;
+    while at < cd_size as usize {
+        at += rebase_central_header(&mut tail[at..cd_size as usize], &disks)?;
+    }
+
+    // Every record now lives on the first and only disk
+    if let Some((locator_offset, record_offset)) = zip64 {
+        let record = record_offset
+            .checked_sub(cd_start)
+            .map(|at| at as usize)
+            .filter(|at| at + ZIP64_END_OF_CENTRAL_DIRECTORY_SIZE <= tail.len())
+            .ok_or_else(|| invalid("zip64 record before the central directory"))?;
+        let entries = le_u64(&tail, record + 32);
+        tail[record + 16..record + 24].fill(0);
+        tail[record + 24..record + 32].copy_from_slice(&entries.to_le_bytes());
+        tail[record + 48..record + 56].copy_from_slice(&cd_start.to_le_bytes());
+
+        let locator = locator_offset
+            .checked_sub(cd_start)
+            .map(|at| at as usize)
+            .filter(|at| at + ZIP64_LOCATOR_SIZE <= tail.len())
+            .ok_or_else(|| invalid("zip64 locator before the central directory"))?;
+        tail[locator + 4..locator + 8].fill(0);
+        tail[locator + 8..locator + 16].copy_from_slice(&record_offset.to_le_bytes());
+        tail[locator + 16..locator + 20].copy_from_slice(&1u32.to_le_bytes());
+    }
+
+    let end = end_offset
+        .checked_sub(cd_start)
+        .map(|at| at as usize)
+        .filter(|at| at + END_OF_CENTRAL_DIRECTORY_SIZE <= tail.len())
+        .ok_or_else(|| invalid("end of central directory before the central directory"))?;
+    let entries = le_u16(&tail, end + 10);
+    tail[end + 4..end + 8].fill(0);
+    tail[end + 8..end + 10].copy_from_slice(&entries.to_le_bytes());
+    if le_u32(&tail, end + 16) != u32::MAX {
+        let offset = match u32::try_from(cd_start) {
+            Ok(offset) if offset != u32::MAX => offset,
+            _ if zip64.is_some() => u32::MAX,
+            _ => return Err(invalid("joined archive is too large")),
+        };
+        tail[end + 16..end + 20].copy_from_slice(&offset.to_le_bytes());
+    }
+
+    Ok(Some((cd_start, tail)))
+}
@@ -0,0 +1,61 @@
+/// ZSTD decoding implementation
+use crate::{
+    File,
+    archive::single::{SingleStream, count_single, for_each_single, read_single},
+    error::{ArchiveError, Result},
+    traits::Decode,
+    types::{DecodeOptions, EntryInfo},
+};
+use ruzstd::decoding::StreamingDecoder;
+use std::io::{Read, Seek};
+
+use super::Zstd;
+
+impl Zstd {
+    /// Wrap a reader with a streaming Zstd decompressor
+    pub(crate) fn decompress<'a, R: Read + 'a>(reader: R) -> Result<Box<dyn Read + 'a>> {
+        let decoder = StreamingDecoder::new(reader).map_err(|e| {
+            ArchiveError::DecompressionError(format!("Failed to create Zstd decoder: {}", e))
+        })?;
+        Ok(Box::new(decoder))
+    }
+
+    /// Wrap a reader with a streaming decompressor, along with the file it holds
+    ///
+    /// The format records no metadata, so the file is unnamed, and its size
+    /// is left at zero as it is only known once the stream is read.
+    pub(crate) fn open<'a, R: Read + 'a>(reader: R) -> Result<SingleStream<'a>> {
+        Ok((EntryInfo::default(), Self::decompress(reader)?))
+    }
+
+    /// Describe the compressed file, decompressing it without buffering its content
+    pub(crate) fn list<R: Read>(reader: R) -> Result<Vec<EntryInfo>> {
+        let (mut info, decoder) = Self::open(reader)?;
+        info.size = count_single("zst", decoder)?;
+        Ok(vec![info])
+    }
+
+    /// Pass the compressed file to `f`, decompressing it without buffering its content
+    pub(crate) fn for_each_entry<R: Read + Seek>(
+        reader: R,
+        options: &DecodeOptions,
+        f: &mut dyn FnMut(&EntryInfo, &mut dyn Read) -> Result<()>,
+    ) -> Result<()> {
+        for_each_single("zst", reader, &options.limits, |r| Self::open(r), f)
+    }
+}
+
+impl Decode for Zstd {
+    fn decode<T: AsRef<[u8]>>(buffer: T) -> Result<Vec<File>> {
+        Self::decode_with(buffer, &DecodeOptions::default())
+    }
+
+    fn decode_with<T: AsRef<[u8]>>(buffer: T, options: &DecodeOptions) -> Result<Vec<File>> {
+        let buffer = buffer.as_ref();
+        let decoder = Self::decompress(buffer)?;
+        // Zstd does not record the file name
+        let data = read_single("zst", "", decoder, &options.limits, buffer.len())?;
+
+        Ok(vec![File::new(String::new(), data, None, false, None)])
+    }
+}
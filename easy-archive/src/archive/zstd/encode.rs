@@ -0,0 +1,63 @@
+/// ZSTD encoding implementation
+use crate::{
+    File,
+    archive::single::{compress_single, single_file},
+    error::{ArchiveError, Result},
+    traits::{Compressor, Encode},
+    types::EncodeOptions,
+    utils::reject_password,
+};
+use std::io::Write;
+
+use super::Zstd;
+
+impl<W: Write> Compressor for zstd::Encoder<'_, W> {
+    fn finish(self: Box<Self>) -> std::io::Result<()> {
+        zstd::Encoder::finish(*self).map(|_| ())
+    }
+}
+
+impl Zstd {
+    /// Wrap a writer with a Zstd compressor
+    pub(crate) fn compressor<'a, W: Write + 'a>(
+        writer: W,
+        options: &EncodeOptions,
+    ) -> Result<Box<dyn Compressor + 'a>> {
+        reject_password("zst", options)?;
+
+        // Use compression level 6 for balanced speed/compression
+        let level = options.level.map_or(6, |level| level.clamp(1, 22) as i32);
+
+        #[allow(unused_mut)]
+        let mut encoder = zstd::Encoder::new(writer, level).map_err(|e| {
+            ArchiveError::CompressionError(format!("Failed to create Zstd encoder: {}", e))
+        })?;
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(threads) = options.threads.filter(|threads| *threads > 1) {
+            encoder.multithread(threads).map_err(|e| {
+                ArchiveError::CompressionError(format!("Failed to enable Zstd workers: {}", e))
+            })?;
+        }
+
+        Ok(Box::new(encoder))
+    }
+}
+
+impl Encode for Zstd {
+    fn encode(files: Vec<File>) -> Result<Vec<u8>> {
+        Self::encode_with(files, &EncodeOptions::default())
+    }
+
+    fn encode_with(files: Vec<File>, options: &EncodeOptions) -> Result<Vec<u8>> {
+        let file = single_file("zst", files)?;
+        let mut compressed = Vec::new();
+
+        compress_single(
+            Self::compressor(&mut compressed, options)?,
+            &file.buffer[..],
+        )?;
+
+        Ok(compressed)
+    }
+}
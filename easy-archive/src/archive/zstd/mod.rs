@@ -0,0 +1,9 @@
+/// Zstd-compressed single file format
+#[cfg(feature = "zstd")]
+pub struct Zstd;
+
+#[cfg(all(feature = "zstd", feature = "decode"))]
+mod decode;
+
+#[cfg(all(feature = "zstd", feature = "encode"))]
+mod encode;
@@ -0,0 +1,114 @@
+/// Archive to archive conversion
+use crate::{
+    ArchiveWriter, Fmt,
+    error::Result,
+    types::{DecodeOptions, EntryInfo},
+    utils::LimitTracker,
+};
+use std::io::{Read, Seek};
+
+/// Range of modification times a zip (MS-DOS) timestamp can hold, 1980 to 2107
+#[cfg(feature = "zip")]
+const DOS_TIME_RANGE: std::ops::Range<u64> = 315_532_800..4_354_819_200;
+
+/// Entry metadata that not every format can store
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Metadata {
+    /// Unix permission bits
+    Mode,
+    /// Modification time
+    ModificationTime,
+    /// Numeric owner and group ids other than root's
+    Owner,
+    /// Directory entries, which flat formats such as ar skip
+    Directory,
+}
+
+/// Metadata of an entry that the target format of a conversion cannot store
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DroppedMetadata {
+    /// The path of the entry
+    pub path: String,
+    /// The metadata that was dropped
+    pub metadata: Metadata,
+}
+
+/// Summary of a [`Fmt::convert`] run
+#[derive(Debug, Default)]
+pub struct ConvertReport {
+    /// Number of entries read from the source archive
+    pub entries: usize,
+    /// Metadata of the source entries that the target format cannot store
+    pub dropped: Vec<DroppedMetadata>,
+}
+
+/// Check whether `fmt` stores a piece of metadata of an entry
+#[cfg_attr(not(feature = "zip"), allow(unused_variables))]
+fn stores(fmt: Fmt, metadata: Metadata, info: &EntryInfo) -> bool {
+    match (fmt, metadata) {
+        // Zip directories are written without permissions
+        #[cfg(feature = "zip")]
+        (Fmt::Zip, Metadata::Mode) => !info.is_dir,
+        #[cfg(feature = "zip")]
+        (Fmt::Zip, Metadata::ModificationTime) => info
+            .last_modified
+            .is_some_and(|time| DOS_TIME_RANGE.contains(&time)),
+        #[cfg(feature = "zip")]
+        (Fmt::Zip, Metadata::Owner) => false,
+        #[cfg(feature = "7z")]
+        (Fmt::SevenZip, Metadata::Owner) => false,
+        #[cfg(feature = "ar")]
+        (Fmt::Ar, Metadata::Directory) => false,
+        // The gzip header records the modification time
+        #[cfg(feature = "gz")]
+        (Fmt::Gz, Metadata::ModificationTime) => true,
+        _ => !fmt.is_single_file(),
+    }
+}
+
+impl ConvertReport {
+    /// Record the metadata of an entry that `fmt` cannot store
+    fn check(&mut self, fmt: Fmt, info: &EntryInfo) {
+        let present = [
+            (Metadata::Mode, info.mode.is_some()),
+            (Metadata::ModificationTime, info.last_modified.is_some()),
+            (
+                Metadata::Owner,
+                info.uid.is_some_and(|id| id != 0) || info.gid.is_some_and(|id| id != 0),
+            ),
+            (Metadata::Directory, info.is_dir),
+        ];
+        for (metadata, present) in present {
+            if present && !stores(fmt, metadata, info) {
+                self.dropped.push(DroppedMetadata {
+                    path: info.path.clone(),
+                    metadata,
+                });
+            }
+        }
+    }
+}
+
+/// Stream the entries of an archive into an archive of another format
+///
+/// See [`Fmt::convert_with`].
+pub(crate) fn convert<R: Read + Seek>(
+    source: Fmt,
+    mut reader: R,
+    target: Fmt,
+    mut output: ArchiveWriter<'_>,
+    decode_options: &DecodeOptions,
+) -> Result<ConvertReport> {
+    // The packed size bounds the compression ratio limit
+    let mut tracker = LimitTracker::for_stream(&mut reader, &decode_options.limits)?;
+
+    let mut report = ConvertReport::default();
+    source.for_each_entry(reader, decode_options, &mut |info, content| {
+        report.entries += 1;
+        report.check(target, info);
+        tracker.stream(info, content, |content| output.add(info, content))
+    })?;
+    output.finish()?;
+
+    Ok(report)
+}
@@ -0,0 +1,235 @@
+/// Structural comparison of archives
+use crate::File;
+use std::collections::BTreeMap;
+
+#[cfg(feature = "decode")]
+use crate::{
+    Fmt,
+    error::Result,
+    types::{DecodeOptions, EntryInfo},
+    utils::{LimitTracker, hash_reader},
+};
+#[cfg(feature = "decode")]
+use sha2::{Digest, Sha256};
+#[cfg(feature = "decode")]
+use std::io::{Read, Seek};
+
+/// How an entry differs between two archives
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    /// The entry only exists in the new archive
+    Added,
+    /// The entry only exists in the old archive
+    Removed,
+    /// The content, link target or kind (file, directory, symlink) changed
+    Modified,
+    /// The permission bits changed
+    Mode {
+        /// The mode in the old archive
+        old: u32,
+        /// The mode in the new archive
+        new: u32,
+    },
+    /// The modification time changed
+    ModificationTime {
+        /// The Unix timestamp in the old archive
+        old: u64,
+        /// The Unix timestamp in the new archive
+        new: u64,
+    },
+}
+
+/// A difference between the entries of two archives
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Difference {
+    /// The path of the entry
+    pub path: String,
+    /// What changed
+    pub change: Change,
+}
+
+/// The kind of an entry, along with its link target
+#[derive(PartialEq)]
+enum Kind {
+    File,
+    Dir,
+    Link(String),
+}
+
+/// What is compared of an entry, `C` standing for its content
+pub(crate) struct Summary<C> {
+    kind: Kind,
+    mode: Option<u32>,
+    last_modified: Option<u64>,
+    content: C,
+}
+
+impl<C> Summary<C> {
+    pub(crate) fn new(
+        is_dir: bool,
+        link: Option<&str>,
+        mode: Option<u32>,
+        last_modified: Option<u64>,
+        content: C,
+    ) -> Self {
+        let kind = match (is_dir, link) {
+            (true, _) => Kind::Dir,
+            (false, Some(link)) => Kind::Link(link.to_string()),
+            (false, None) => Kind::File,
+        };
+        Summary {
+            kind,
+            mode,
+            last_modified,
+            content,
+        }
+    }
+}
+
+/// Compare the entries of two archives, by path
+///
+/// Metadata is only compared when both archives store it. Modification
+/// times are compared in units of `time_precision` seconds.
+pub(crate) fn compare<C: PartialEq>(
+    old: BTreeMap<String, Summary<C>>,
+    mut new: BTreeMap<String, Summary<C>>,
+    time_precision: u64,
+) -> Vec<Difference> {
+    let mut differences = Vec::new();
+    let mut push = |path: &str, change| {
+        differences.push(Difference {
+            path: path.to_string(),
+            change,
+        })
+    };
+
+    for (path, old) in old {
+        let Some(new) = new.remove(&path) else {
+            push(&path, Change::Removed);
+            continue;
+        };
+        if old.kind != new.kind || old.content != new.content {
+            push(&path, Change::Modified);
+        }
+        if let (Some(old), Some(new)) = (old.mode, new.mode)
+            && old != new
+        {
+            push(&path, Change::Mode { old, new });
+        }
+        if let (Some(old), Some(new)) = (old.last_modified, new.last_modified)
+            && old / time_precision != new / time_precision
+        {
+            push(&path, Change::ModificationTime { old, new });
+        }
+    }
+    for path in new.keys() {
+        push(path, Change::Added);
+    }
+
+    differences.sort_by(|a, b| a.path.cmp(&b.path));
+    differences
+}
+
+/// Compare the files of two archives
+///
+/// Entries are matched by path. Besides added and removed entries, an
+/// entry is reported when its content, link target or kind changed, and
+/// when both archives store its mode or modification time and they differ.
+///
+/// # Arguments
+/// * `old` - The files of the old archive
+/// * `new` - The files of the new archive
+///
+/// # Returns
+/// The differences, sorted by path
+///
+/// # Example
+/// ```no_run
+/// use easy_archive::{Change, Fmt, diff};
+///
+/// let old = Fmt::Zip.decode(std::fs::read("release-1.0.zip")?)?;
+/// let new = Fmt::TarGz.decode(std::fs::read("release-1.1.tar.gz")?)?;
+/// for difference in diff(&old, &new) {
+///     if difference.change == Change::Added {
+///         println!("new file: {}", difference.path);
+///     }
+/// }
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn diff(old: &[File], new: &[File]) -> Vec<Difference> {
+    fn summarize(files: &[File]) -> BTreeMap<String, Summary<&[u8]>> {
+        files
+            .iter()
+            .map(|file| {
+                let summary = Summary::new(
+                    file.is_dir,
+                    file.link.as_deref(),
+                    file.mode,
+                    file.last_modified,
+                    &file.buffer[..],
+                );
+                (file.path.clone(), summary)
+            })
+            .collect()
+    }
+    compare(summarize(old), summarize(new), 1)
+}
+
+/// Seconds a modification time of `fmt` is rounded to
+#[cfg(feature = "decode")]
+fn time_precision(fmt: Fmt) -> u64 {
+    match fmt {
+        // MS-DOS timestamps have a two-second precision
+        #[cfg(feature = "zip")]
+        Fmt::Zip => 2,
+        #[allow(unreachable_patterns)]
+        _ => 1,
+    }
+}
+
+/// Read every entry of an archive, hashing its content
+#[cfg(feature = "decode")]
+fn summarize<R: Read + Seek>(
+    fmt: Fmt,
+    mut reader: R,
+    options: &DecodeOptions,
+) -> Result<BTreeMap<String, Summary<[u8; 32]>>> {
+    let mut tracker = LimitTracker::for_stream(&mut reader, &options.limits)?;
+
+    let mut summaries = BTreeMap::new();
+    fmt.for_each_entry(reader, options, &mut |info: &EntryInfo, content| {
+        let mut hasher = Sha256::new();
+        tracker.stream(info, content, |content| {
+            hash_reader(content, |data| hasher.update(data))
+        })?;
+        let summary = Summary::new(
+            info.is_dir,
+            info.link.as_deref(),
+            info.mode,
+            info.last_modified,
+            hasher.finalize().into(),
+        );
+        summaries.insert(info.path.clone(), summary);
+        Ok(())
+    })?;
+    Ok(summaries)
+}
+
+/// Compare two archives, streaming their entries
+///
+/// See [`Fmt::diff_with`].
+#[cfg(feature = "decode")]
+pub(crate) fn diff_archives<A: Read + Seek, B: Read + Seek>(
+    old_fmt: Fmt,
+    old: A,
+    new_fmt: Fmt,
+    new: B,
+    options: &DecodeOptions,
+) -> Result<Vec<Difference>> {
+    let precision = time_precision(old_fmt).max(time_precision(new_fmt));
+    Ok(compare(
+        summarize(old_fmt, old, options)?,
+        summarize(new_fmt, new, options)?,
+        precision,
+    ))
+}
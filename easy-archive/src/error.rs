@@ -51,4 +51,73 @@ pub enum ArchiveError {
     /// Decompression operation failed
     #[error("Decompression error: {0}")]
     DecompressionError(String),
+
+    /// The archive is encrypted and no password was given
+    #[error("The archive is encrypted, a password is required")]
+    PasswordRequired,
+
+    /// The password does not decrypt the archive
+    #[error("Wrong password for the encrypted archive")]
+    InvalidPassword,
+
+    /// The archive holds more entries than allowed by the decode limits
+    #[error("Archive exceeds the limit of {limit} entries")]
+    TooManyEntries {
+        /// The maximum number of entries
+        limit: u64,
+    },
+
+    /// An entry unpacks to more bytes than allowed by the decode limits
+    #[error("Entry '{path}' exceeds the size limit of {limit} bytes")]
+    EntryTooLarge {
+        /// The path of the offending entry
+        path: String,
+        /// The maximum size of a single entry
+        limit: u64,
+    },
+
+    /// The archive unpacks to more bytes than allowed by the decode limits
+    #[error("Unpacked data exceeds the total size limit of {limit} bytes")]
+    TotalSizeExceeded {
+        /// The maximum number of unpacked bytes
+        limit: u64,
+    },
+
+    /// The archive unpacks to more than its size times the allowed ratio
+    #[error("Compression ratio exceeds the limit of {limit}")]
+    RatioExceeded {
+        /// The maximum ratio between unpacked and archive size
+        limit: u64,
+    },
+
+    /// An entry holds a different amount of data than its header declares
+    #[error("Entry '{path}' holds {actual} bytes, but declares {declared}")]
+    SizeMismatch {
+        /// The path of the offending entry
+        path: String,
+        /// The size declared by the archive
+        declared: u64,
+        /// The number of bytes actually unpacked
+        actual: u64,
+    },
+
+    /// The archive has no entry with the given path
+    #[error("No entry '{path}' in the archive")]
+    EntryNotFound {
+        /// The path that was looked up
+        path: String,
+    },
+
+    /// A checksum manifest is malformed
+    #[error("Invalid manifest: {0}")]
+    InvalidManifest(String),
+
+    /// An entry would be extracted outside the destination directory
+    #[error("Unsafe entry '{path}': {reason}")]
+    UnsafePath {
+        /// The path of the offending entry
+        path: String,
+        /// Why the entry was rejected
+        reason: String,
+    },
 }
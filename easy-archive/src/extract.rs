@@ -0,0 +1,358 @@
+/// Safe extraction of decoded entries to the file system
+use crate::{
+    File,
+    error::{ArchiveError, Result},
+};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Options for [`extract`]
+#[derive(Debug, Clone, Default)]
+pub struct ExtractOptions {
+    /// Skip entries that would escape the destination instead of failing
+    ///
+    /// Skipped entries are reported in [`ExtractReport::skipped`].
+    pub skip_unsafe: bool,
+}
+
+/// Summary of an [`extract`] run
+#[derive(Debug, Default)]
+pub struct ExtractReport {
+    /// Number of entries written to the destination
+    pub extracted: usize,
+    /// Entries rejected with [`ArchiveError::UnsafePath`] while skipping is enabled
+    pub skipped: Vec<ArchiveError>,
+}
+
+impl ExtractReport {
+    /// Record an unsafe entry, or return the error when skipping is disabled
+    fn skip(&mut self, error: ArchiveError, options: &ExtractOptions) -> Result<()> {
+        if options.skip_unsafe && matches!(error, ArchiveError::UnsafePath { .. }) {
+            self.skipped.push(error);
+            Ok(())
+        } else {
+            Err(error)
+        }
+    }
+}
+
+/// Build an [`ArchiveError::UnsafePath`] for an entry
+fn unsafe_path(path: &str, reason: &str) -> ArchiveError {
+    ArchiveError::UnsafePath {
+        path: path.to_string(),
+        reason: reason.to_string(),
+    }
+}
+
+/// Check whether a path is absolute on any platform
+///
+/// Drive letters are rejected everywhere, so that an archive behaves the
+/// same regardless of the system it is extracted on.
+fn is_absolute(path: &str) -> bool {
+    let bytes = path.as_bytes();
+    path.starts_with('/')
+        || (bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':')
+}
+
+/// Lexically resolve `path` on top of `base`, failing if it climbs above the root
+fn resolve<'a>(mut base: Vec<&'a str>, path: &'a str) -> Option<Vec<&'a str>> {
+    for part in path.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                base.pop()?;
+            }
+            part => base.push(part),
+        }
+    }
+    Some(base)
+}
+
+/// Resolve an entry path to a path relative to the destination
+fn entry_path(path: &str) -> Result<Vec<&str>> {
+    if path.contains('\0') {
+        return Err(unsafe_path(path, "path contains a NUL byte"));
+    }
+    if is_absolute(path) {
+        return Err(unsafe_path(path, "path is absolute"));
+    }
+    resolve(Vec::new(), path)
+        .ok_or_else(|| unsafe_path(path, "path points outside the destination"))
+}
+
+/// Check that a symlink target stays inside the destination
+fn check_link(path: &str, parts: &[&str], link: &str) -> Result<()> {
+    if link.is_empty() || link.contains('\0') {
+        return Err(unsafe_path(path, "symlink target is empty or invalid"));
+    }
+    let link = link.replace('\\', "/");
+    if is_absolute(&link) {
+        return Err(unsafe_path(path, "symlink target is absolute"));
+    }
+    let parent = parts[..parts.len().saturating_sub(1)].to_vec();
+    resolve(parent, &link)
+        .map(|_| ())
+        .ok_or_else(|| unsafe_path(path, "symlink target points outside the destination"))
+}
+
+/// Ensure no existing component below `root` is a symlink leading outside of it
+fn check_components(root: &Path, parts: &[&str], path: &str) -> Result<()> {
+    let mut current = root.to_path_buf();
+    for part in parts {
+        current.push(part);
+        let Ok(metadata) = current.symlink_metadata() else {
+            // Nothing exists below a missing component
+            return Ok(());
+        };
+        if metadata.file_type().is_symlink() {
+            match current.canonicalize() {
+                Ok(resolved) if resolved.starts_with(root) => {}
+                _ => {
+                    return Err(unsafe_path(
+                        path,
+                        "a parent directory is a symlink leading outside the destination",
+                    ));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Upper bound on symlinks followed while resolving a single path
+const MAX_LINK_HOPS: usize = 40;
+
+/// Follow `path` below `root` one component at a time, expanding symlinks
+/// found on disk with `read_link`, and check that it never leaves `root`
+///
+/// Unlike `canonicalize`, this also resolves chains that end at a missing
+/// target. Components that do not exist are resolved lexically, while
+/// absolute or unreadable link targets and loops count as escapes.
+fn stays_inside(root: &Path, path: &Path) -> bool {
+    let mut pending: Vec<String> = path
+        .iter()
+        .rev()
+        .map(|part| part.to_string_lossy().into_owned())
+        .collect();
+    let mut current = PathBuf::new();
+    let mut hops = 0;
+    while let Some(part) = pending.pop() {
+        match part.as_str() {
+            "" | "." => {}
+            ".." => {
+                if !current.pop() {
+                    return false;
+                }
+            }
+            _ => {
+                current.push(&part);
+                let full = root.join(&current);
+                match full.symlink_metadata() {
+                    Ok(metadata) if metadata.file_type().is_symlink() => {}
+                    _ => continue,
+                }
+                hops += 1;
+                if hops > MAX_LINK_HOPS {
+                    return false;
+                }
+                let Some(target) = fs::read_link(&full)
+                    .ok()
+                    .and_then(|target| target.to_str().map(|t| t.replace('\\', "/")))
+                else {
+                    return false;
+                };
+                if is_absolute(&target) {
+                    return false;
+                }
+                current.pop();
+                pending.extend(target.split('/').rev().map(str::to_string));
+            }
+        }
+    }
+    true
+}
+
+/// Remove an existing non-directory entry, so that writes never follow it
+fn remove_existing(path: &Path) -> Result<()> {
+    if let Ok(metadata) = path.symlink_metadata()
+        && !metadata.is_dir()
+    {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+/// Apply the permission bits of an entry, dropping setuid/setgid/sticky
+fn set_mode(path: &Path, mode: Option<u32>) -> Result<()> {
+    #[cfg(unix)]
+    if let Some(mode) = mode {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(mode & 0o777))?;
+    }
+    #[cfg(not(unix))]
+    let _ = (path, mode);
+    Ok(())
+}
+
+/// Create a symlink at `path` pointing to `target`
+fn create_symlink(target: &str, path: &Path) -> Result<()> {
+    #[cfg(unix)]
+    std::os::unix::fs::symlink(target, path)?;
+
+    #[cfg(windows)]
+    {
+        let resolved = path.parent().unwrap_or_else(|| Path::new("")).join(target);
+        if resolved.is_dir() {
+            std::os::windows::fs::symlink_dir(target, path)?;
+        } else {
+            std::os::windows::fs::symlink_file(target, path)?;
+        }
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    {
+        let _ = (target, path);
+        return Err(ArchiveError::UnsupportedFormat(
+            "Symlinks are not supported on this platform".to_string(),
+        ));
+    }
+
+    #[allow(unreachable_code)]
+    Ok(())
+}
+
+/// Extract files into a directory, keeping every entry inside it
+///
+/// Absolute paths, `..` components that climb above the destination and
+/// symlinks whose target leaves it are rejected with
+/// [`ArchiveError::UnsafePath`]. Entry paths are checked before anything is
+/// written, existing files are replaced instead of written through, and
+/// symlinks are created only after all regular entries, so an archive can
+/// never redirect its own writes. Permission bits are restored without the
+/// setuid, setgid and sticky bits.
+///
+/// # Arguments
+/// * `files` - The decoded entries, e.g. from [`crate::Fmt::decode`]
+/// * `dest` - The destination directory, created if missing
+/// * `options` - Whether unsafe entries fail the extraction or are skipped
+///
+/// # Returns
+/// * `Ok(ExtractReport)` - The number of extracted entries and the skipped ones
+/// * `Err(ArchiveError)` - If an entry is unsafe and skipping is disabled, or writing fails
+///
+/// # Example
+/// ```no_run
+/// use easy_archive::{ExtractOptions, Fmt, extract};
+/// let files = Fmt::Zip.decode(std::fs::read("upload.zip")?)?;
+/// let options = ExtractOptions { skip_unsafe: true };
+/// let report = extract(&files, "output", &options)?;
+/// for error in &report.skipped {
+///     eprintln!("Skipped: {}", error);
+/// }
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn extract<P: AsRef<Path>>(
+    files: &[File],
+    dest: P,
+    options: &ExtractOptions,
+) -> Result<ExtractReport> {
+    let mut report = ExtractReport::default();
+
+    // Validate every path up front, so a rejected archive leaves nothing behind
+    let mut entries = Vec::with_capacity(files.len());
+    for file in files {
+        let normalized = file.path.replace('\\', "/");
+        let checked = entry_path(&normalized).and_then(|parts| {
+            if parts.is_empty() && !file.is_dir {
+                return Err(unsafe_path(&file.path, "path is empty"));
+            }
+            if let Some(link) = &file.link {
+                check_link(&file.path, &parts, link)?;
+            }
+            Ok(parts.iter().collect::<PathBuf>())
+        });
+        match checked {
+            Ok(relative) => entries.push((file, relative)),
+            Err(e) => report.skip(e, options)?,
+        }
+    }
+
+    fs::create_dir_all(dest.as_ref())?;
+    let root = dest.as_ref().canonicalize()?;
+
+    let mut dirs = Vec::new();
+    let mut links = Vec::new();
+    for (file, relative) in entries {
+        if relative.as_os_str().is_empty() {
+            continue;
+        }
+        if file.link.is_some() {
+            links.push((file, relative));
+            continue;
+        }
+
+        let parts: Vec<&str> = relative.iter().filter_map(|p| p.to_str()).collect();
+        let checked = if file.is_dir {
+            &parts[..]
+        } else {
+            &parts[..parts.len() - 1]
+        };
+        if let Err(e) = check_components(&root, checked, &file.path) {
+            report.skip(e, options)?;
+            continue;
+        }
+
+        let path = root.join(&relative);
+        if file.is_dir {
+            fs::create_dir_all(&path)?;
+            dirs.push((file, path));
+        } else {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            remove_existing(&path)?;
+            fs::write(&path, &file.buffer)?;
+            set_mode(&path, file.mode)?;
+        }
+        report.extracted += 1;
+    }
+
+    let mut created = Vec::new();
+    for (file, relative) in links {
+        let parts: Vec<&str> = relative.iter().filter_map(|p| p.to_str()).collect();
+        if let Err(e) = check_components(&root, &parts[..parts.len() - 1], &file.path) {
+            report.skip(e, options)?;
+            continue;
+        }
+
+        let path = root.join(&relative);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        remove_existing(&path)?;
+        create_symlink(file.link.as_deref().unwrap_or_default(), &path)?;
+        created.push((file, relative, path));
+        report.extracted += 1;
+    }
+
+    // Chains of links can escape even when every single target looks
+    // harmless, e.g. `a -> .` followed by `b -> a/..`, including chains
+    // that end at a missing target
+    for (file, relative, path) in created {
+        if !stays_inside(&root, &relative) {
+            fs::remove_file(&path)?;
+            report.extracted -= 1;
+            report.skip(
+                unsafe_path(&file.path, "symlink chain resolves outside the destination"),
+                options,
+            )?;
+        }
+    }
+
+    // Directory permissions come last, so read-only directories can be filled first
+    for (file, path) in dirs.iter().rev() {
+        set_mode(path, file.mode)?;
+    }
+
+    Ok(report)
+}
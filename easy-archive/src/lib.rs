@@ -11,7 +11,13 @@
 /// - `tar-xz` - XZ-compressed TAR
 /// - `tar-bz` - Bzip2-compressed TAR
 /// - `tar-zstd` - Zstd-compressed TAR
+/// - `tar-lz4` - LZ4-compressed TAR
+/// - `tar-lzma` - LZMA-compressed TAR (legacy LZMA-alone)
+/// - `tar-lz` - Lzip-compressed TAR
+/// - `tar-z` - TAR compressed with Unix `compress` (decode only)
 /// - `zip` - ZIP format
+/// - `ar` - Unix ar archives and Debian packages
+/// - `cpio` - cpio archives and RPM payloads
 /// - `default` - Enables all formats
 ///
 /// # Example
@@ -37,37 +43,114 @@
 /// ```
 // Module declarations
 pub mod archive;
+#[cfg(all(feature = "decode", feature = "encode"))]
+pub mod convert;
+pub mod diff;
 pub mod error;
+#[cfg(feature = "decode")]
+pub mod extract;
+#[cfg(feature = "decode")]
+pub mod manifest;
 pub mod traits;
 pub mod types;
 pub mod utils;
+#[cfg(feature = "decode")]
+pub mod verify;
+pub mod volume;
+#[cfg(feature = "encode")]
+pub mod writer;
 
 // Re-export commonly used types and functions
+pub use diff::{Change, Difference, diff};
 pub use error::{ArchiveError, Result};
-pub use types::{File, Fmt};
+pub use types::{
+    DecodeLimits, DecodeOptions, EncodeOptions, EntryInfo, File, Fmt, Reproducible, ZipMethod,
+};
 pub use utils::{clean, human_size, mode_to_string};
 
 #[cfg(feature = "decode")]
 pub use traits::Decode;
 
+#[cfg(feature = "decode")]
+pub use extract::{ExtractOptions, ExtractReport, extract};
+
 #[cfg(feature = "encode")]
 pub use traits::Encode;
 
+#[cfg(all(feature = "tar", feature = "decode"))]
+pub use archive::tar::{Entries, Entry};
+
 #[cfg(feature = "encode")]
 pub use utils::check_duplicate_files;
 
+#[cfg(feature = "encode")]
+pub use writer::ArchiveWriter;
+
+#[cfg(feature = "decode")]
+pub use volume::Volumes;
+
+#[cfg(feature = "decode")]
+pub use verify::{VerifyFailure, VerifyReport};
+
+#[cfg(feature = "decode")]
+pub use manifest::{HashAlgorithm, Manifest, ManifestEntry};
+
+#[cfg(feature = "encode")]
+pub use volume::VolumeWriter;
+
 #[cfg(all(feature = "encode", feature = "decode"))]
 pub use traits::Archive;
 
+#[cfg(all(feature = "encode", feature = "decode"))]
+pub use convert::{ConvertReport, DroppedMetadata, Metadata};
+
+#[cfg(all(feature = "zip", feature = "encode", feature = "decode"))]
+pub use archive::zip::ZipEditor;
+
 #[cfg(test)]
 mod test {
-    use crate::{File, types::Fmt};
+    use crate::{ArchiveError, EncodeOptions, EntryInfo, File, ZipMethod, types::Fmt};
+    use std::io::Read;
     use strum::IntoEnumIterator;
 
+    /// A deflated zip entry of `size` zeros whose headers declare only 10 bytes
+    #[cfg(feature = "zip")]
+    fn lying_zip(size: usize) -> Vec<u8> {
+        let files = vec![File::new(
+            "zeros.bin".to_string(),
+            vec![0; size],
+            None,
+            false,
+            None,
+        )];
+        let mut zip = Fmt::Zip.encode(files).unwrap();
+        for (signature, offset) in [(b"PK\x03\x04", 22), (b"PK\x01\x02", 24)] {
+            let at = zip.windows(4).position(|w| w == signature).unwrap() + offset;
+            zip[at..at + 4].copy_from_slice(&10u32.to_le_bytes());
+        }
+        zip
+    }
+
+    /// The content of the sparse files in `assets/sparse`
+    fn sparse_files() -> [(&'static str, Vec<u8>); 2] {
+        let mut holes = vec![0; 1 << 20];
+        holes[65536..][..600].copy_from_slice(&b"middle".repeat(100));
+        holes[(1 << 20) - 4..].copy_from_slice(b"end\n");
+        let mut many = vec![0; 16 * 65536];
+        for i in 0..16 {
+            let chunk = format!("chunk {}\n", i);
+            many[i * 65536 + 100..][..chunk.len()].copy_from_slice(chunk.as_bytes());
+        }
+        [("sparse/holes.bin", holes), ("sparse/many.bin", many)]
+    }
+
     #[test]
     fn test_decode() {
         for name in std::fs::read_dir("../assets").unwrap() {
             let path = name.unwrap().path();
+            if path.is_dir() {
+                continue;
+            }
             let buffer = std::fs::read(&path).unwrap();
             let fmt = Fmt::guess(&path.to_string_lossy()).unwrap();
             let files = fmt.decode(buffer).unwrap();
@@ -77,26 +160,2012 @@ mod test {
                 .unwrap();
             assert!(!dist.buffer.is_empty());
         }
+
+        // GNU sparse files, in the old GNU format and the PAX formats 0.0 to 1.0
+        for name in std::fs::read_dir("../assets/sparse").unwrap() {
+            let path = name.unwrap().path();
+            let files = Fmt::TarGz.decode(std::fs::read(&path).unwrap()).unwrap();
+            assert_eq!(files.len(), 3, "{:?}", path);
+            for (name, content) in sparse_files() {
+                let file = files.iter().find(|f| f.path == name).unwrap();
+                assert!(file.buffer == content, "{:?}: {}", path, name);
+            }
+        }
     }
 
-    use std::path::PathBuf;
+    #[test]
+    fn test_entries() {
+        for name in std::fs::read_dir("../assets").unwrap() {
+            let path = name.unwrap().path();
+            if path.is_dir() {
+                continue;
+            }
+            let fmt = Fmt::guess(&path.to_string_lossy()).unwrap();
+            let reader = std::fs::File::open(&path).unwrap();
+            let Ok(mut entries) = fmt.entries(reader) else {
+                continue;
+            };
+
+            let mut count = 0;
+            let mut manifest = String::new();
+            while let Some(mut entry) = entries.next_entry().unwrap() {
+                count += 1;
+                if entry.info().path == "mujs-build-0.0.11/dist-manifest.json" {
+                    entry.read_to_string(&mut manifest).unwrap();
+                    assert_eq!(manifest.len() as u64, entry.info().size);
+                }
+            }
+            assert!(!manifest.is_empty());
+
+            let files = fmt.decode(std::fs::read(&path).unwrap()).unwrap();
+            assert_eq!(count, files.len());
+        }
+
+        // Sparse holes are expanded while reading, and skipped unexpanded
+        let [(holes, content), (many, _)] = sparse_files();
+        for name in std::fs::read_dir("../assets/sparse").unwrap() {
+            let path = name.unwrap().path();
+            let reader = std::fs::File::open(&path).unwrap();
+            let mut entries = Fmt::TarGz.entries(reader).unwrap();
+            let mut count = 0;
+            while let Some(mut entry) = entries.next_entry().unwrap() {
+                count += 1;
+                if entry.info().path == holes {
+                    assert_eq!(entry.info().size, content.len() as u64);
+                    let mut buffer = Vec::new();
+                    entry.read_to_end(&mut buffer).unwrap();
+                    assert!(buffer == content, "{:?}", path);
+                } else if entry.info().path == many {
+                    let mut buffer = [0; 200];
+                    entry.read_exact(&mut buffer).unwrap();
+                    assert_eq!(&buffer[100..108], b"chunk 0\n");
+                }
+            }
+            assert_eq!(count, 3, "{:?}", path);
+        }
+    }
 
     #[test]
-    fn encode_decode() {
-        for fmt in Fmt::iter() {
-            // Skip TarBz if not fully implemented
-            #[cfg(feature = "tar-bz")]
-            if fmt == Fmt::TarBz {
-                // TarBz encoding is now implemented
+    fn test_list() {
+        for name in std::fs::read_dir("../assets").unwrap() {
+            let path = name.unwrap().path();
+            if path.is_dir() {
                 continue;
             }
+            let fmt = Fmt::guess(&path.to_string_lossy()).unwrap();
+            let list = fmt.list(std::fs::File::open(&path).unwrap()).unwrap();
+            let files = fmt.decode(std::fs::read(&path).unwrap()).unwrap();
+
+            assert_eq!(list.len(), files.len(), "{:?}", path);
+            for (info, file) in list.iter().zip(&files) {
+                assert_eq!(info.path, file.path);
+                assert_eq!(info.is_dir, file.is_dir);
+                assert_eq!(info.size, file.buffer.len() as u64, "{}", info.path);
+            }
+        }
+
+        for fmt in Fmt::iter().filter(|fmt| !fmt.is_single_file() && fmt.can_encode()) {
+            let files = vec![File {
+                path: "hello.txt".to_string(),
+                buffer: b"Hello, world!".to_vec(),
+                last_modified: Some(1700000000),
+                ..Default::default()
+            }];
+            let archive = match fmt.encode(files) {
+                Ok(archive) => archive,
+                Err(ArchiveError::UnsupportedFormat(_)) => continue,
+                Err(e) => panic!("{:?} encode error: {}", fmt, e),
+            };
+
+            let list = fmt.list(std::io::Cursor::new(archive)).unwrap();
+            assert_eq!(list.len(), 1, "{:?}", fmt);
+            assert_eq!(list[0].path, "hello.txt");
+            assert_eq!(list[0].size, 13);
+            assert_eq!(list[0].last_modified, Some(1700000000), "{:?}", fmt);
+        }
+    }
+
+    #[test]
+    fn test_writer() {
+        for fmt in Fmt::iter().filter(|fmt| !fmt.is_single_file()) {
+            let mut output = Vec::new();
+            let mut writer = match fmt.writer(&mut output) {
+                Ok(w) => w,
+                Err(ArchiveError::UnsupportedFormat(_)) => continue,
+                Err(e) => panic!("{:?} writer error: {}", fmt, e),
+            };
+
+            let content = b"streamed content".repeat(1000);
+            let dir = EntryInfo {
+                path: "dir".to_string(),
+                is_dir: true,
+                ..Default::default()
+            };
+            let info = EntryInfo {
+                path: "dir/data.bin".to_string(),
+                size: content.len() as u64,
+                mode: Some(0o644),
+                last_modified: Some(1700000000),
+                ..Default::default()
+            };
+            writer.add(&dir, std::io::empty()).unwrap();
+            writer.add(&info, &content[..]).unwrap();
+            assert!(writer.add(&info, &content[..]).is_err());
+            writer.finish().unwrap();
+
+            let files = fmt.decode(output).unwrap();
+            let file = files.iter().find(|f| f.path == "dir/data.bin").unwrap();
+            assert_eq!(file.buffer, content, "{:?}", fmt);
+            assert_eq!(file.mode, Some(0o644), "{:?}", fmt);
+            // ar archives are flat and skip directories
+            assert!(
+                fmt == Fmt::Ar || files.iter().any(|f| f.path == "dir" && f.is_dir),
+                "{:?}",
+                fmt
+            );
+        }
+
+        // Seekable destinations receive 7z archives in place, after any prefix
+        #[cfg(feature = "7z")]
+        {
+            let file = File::new(
+                "data.bin".to_string(),
+                b"7z".repeat(1000),
+                None,
+                false,
+                None,
+            );
+            let mut output = std::io::Cursor::new(b"prefix".to_vec());
+            output.set_position(6);
+            let mut writer = Fmt::SevenZip.writer_seekable(&mut output).unwrap();
+            writer.add_file(&file).unwrap();
+            writer.finish().unwrap();
+
+            let output = output.into_inner();
+            assert!(output.starts_with(b"prefix"));
+            let files = Fmt::SevenZip.decode(output[6..].to_vec()).unwrap();
+            assert_eq!(files[0].buffer, file.buffer);
+        }
+    }
+
+    #[cfg(all(
+        feature = "tar",
+        feature = "gz",
+        feature = "zip",
+        feature = "7z",
+        feature = "ar"
+    ))]
+    #[test]
+    fn test_convert() {
+        use crate::{DecodeLimits, DecodeOptions, Metadata};
+        use std::io::Cursor;
+
+        let content = b"converted content".repeat(1000);
+        let entries = [
+            EntryInfo {
+                path: "dir".to_string(),
+                is_dir: true,
+                mode: Some(0o750),
+                last_modified: Some(1700000000),
+                ..Default::default()
+            },
+            EntryInfo {
+                path: "dir/data.bin".to_string(),
+                size: content.len() as u64,
+                mode: Some(0o640),
+                last_modified: Some(1700000000),
+                uid: Some(1000),
+                gid: Some(1000),
+                ..Default::default()
+            },
+            EntryInfo {
+                path: "link".to_string(),
+                mode: Some(0o777),
+                last_modified: Some(1700000000),
+                link: Some("dir/data.bin".to_string()),
+                ..Default::default()
+            },
+        ];
+        let mut tar = Vec::new();
+        let mut writer = Fmt::Tar.writer(&mut tar).unwrap();
+        for info in &entries {
+            writer.add(info, &content[..]).unwrap();
+        }
+        writer.finish().unwrap();
+
+        for fmt in Fmt::iter().filter(|fmt| !fmt.is_single_file() && fmt.can_encode()) {
+            let mut output = Vec::new();
+            let result = Fmt::convert(Fmt::Tar, Cursor::new(&tar), fmt, &mut output);
+            if fmt == Fmt::Ar {
+                assert!(matches!(result, Err(ArchiveError::EncodeFailed { .. })));
+                continue;
+            }
+            let report = result.unwrap();
+            assert_eq!(report.entries, entries.len());
+
+            let mut dropped: Vec<_> = report
+                .dropped
+                .iter()
+                .map(|dropped| (dropped.path.as_str(), dropped.metadata))
+                .collect();
+            dropped.sort_by_key(|(path, _)| *path);
+            let expected = match fmt {
+                Fmt::Zip => vec![("dir", Metadata::Mode), ("dir/data.bin", Metadata::Owner)],
+                Fmt::SevenZip => vec![("dir/data.bin", Metadata::Owner)],
+                _ => vec![],
+            };
+            assert_eq!(dropped, expected, "{:?}", fmt);
+
+            // The converted archive converts back with the same content
+            let mut back = Vec::new();
+            fmt.convert(Cursor::new(&output), Fmt::Tar, &mut back)
+                .unwrap();
+            for archive in [(fmt, output), (Fmt::Tar, back)] {
+                let files = archive.0.decode(archive.1).unwrap();
+                let file = files.iter().find(|f| f.path == "dir/data.bin").unwrap();
+                assert_eq!(file.buffer, content, "{:?}", fmt);
+                assert_eq!(file.last_modified, Some(1700000000), "{:?}", fmt);
+                let link = files.iter().find(|f| f.path == "link").unwrap();
+                assert_eq!(link.link.as_deref(), Some("dir/data.bin"), "{:?}", fmt);
+            }
+        }
+
+        // Single-file formats hold exactly one file
+        let gz = Fmt::Gz
+            .encode(vec![File::new(
+                "notes.txt".to_string(),
+                content.clone(),
+                None,
+                false,
+                Some(1700000000),
+            )])
+            .unwrap();
+        let mut output = Vec::new();
+        let report = Fmt::Gz
+            .convert(Cursor::new(gz), Fmt::Tar, &mut output)
+            .unwrap();
+        assert_eq!(report.entries, 1);
+        let files = Fmt::Tar.decode(output).unwrap();
+        assert_eq!(files[0].path, "notes.txt");
+        assert_eq!(files[0].buffer, content);
+        assert!(matches!(
+            Fmt::Tar.convert(Cursor::new(&tar), Fmt::Gz, Vec::new()),
+            Err(ArchiveError::EncodeFailed { .. })
+        ));
+
+        // The limits apply to the source entries
+        let options = DecodeOptions {
+            limits: DecodeLimits {
+                max_entry_size: Some(100),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let result = Fmt::Tar.convert_with(
+            Cursor::new(&tar),
+            Fmt::Zip,
+            Vec::new(),
+            &options,
+            &EncodeOptions::default(),
+        );
+        assert!(matches!(result, Err(ArchiveError::EntryTooLarge { .. })));
+
+        // The limits apply to the data actually inflated, not the declared size
+        let options = DecodeOptions {
+            limits: DecodeLimits {
+                max_entry_size: Some(1000),
+                max_total_size: Some(1000),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let zip = lying_zip(1 << 20);
+        let mut output = Vec::new();
+        let result = Fmt::Zip.convert_with(
+            Cursor::new(&zip),
+            Fmt::Zip,
+            &mut output,
+            &options,
+            &EncodeOptions::default(),
+        );
+        assert!(matches!(result, Err(ArchiveError::EntryTooLarge { .. })));
+        let result = Fmt::Zip.convert(Cursor::new(&zip), Fmt::Tar, Vec::new());
+        assert!(matches!(
+            result,
+            Err(ArchiveError::SizeMismatch {
+                declared: 10,
+                actual: 1048576,
+                ..
+            })
+        ));
+    }
+
+    #[cfg(all(feature = "tar-gz", feature = "zip"))]
+    #[test]
+    fn test_diff() {
+        use crate::{Change, Difference, diff};
+        use std::io::Cursor;
+
+        let file = |path: &str, content: &[u8], mode: u32, time: u64| {
+            File::new(
+                path.to_string(),
+                content.to_vec(),
+                Some(mode),
+                false,
+                Some(time),
+            )
+        };
+        let time = 1_700_000_000;
+        let old = vec![
+            File::new("bin".to_string(), vec![], Some(0o755), true, Some(time)),
+            file("bin/tool", b"v1", 0o644, time),
+            file("README", b"readme", 0o644, time),
+            file("CHANGELOG", b"old", 0o644, time),
+            file("latest", b"bin/tool", 0o644, time),
+        ];
+        let new = vec![
+            File::new("bin".to_string(), vec![], Some(0o755), true, Some(time)),
+            file("bin/tool", b"v2", 0o755, time),
+            file("README", b"readme", 0o644, time + 60),
+            file("LICENSE", b"MIT", 0o644, time),
+            File::new_symlink(
+                "latest".to_string(),
+                "bin/tool".to_string(),
+                Some(0o644),
+                Some(time),
+            ),
+        ];
+
+        let change = |path: &str, change| Difference {
+            path: path.to_string(),
+            change,
+        };
+        let expected = vec![
+            change("CHANGELOG", Change::Removed),
+            change("LICENSE", Change::Added),
+            change(
+                "README",
+                Change::ModificationTime {
+                    old: time,
+                    new: time + 60,
+                },
+            ),
+            change("bin/tool", Change::Modified),
+            change(
+                "bin/tool",
+                Change::Mode {
+                    old: 0o644,
+                    new: 0o755,
+                },
+            ),
+            change("latest", Change::Modified),
+        ];
+        assert_eq!(diff(&old, &new), expected);
+        assert!(diff(&old, &old).is_empty());
+
+        // Streaming, across formats
+        let zip = Fmt::Zip.encode(old.clone()).unwrap();
+        let tar_gz = Fmt::TarGz.encode(new.clone()).unwrap();
+        let differences = Fmt::Zip
+            .diff(Cursor::new(&zip), Fmt::TarGz, Cursor::new(&tar_gz))
+            .unwrap();
+        assert_eq!(differences, expected);
+
+        // Zip timestamps are rounded to two seconds
+        let tar_gz = Fmt::TarGz
+            .encode(
+                old.iter()
+                    .cloned()
+                    .map(|file| File {
+                        last_modified: Some(time + 1),
+                        ..file
+                    })
+                    .collect(),
+            )
+            .unwrap();
+        let differences = Fmt::Zip
+            .diff(Cursor::new(&zip), Fmt::TarGz, Cursor::new(&tar_gz))
+            .unwrap();
+        assert!(differences.is_empty(), "{:?}", differences);
+
+        // Entries are compared on the data actually stored
+        let zip = lying_zip(1 << 20);
+        assert!(matches!(
+            Fmt::Zip.diff(Cursor::new(&zip), Fmt::Zip, Cursor::new(&zip)),
+            Err(ArchiveError::SizeMismatch { .. })
+        ));
+    }
+
+    #[cfg(all(feature = "tar-gz", feature = "zip", feature = "7z"))]
+    #[test]
+    fn test_verify() {
+        use std::io::Cursor;
+
+        let files = vec![
+            File::new(
+                "a.txt".to_string(),
+                b"first entry".repeat(64),
+                None,
+                false,
+                None,
+            ),
+            File::new(
+                "b.txt".to_string(),
+                b"second entry".repeat(64),
+                None,
+                false,
+                None,
+            ),
+        ];
+        let flip = |mut archive: Vec<u8>, at: usize| {
+            archive[at] ^= 0xff;
+            archive
+        };
+        let find = |archive: &[u8], needle: &[u8]| {
+            archive
+                .windows(needle.len())
+                .position(|window| window == needle)
+                .unwrap()
+        };
+
+        for fmt in [Fmt::Zip, Fmt::Tar, Fmt::TarGz, Fmt::SevenZip] {
+            let archive = fmt.encode(files.clone()).unwrap();
+            let report = fmt.verify(Cursor::new(&archive)).unwrap();
+            assert!(report.is_ok(), "{:?}: {:?}", fmt, report.failures);
+            assert_eq!(report.entries, 2);
+        }
+
+        // A zip entry failing its CRC-32, the other entry still passes
+        let options = EncodeOptions {
+            method: ZipMethod::Stored,
+            ..Default::default()
+        };
+        let zip = Fmt::Zip.encode_with(files.clone(), &options).unwrap();
+        let at = find(&zip, b"second entry");
+        let report = Fmt::Zip.verify(Cursor::new(flip(zip, at))).unwrap();
+        assert_eq!(report.entries, 2);
+        assert_eq!(report.failures.len(), 1);
+        assert_eq!(report.failures[0].path.as_deref(), Some("b.txt"));
+
+        // A tar header failing its checksum
+        let tar = Fmt::Tar.encode(files.clone()).unwrap();
+        let at = find(&tar, b"b.txt");
+        let report = Fmt::Tar.verify(Cursor::new(flip(tar, at))).unwrap();
+        assert_eq!(report.entries, 1);
+        assert_eq!(report.failures.len(), 1);
+        assert!(report.failures[0].path.is_none());
+
+        // The gzip trailer, read past the end of the tar archive
+        let tar_gz = Fmt::TarGz.encode(files.clone()).unwrap();
+        let at = tar_gz.len() - 8;
+        let report = Fmt::TarGz.verify(Cursor::new(flip(tar_gz, at))).unwrap();
+        assert_eq!(report.entries, 2);
+        assert_eq!(report.failures.len(), 1);
+        assert!(report.failures[0].path.is_none());
+
+        // A zip entry inflating to more data than it declares
+        let zip = lying_zip(1 << 20);
+        let report = Fmt::Zip.verify(Cursor::new(&zip)).unwrap();
+        assert_eq!(report.entries, 1);
+        assert_eq!(report.failures.len(), 1);
+        assert_eq!(report.failures[0].path.as_deref(), Some("zeros.bin"));
+        let options = crate::DecodeOptions {
+            limits: crate::DecodeLimits {
+                max_entry_size: Some(1000),
+                max_total_size: Some(1000),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert!(matches!(
+            Fmt::Zip.verify_with(Cursor::new(&zip), &options),
+            Err(ArchiveError::EntryTooLarge { .. })
+        ));
+
+        // Damaged 7z packed data
+        let seven_zip = Fmt::SevenZip.encode(files.clone()).unwrap();
+        let report = Fmt::SevenZip
+            .verify(Cursor::new(flip(seven_zip, 48)))
+            .unwrap();
+        assert!(!report.is_ok());
+
+        // An encrypted archive cannot be checked without its password
+        let options = EncodeOptions {
+            password: Some("secret".to_string()),
+            ..Default::default()
+        };
+        let zip = Fmt::Zip.encode_with(files, &options).unwrap();
+        assert!(matches!(
+            Fmt::Zip.verify(Cursor::new(&zip)),
+            Err(ArchiveError::PasswordRequired)
+        ));
+    }
+
+    #[cfg(all(feature = "tar-gz", feature = "blake3"))]
+    #[test]
+    fn test_manifest() {
+        use crate::{Change, Difference, HashAlgorithm, Manifest};
+        use std::io::Cursor;
+
+        let hello = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+        let files = vec![
+            File::new("docs".to_string(), vec![], None, true, None),
+            File::new(
+                "docs/hello.txt".to_string(),
+                b"hello".to_vec(),
+                None,
+                false,
+                None,
+            ),
+            File::new("odd\nname".to_string(), b"odd".to_vec(), None, false, None),
+            File::new_symlink(
+                "latest".to_string(),
+                "docs/hello.txt".to_string(),
+                None,
+                None,
+            ),
+        ];
+
+        let manifest = Manifest::from_files(&files, HashAlgorithm::Sha256);
+        let paths: Vec<&str> = manifest.entries.iter().map(|e| e.path.as_str()).collect();
+        assert_eq!(paths, ["docs/hello.txt", "odd\nname"]);
+        assert_eq!(manifest.entries[0].digest, hello);
+        let text = manifest.to_string();
+        assert!(text.starts_with(&format!("{}  docs/hello.txt\n\\", hello)));
+        assert_eq!(
+            Manifest::parse(&text, HashAlgorithm::Sha256).unwrap(),
+            manifest
+        );
+
+        // Streaming, with either algorithm
+        let tar_gz = Fmt::TarGz.encode(files.clone()).unwrap();
+        for algorithm in [HashAlgorithm::Sha256, HashAlgorithm::Blake3] {
+            let streamed = Fmt::TarGz
+                .manifest(Cursor::new(&tar_gz), algorithm)
+                .unwrap();
+            assert_eq!(streamed, Manifest::from_files(&files, algorithm));
+        }
+        assert_eq!(
+            Manifest::from_files(&files, HashAlgorithm::Blake3).entries[0].digest,
+            "ea8f163db38682925e4491c5e58d4bb3506ef8c14eb78a86e908c5624a67200f"
+        );
+
+        // Binary mode markers and uppercase digests, as other tools write them
+        let sums = format!(
+            "{} *docs/hello.txt\n\n{}  missing.txt\n",
+            hello.to_uppercase(),
+            hello
+        );
+        let expected = Manifest::parse(&sums, HashAlgorithm::Sha256).unwrap();
+        let change = |path: &str, change| Difference {
+            path: path.to_string(),
+            change,
+        };
+        assert_eq!(
+            Fmt::TarGz
+                .verify_manifest(Cursor::new(&tar_gz), &expected)
+                .unwrap(),
+            vec![
+                change("missing.txt", Change::Removed),
+                change("odd\nname", Change::Added),
+            ]
+        );
+        let tampered = Fmt::TarGz
+            .encode(vec![File::new(
+                "docs/hello.txt".to_string(),
+                b"hello!".to_vec(),
+                None,
+                false,
+                None,
+            )])
+            .unwrap();
+        let expected =
+            Manifest::parse(&format!("{}  docs/hello.txt", hello), HashAlgorithm::Sha256).unwrap();
+        assert_eq!(
+            Fmt::TarGz
+                .verify_manifest(Cursor::new(&tampered), &expected)
+                .unwrap(),
+            vec![change("docs/hello.txt", Change::Modified)]
+        );
+
+        // The digest covers the data actually stored, not the declared size
+        #[cfg(feature = "zip")]
+        assert!(matches!(
+            Fmt::Zip.manifest(Cursor::new(lying_zip(1 << 20)), HashAlgorithm::Sha256),
+            Err(ArchiveError::SizeMismatch { .. })
+        ));
+
+        for invalid in ["abc  file", hello, &format!("{}file", hello)] {
+            assert!(matches!(
+                Manifest::parse(invalid, HashAlgorithm::Sha256),
+                Err(ArchiveError::InvalidManifest(_))
+            ));
+        }
+    }
+
+    #[cfg(all(feature = "tar-gz", feature = "zip", feature = "7z"))]
+    #[test]
+    fn test_reproducible() {
+        use crate::Reproducible;
+
+        let epoch = 1_600_000_000;
+        let options = EncodeOptions {
+            reproducible: Some(Reproducible { mtime: Some(epoch) }),
+            ..Default::default()
+        };
+        let file = |path: &str, mode: u32, time: Option<u64>| {
+            File::new(
+                path.to_string(),
+                path.as_bytes().to_vec(),
+                Some(mode),
+                false,
+                time,
+            )
+        };
+        // The same tree, checked out at different times by different users
+        let first = vec![
+            File::new(
+                "bin".to_string(),
+                vec![],
+                Some(0o700),
+                true,
+                Some(1_700_000_000),
+            ),
+            file("bin/tool", 0o700, Some(1_700_000_000)),
+            file("docs/guide.md", 0o600, Some(1_700_000_100)),
+            file("README", 0o600, Some(1_700_000_000)),
+            File::new_symlink("latest".to_string(), "bin/tool".to_string(), None, None),
+        ];
+        let second = vec![
+            File::new_symlink(
+                "latest".to_string(),
+                "bin/tool".to_string(),
+                Some(0o755),
+                None,
+            ),
+            file("README", 0o664, None),
+            file("docs/guide.md", 0o644, Some(1_800_000_000)),
+            file("bin/tool", 0o755, Some(1_800_000_000)),
+            File::new("bin".to_string(), vec![], Some(0o755), true, None),
+        ];
+
+        for fmt in [Fmt::Tar, Fmt::TarGz, Fmt::TarXz, Fmt::Zip, Fmt::SevenZip] {
+            let archive = fmt.encode_with(first.clone(), &options).unwrap();
+            assert_eq!(
+                archive,
+                fmt.encode_with(second.clone(), &options).unwrap(),
+                "{:?}",
+                fmt
+            );
+
+            let listed = fmt.list(std::io::Cursor::new(&archive)).unwrap();
+            let paths: Vec<&str> = listed
+                .iter()
+                .map(|info| info.path.trim_end_matches('/'))
+                .filter(|path| *path != "docs")
+                .collect();
+            // Zip archives list their directories first
+            if fmt != Fmt::Zip {
+                assert_eq!(
+                    paths,
+                    ["README", "bin", "bin/tool", "docs/guide.md", "latest"]
+                );
+            }
+            for info in &listed {
+                assert_eq!(info.last_modified, Some(epoch), "{:?} {}", fmt, info.path);
+                // Zip directories are written without permissions
+                if fmt == Fmt::Zip && info.is_dir {
+                    continue;
+                }
+                let mode = match info.path.as_str() {
+                    "README" | "docs/guide.md" => 0o644,
+                    "latest" => 0o777,
+                    _ => 0o755,
+                };
+                assert_eq!(info.mode, Some(mode), "{:?} {}", fmt, info.path);
+            }
+        }
+
+        // Streamed entries keep their order, but lose their owners
+        let mut tar = Vec::new();
+        let mut writer = Fmt::Tar.writer_with(&mut tar, &options).unwrap();
+        let info = EntryInfo {
+            path: "data.bin".to_string(),
+            size: 4,
+            mode: Some(0o600),
+            last_modified: Some(1_700_000_000),
+            uid: Some(1000),
+            gid: Some(1000),
+            ..Default::default()
+        };
+        writer.add(&info, &b"data"[..]).unwrap();
+        writer.finish().unwrap();
+        let mut entries = Fmt::Tar.entries(&tar[..]).unwrap();
+        let entry = entries.next_entry().unwrap().unwrap();
+        let info = entry.info();
+        assert_eq!((info.uid, info.gid), (Some(0), Some(0)));
+        assert_eq!(info.mode, Some(0o644));
+        assert_eq!(info.last_modified, Some(epoch));
+    }
+
+    #[test]
+    fn test_encode_options() {
+        let files = vec![File {
+            path: "data.txt".to_string(),
+            buffer: b"options content ".repeat(4096),
+            ..Default::default()
+        }];
+
+        let mut cases = vec![];
+        for fmt in Fmt::iter() {
+            for level in [0, 1, 9, 100] {
+                cases.push((
+                    fmt,
+                    EncodeOptions {
+                        level: Some(level),
+                        threads: Some(2),
+                        ..Default::default()
+                    },
+                ));
+            }
+        }
+        #[cfg(feature = "zip")]
+        for method in ZipMethod::iter().filter(|m| *m != ZipMethod::Lzma) {
+            for level in [None, Some(1)] {
+                cases.push((
+                    Fmt::Zip,
+                    EncodeOptions {
+                        level,
+                        method,
+                        ..Default::default()
+                    },
+                ));
+            }
+        }
+
+        for (fmt, options) in cases {
+            let archive = match fmt.encode_with(files.clone(), &options) {
+                Ok(archive) => archive,
+                Err(ArchiveError::UnsupportedFormat(_)) => continue,
+                Err(e) => panic!("{:?} {:?} encode error: {}", fmt, options, e),
+            };
+            let decoded = fmt.decode(archive).unwrap();
+            assert_eq!(decoded.len(), 1, "{:?} {:?}", fmt, options);
+            assert_eq!(
+                decoded[0].buffer, files[0].buffer,
+                "{:?} {:?}",
+                fmt, options
+            );
+        }
+
+        #[cfg(feature = "zip")]
+        {
+            let options = EncodeOptions {
+                method: ZipMethod::Lzma,
+                ..Default::default()
+            };
+            assert!(matches!(
+                Fmt::Zip.encode_with(files.clone(), &options),
+                Err(ArchiveError::UnsupportedFormat(_))
+            ));
+            assert_eq!("deflate".parse::<ZipMethod>().unwrap(), ZipMethod::Deflate);
+            assert_eq!(ZipMethod::Stored.to_string(), "stored");
+        }
+    }
+
+    #[test]
+    fn test_symlink() {
+        let long_target = format!("{}/libx.so.1", "deep".repeat(40));
+        let files = vec![
+            File::new(
+                "lib/libx.so.1".to_string(),
+                b"lib".to_vec(),
+                Some(0o644),
+                false,
+                None,
+            ),
+            File::new_symlink(
+                "lib/libx.so".to_string(),
+                "libx.so.1".to_string(),
+                None,
+                Some(1700000000),
+            ),
+            File::new_symlink("lib/long.so".to_string(), long_target.clone(), None, None),
+        ];
+
+        // ar archives cannot store symbolic links
+        for fmt in
+            Fmt::iter().filter(|fmt| !fmt.is_single_file() && fmt.can_encode() && *fmt != Fmt::Ar)
+        {
+            let mut output = Vec::new();
+            let mut writer = fmt.writer(&mut output).unwrap();
+            for file in &files {
+                writer.add_file(file).unwrap();
+            }
+            writer.finish().unwrap();
+
+            for archive in [fmt.encode(files.clone()).unwrap(), output] {
+                let decoded = fmt.decode(archive.clone()).unwrap();
+                let link = decoded.iter().find(|f| f.path == "lib/libx.so").unwrap();
+                assert_eq!(link.link.as_deref(), Some("libx.so.1"), "{:?}", fmt);
+                assert!(link.buffer.is_empty() && !link.is_dir, "{:?}", fmt);
+                let long = decoded.iter().find(|f| f.path == "lib/long.so").unwrap();
+                assert_eq!(long.link.as_ref(), Some(&long_target), "{:?}", fmt);
+                let lib = decoded.iter().find(|f| f.path == "lib/libx.so.1").unwrap();
+                assert_eq!((lib.link.as_ref(), &lib.buffer[..]), (None, &b"lib"[..]));
+
+                let list = fmt.list(std::io::Cursor::new(archive)).unwrap();
+                let info = list.iter().find(|i| i.path == "lib/libx.so").unwrap();
+                assert_eq!(info.link.as_deref(), Some("libx.so.1"), "{:?}", fmt);
+                assert_eq!(info.size, 0, "{:?}", fmt);
+            }
+        }
+    }
+
+    #[test]
+    fn test_decode_limits() {
+        use crate::{DecodeLimits, DecodeOptions};
+
+        let files = vec![
+            File::new("zeros.bin".to_string(), vec![0; 1 << 20], None, false, None),
+            File::new(
+                "small.txt".to_string(),
+                b"small".to_vec(),
+                None,
+                false,
+                None,
+            ),
+        ];
+        let decode = |fmt: Fmt, archive: &Vec<u8>, limits: DecodeLimits| {
+            let options = DecodeOptions {
+                limits,
+                ..Default::default()
+            };
+            fmt.decode_with(archive.clone(), &options)
+        };
+
+        for fmt in Fmt::iter().filter(|fmt| !fmt.is_single_file() && fmt.can_encode()) {
+            let archive = fmt.encode(files.clone()).unwrap();
+
+            let limits = DecodeLimits {
+                max_entries: Some(1),
+                ..Default::default()
+            };
+            let result = decode(fmt, &archive, limits);
+            assert!(
+                matches!(result, Err(ArchiveError::TooManyEntries { limit: 1 })),
+                "{:?}",
+                fmt
+            );
+
+            let limits = DecodeLimits {
+                max_entry_size: Some(1000),
+                ..Default::default()
+            };
+            let result = decode(fmt, &archive, limits);
+            assert!(
+                matches!(&result, Err(ArchiveError::EntryTooLarge { path, limit: 1000 }) if path == "zeros.bin"),
+                "{:?}",
+                fmt
+            );
+
+            let limits = DecodeLimits {
+                max_total_size: Some((1 << 20) + 4),
+                ..Default::default()
+            };
+            let result = decode(fmt, &archive, limits);
+            assert!(
+                matches!(result, Err(ArchiveError::TotalSizeExceeded { .. })),
+                "{:?}",
+                fmt
+            );
+
+            // Uncompressed formats cannot exceed a ratio
+            if !matches!(fmt, Fmt::Tar | Fmt::Ar | Fmt::Cpio) {
+                let limits = DecodeLimits {
+                    max_ratio: Some(10),
+                    ..Default::default()
+                };
+                let result = decode(fmt, &archive, limits);
+                assert!(
+                    matches!(result, Err(ArchiveError::RatioExceeded { limit: 10 })),
+                    "{:?}",
+                    fmt
+                );
+            }
+
+            let limits = DecodeLimits {
+                max_total_size: Some((1 << 20) + 5),
+                max_entries: Some(2),
+                max_entry_size: Some(1 << 20),
+                max_ratio: Some(10_000),
+            };
+            let decoded = decode(fmt, &archive, limits).unwrap();
+            assert_eq!(decoded.len(), 2, "{:?}", fmt);
+        }
+    }
+
+    #[cfg(feature = "7z")]
+    #[test]
+    fn test_seven_zip_password() {
+        use crate::DecodeOptions;
+
+        let files = vec![File::new(
+            "secret.txt".to_string(),
+            b"top secret ".repeat(100),
+            None,
+            false,
+            None,
+        )];
+        let options = EncodeOptions {
+            password: Some("hunter2".to_string()),
+            ..Default::default()
+        };
+        let password = |password: &str| DecodeOptions {
+            password: Some(password.to_string()),
+            ..Default::default()
+        };
+
+        let mut streamed = Vec::new();
+        let mut writer = Fmt::SevenZip.writer_with(&mut streamed, &options).unwrap();
+        writer.add_file(&files[0]).unwrap();
+        writer.finish().unwrap();
+
+        for archive in [
+            Fmt::SevenZip.encode_with(files.clone(), &options).unwrap(),
+            streamed,
+        ] {
+            assert!(matches!(
+                Fmt::SevenZip.decode(archive.clone()),
+                Err(ArchiveError::PasswordRequired)
+            ));
+            assert!(matches!(
+                Fmt::SevenZip.decode_with(archive.clone(), &password("wrong")),
+                Err(ArchiveError::InvalidPassword)
+            ));
+            let decoded = Fmt::SevenZip
+                .decode_with(archive.clone(), &password("hunter2"))
+                .unwrap();
+            assert_eq!(decoded[0].buffer, files[0].buffer);
+
+            // The header is encrypted too
+            let reader = || std::io::Cursor::new(archive.clone());
+            assert!(matches!(
+                Fmt::SevenZip.list(reader()),
+                Err(ArchiveError::PasswordRequired)
+            ));
+            let list = Fmt::SevenZip
+                .list_with(reader(), &password("hunter2"))
+                .unwrap();
+            assert_eq!(list[0].path, "secret.txt");
+        }
+
+        // Formats without encryption never write the data in plain
+        for fmt in Fmt::iter().filter(|fmt| !matches!(fmt, Fmt::SevenZip | Fmt::Zip)) {
+            assert!(matches!(
+                fmt.encode_with(files.clone(), &options),
+                Err(ArchiveError::UnsupportedFormat(_))
+            ));
+        }
+    }
+
+    #[cfg(feature = "zip")]
+    #[test]
+    fn test_zip_password() {
+        use crate::DecodeOptions;
+        use std::io::Write;
+
+        let files = vec![
+            File::new("docs".to_string(), vec![], None, true, None),
+            File::new(
+                "docs/secret.txt".to_string(),
+                b"top secret ".repeat(100),
+                None,
+                false,
+                None,
+            ),
+        ];
+        let options = EncodeOptions {
+            password: Some("hunter2".to_string()),
+            ..Default::default()
+        };
+        let password = |password: &str| DecodeOptions {
+            password: Some(password.to_string()),
+            ..Default::default()
+        };
+
+        let mut streamed = Vec::new();
+        let mut writer = Fmt::Zip.writer_with(&mut streamed, &options).unwrap();
+        for file in &files {
+            writer.add_file(file).unwrap();
+        }
+        writer.finish().unwrap();
+
+        // AES-128, as written by other tools
+        let mut aes128 = std::io::Cursor::new(Vec::new());
+        let mut zip = zip::ZipWriter::new(&mut aes128);
+        let entry_options = zip::write::SimpleFileOptions::default()
+            .with_aes_encryption(zip::AesMode::Aes128, "hunter2");
+        zip.start_file("docs/secret.txt", entry_options).unwrap();
+        zip.write_all(&files[1].buffer).unwrap();
+        zip.finish().unwrap();
+
+        for archive in [
+            Fmt::Zip.encode_with(files.clone(), &options).unwrap(),
+            streamed,
+            aes128.into_inner(),
+        ] {
+            assert!(matches!(
+                Fmt::Zip.decode(archive.clone()),
+                Err(ArchiveError::PasswordRequired)
+            ));
+            assert!(matches!(
+                Fmt::Zip.decode_with(archive.clone(), &password("wrong")),
+                Err(ArchiveError::InvalidPassword)
+            ));
+            let decoded = Fmt::Zip
+                .decode_with(archive.clone(), &password("hunter2"))
+                .unwrap();
+            let secret = decoded
+                .iter()
+                .find(|f| f.path == "docs/secret.txt")
+                .unwrap();
+            assert_eq!(secret.buffer, files[1].buffer);
+
+            // Only the content is encrypted, names stay readable
+            let list = Fmt::Zip.list(std::io::Cursor::new(archive)).unwrap();
+            assert!(list.iter().any(|entry| entry.path == "docs/secret.txt"));
+        }
+
+        // ZipCrypto, created with `zip -P hunter2`
+        let zip_crypto = vec![
+            0x50, 0x4b, 0x03, 0x04, 0x0a, 0x00, 0x09, 0x00, 0x00, 0x00, 0x42, 0x13, 0x51, 0x5d,
+            0xab, 0xe2, 0x7f, 0x11, 0x16, 0x00, 0x00, 0x00, 0x0a, 0x00, 0x00, 0x00, 0x0a, 0x00,
+            0x00, 0x00, 0x73, 0x65, 0x63, 0x72, 0x65, 0x74, 0x2e, 0x74, 0x78, 0x74, 0xd7, 0x38,
+            0x81, 0xd9, 0x2d, 0xd7, 0x64, 0x21, 0x52, 0xd6, 0x4f, 0x93, 0xc8, 0xf4, 0x6c, 0x01,
+            0xb5, 0xd5, 0x7a, 0xdb, 0x79, 0x3f, 0x50, 0x4b, 0x07, 0x08, 0xab, 0xe2, 0x7f, 0x11,
+            0x16, 0x00, 0x00, 0x00, 0x0a, 0x00, 0x00, 0x00, 0x50, 0x4b, 0x01, 0x02, 0x1e, 0x03,
+            0x0a, 0x00, 0x09, 0x00, 0x00, 0x00, 0x42, 0x13, 0x51, 0x5d, 0xab, 0xe2, 0x7f, 0x11,
+            0x16, 0x00, 0x00, 0x00, 0x0a, 0x00, 0x00, 0x00, 0x0a, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xa4, 0x81, 0x00, 0x00, 0x00, 0x00, 0x73, 0x65,
+            0x63, 0x72, 0x65, 0x74, 0x2e, 0x74, 0x78, 0x74, 0x50, 0x4b, 0x05, 0x06, 0x00, 0x00,
+            0x00, 0x00, 0x01, 0x00, 0x01, 0x00, 0x38, 0x00, 0x00, 0x00, 0x4e, 0x00, 0x00, 0x00,
+            0x00, 0x00,
+        ];
+        assert!(matches!(
+            Fmt::Zip.decode(zip_crypto.clone()),
+            Err(ArchiveError::PasswordRequired)
+        ));
+        assert!(matches!(
+            Fmt::Zip.decode_with(zip_crypto.clone(), &password("wrong")),
+            Err(ArchiveError::InvalidPassword)
+        ));
+        let decoded = Fmt::Zip
+            .decode_with(zip_crypto, &password("hunter2"))
+            .unwrap();
+        assert_eq!(decoded[0].path, "secret.txt");
+        assert_eq!(decoded[0].buffer, b"top secret");
+    }
+
+    #[cfg(feature = "zip")]
+    #[test]
+    fn test_zip_prefix() {
+        use crate::archive::Zip;
+
+        let files = vec![
+            File::new(
+                "bin/tool".to_string(),
+                b"tool".repeat(100),
+                Some(0o755),
+                false,
+                None,
+            ),
+            File::new("README".to_string(), b"readme".to_vec(), None, false, None),
+        ];
+        let script = b"#!/bin/sh\nunzip -o \"$0\"\nexit 0\n";
+
+        let bundle =
+            Zip::encode_with_prefix(script, files.clone(), &EncodeOptions::default()).unwrap();
+        assert!(bundle.starts_with(script));
+        assert_eq!(Fmt::detect(&bundle), Some(Fmt::Zip));
+
+        // The central directory offset counts the prefix
+        let end = bundle.len() - 22;
+        assert!(bundle[end..].starts_with(b"PK\x05\x06"));
+        let offset = u32::from_le_bytes(bundle[end + 16..end + 20].try_into().unwrap());
+        assert!(bundle[offset as usize..].starts_with(b"PK\x01\x02"));
+
+        // Archives simply appended to a stub keep offsets relative to their start
+        let appended = [
+            &b"MZ\x90\x00stub"[..],
+            &Fmt::Zip.encode(files.clone()).unwrap(),
+        ]
+        .concat();
+        assert_eq!(Fmt::detect(&appended), Some(Fmt::Zip));
+
+        for archive in [bundle, appended] {
+            let decoded = Fmt::Zip.decode(archive.clone()).unwrap();
+            let file = decoded.iter().find(|f| f.path == "bin/tool").unwrap();
+            assert_eq!(file.buffer, files[0].buffer);
+            let list = Fmt::Zip.list(std::io::Cursor::new(archive)).unwrap();
+            assert!(
+                list.iter()
+                    .any(|info| info.path == "README" && info.size == 6)
+            );
+        }
+
+        // The record must end the buffer, up to its comment
+        let mut stub = script.to_vec();
+        stub.extend_from_slice(b"PK\x05\x06");
+        stub.resize(stub.len() + 18, 0);
+        assert_eq!(Fmt::detect(&stub), Some(Fmt::Zip));
+        stub.push(0);
+        assert_eq!(Fmt::detect(&stub), None);
+    }
+
+    #[cfg(feature = "zip")]
+    #[test]
+    fn test_zip_edit() {
+        use crate::archive::Zip;
+        use std::io::Cursor;
+
+        let files = vec![
+            File::new("dir".to_string(), vec![], None, true, None),
+            File::new("dir/a.txt".to_string(), b"old".to_vec(), None, false, None),
+            File::new("dir/b.txt".to_string(), b"b".repeat(100), None, false, None),
+            File::new(
+                "keep.txt".to_string(),
+                b"keep".repeat(100),
+                None,
+                false,
+                None,
+            ),
+            File {
+                link: Some("keep.txt".to_string()),
+                ..File::new("link".to_string(), vec![], None, false, None)
+            },
+        ];
+        let script = b"#!/bin/sh\nexit 0\n";
+        let options = EncodeOptions {
+            password: Some("hunter2".to_string()),
+            ..Default::default()
+        };
+        let original = Zip::encode_with_prefix(script, files, &options).unwrap();
+
+        // Encrypted symlinks are the only entries that need the password
+        let mut editor = Zip::edit(Cursor::new(&original)).unwrap();
+        editor.delete("link").unwrap();
+        editor.save(Cursor::new(Vec::new())).unwrap();
+        let editor = Zip::edit(Cursor::new(&original)).unwrap();
+        assert!(matches!(
+            editor.save(Cursor::new(Vec::new())),
+            Err(ArchiveError::PasswordRequired)
+        ));
+
+        let config = File::new("dir/a.txt".to_string(), b"new".to_vec(), None, false, None);
+        let mut editor = Zip::edit_with(Cursor::new(&original), &options).unwrap();
+        editor.add_file(&config).unwrap();
+        assert!(matches!(
+            editor.add_file(&config),
+            Err(ArchiveError::DuplicateFiles { .. })
+        ));
+        assert_eq!(editor.delete("dir/b.txt").unwrap(), 1);
+        assert!(matches!(
+            editor.delete("dir/b.txt"),
+            Err(ArchiveError::EntryNotFound { .. })
+        ));
+        assert!(matches!(
+            editor.rename("keep.txt", "dir"),
+            Err(ArchiveError::DuplicateFiles { .. })
+        ));
+        assert_eq!(editor.rename("dir", "renamed").unwrap(), 2);
+        assert!(editor.contains("renamed/a.txt"));
+        assert!(!editor.contains("dir"));
+
+        let mut edited = Cursor::new(Vec::new());
+        editor.save(&mut edited).unwrap();
+        let edited = edited.into_inner();
+        assert!(edited.starts_with(script));
+        assert_eq!(Fmt::detect(&edited), Some(Fmt::Zip));
+
+        let password = crate::DecodeOptions {
+            password: Some("hunter2".to_string()),
+            ..Default::default()
+        };
+        let mut decoded = Fmt::Zip.decode_with(edited.clone(), &password).unwrap();
+        decoded.sort_by(|a, b| a.path.cmp(&b.path));
+        let paths: Vec<_> = decoded.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, ["keep.txt", "link", "renamed", "renamed/a.txt"]);
+        assert_eq!(decoded[0].buffer, b"keep".repeat(100));
+        assert_eq!(decoded[1].link.as_deref(), Some("keep.txt"));
+        assert_eq!(decoded[3].buffer, b"new");
+
+        // Copied entries keep their compressed data
+        let raw = |archive: &[u8], name: &str| {
+            let mut archive = zip::ZipArchive::new(Cursor::new(archive)).unwrap();
+            let index = archive.index_for_name(name).unwrap();
+            let file = archive.by_index_raw(index).unwrap();
+            (file.compressed_size(), file.crc32(), file.encrypted())
+        };
+        assert_eq!(raw(&original, "keep.txt"), raw(&edited, "keep.txt"));
+        assert!(raw(&edited, "keep.txt").2);
+        assert!(raw(&edited, "renamed/a.txt").2);
+
+        // Adding and deleting leaves everything before the central directory as is
+        let path =
+            std::env::temp_dir().join(format!("easy-archive-edit-{}.zip", std::process::id()));
+        std::fs::write(&path, &original).unwrap();
+        let mut editor = Zip::edit_with(Cursor::new(&original), &options).unwrap();
+        editor.add_file(&config).unwrap();
+        editor.delete("dir/b.txt").unwrap();
+        assert!(editor.can_save_in_place());
+        let file = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(&path)
+            .unwrap();
+        editor.save_in_place(file).unwrap();
+
+        let in_place = std::fs::read(&path).unwrap();
+        let start = zip::ZipArchive::new(Cursor::new(&original))
+            .unwrap()
+            .central_directory_start() as usize;
+        assert_eq!(in_place[..start], original[..start]);
+        let mut decoded = Fmt::Zip.decode_with(in_place.clone(), &password).unwrap();
+        decoded.sort_by(|a, b| a.path.cmp(&b.path));
+        let paths: Vec<_> = decoded.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, ["dir", "dir/a.txt", "keep.txt", "link"]);
+        assert_eq!(decoded[1].buffer, b"new");
+        assert_eq!(decoded[3].link.as_deref(), Some("keep.txt"));
+
+        let mut editor = Zip::edit(Cursor::new(&in_place)).unwrap();
+        editor.rename("keep.txt", "kept.txt").unwrap();
+        assert!(!editor.can_save_in_place());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[cfg(all(feature = "tar", feature = "zip"))]
+    #[test]
+    fn test_volumes() {
+        use crate::{VolumeWriter, Volumes, volume::volume_paths};
+        use std::io::Write;
+
+        let dir = std::env::temp_dir().join(format!("easy-archive-volumes-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let files = vec![
+            File {
+                path: "numbers.bin".to_string(),
+                buffer: (0..5000u32).flat_map(|i| i.to_le_bytes()).collect(),
+                ..Default::default()
+            },
+            File {
+                path: "hello.txt".to_string(),
+                buffer: b"Hello, world!".to_vec(),
+                ..Default::default()
+            },
+        ];
+
+        for fmt in Fmt::iter().filter(|fmt| !fmt.is_single_file() && fmt.can_encode()) {
+            let name = format!("archive{}", fmt.extensions()[0]);
+            let archive = fmt.encode(files.clone()).unwrap();
+
+            let mut volumes = VolumeWriter::create(dir.join(&name), 1000);
+            volumes.write_all(&archive).unwrap();
+            let count = volumes.finish().unwrap();
+            assert_eq!(count, archive.len().div_ceil(1000), "{:?}", fmt);
+
+            let first = dir.join(format!("{}.001", name));
+            let last = dir.join(format!("{}.{:03}", name, count));
+            assert_eq!(Fmt::guess(&first.to_string_lossy()), Some(fmt));
+            assert_eq!(
+                std::fs::metadata(&last).unwrap().len() as usize,
+                archive.len() - (count - 1) * 1000
+            );
+            assert_eq!(volume_paths(&last).unwrap().len(), count);
+
+            let mut joined = Vec::new();
+            Volumes::open(&last)
+                .unwrap()
+                .read_to_end(&mut joined)
+                .unwrap();
+            assert_eq!(joined, archive, "{:?}", fmt);
+
+            let list = fmt.list(Volumes::open(&first).unwrap()).unwrap();
+            assert_eq!(list.len(), files.len(), "{:?}", fmt);
+            assert!(
+                list.iter()
+                    .any(|info| info.path == "numbers.bin" && info.size == 20000)
+            );
+        }
+
+        // A missing volume is reported
+        std::fs::remove_file(dir.join("archive.tar.001")).unwrap();
+        assert!(matches!(
+            volume_paths(dir.join("archive.tar.002")),
+            Err(ArchiveError::InvalidArchive(_))
+        ));
+
+        // Spanned zip archives record offsets relative to their volumes
+        let archive = Fmt::Zip.encode(files.clone()).unwrap();
+        let end = archive.len() - 22;
+        assert!(archive[end..].starts_with(b"PK\x05\x06"));
+        let mut spanned = [&b"PK\x07\x08"[..], &archive].concat();
+        let size = 3000;
+        let locate = |offset: usize| ((offset / size) as u16, (offset % size) as u32);
+        let cd_offset =
+            u32::from_le_bytes(archive[end + 16..end + 20].try_into().unwrap()) as usize + 4;
+        let mut at = cd_offset;
+        while spanned[at..].starts_with(b"PK\x01\x02") {
+            let offset = u32::from_le_bytes(spanned[at + 42..at + 46].try_into().unwrap());
+            let (disk, offset) = locate(offset as usize + 4);
+            spanned[at + 34..at + 36].copy_from_slice(&disk.to_le_bytes());
+            spanned[at + 42..at + 46].copy_from_slice(&offset.to_le_bytes());
+            let [name, extra, comment] = [28, 30, 32]
+                .map(|i| u16::from_le_bytes([spanned[at + i], spanned[at + i + 1]]) as usize);
+            at += 46 + name + extra + comment;
+        }
+        let end = end + 4;
+        let (cd_disk, cd_offset) = locate(cd_offset);
+        let last_disk = ((spanned.len() - 1) / size) as u16;
+        spanned[end + 4..end + 6].copy_from_slice(&last_disk.to_le_bytes());
+        spanned[end + 6..end + 8].copy_from_slice(&cd_disk.to_le_bytes());
+        spanned[end + 16..end + 20].copy_from_slice(&cd_offset.to_le_bytes());
+        let parts: Vec<_> = spanned.chunks(size).map(|part| part.to_vec()).collect();
+        assert!(parts.len() > 2);
+        assert_eq!(Fmt::detect(&parts[0]), Some(Fmt::Zip));
+
+        let volumes = Volumes::new(parts.iter().map(std::io::Cursor::new).collect()).unwrap();
+        assert_eq!(volumes.count(), parts.len());
+        assert_eq!(Fmt::Zip.list(volumes).unwrap().len(), files.len());
+
+        for (i, part) in parts.iter().enumerate() {
+            let name = match i + 1 {
+                n if n == parts.len() => "spanned.zip".to_string(),
+                n => format!("spanned.z{:02}", n),
+            };
+            std::fs::write(dir.join(name), part).unwrap();
+        }
+        assert_eq!(Fmt::guess("spanned.z01"), Some(Fmt::Zip));
+        let mut joined = Vec::new();
+        Volumes::open(dir.join("spanned.z01"))
+            .unwrap()
+            .read_to_end(&mut joined)
+            .unwrap();
+        let decoded = Fmt::Zip.decode(joined).unwrap();
+        assert_eq!(decoded[0].buffer, files[0].buffer);
+
+        // Without all volumes the central directory points nowhere
+        std::fs::remove_file(dir.join("spanned.z02")).unwrap();
+        assert!(Volumes::open(dir.join("spanned.zip")).is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_extract() {
+        use crate::{ExtractOptions, extract};
+
+        let file =
+            |path: &str| File::new(path.to_string(), b"x".to_vec(), Some(0o4755), false, None);
+        let link = |path: &str, target: &str| {
+            File::new_symlink(path.to_string(), target.to_string(), None, None)
+        };
+
+        let root =
+            std::env::temp_dir().join(format!("easy-archive-extract-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        let dest = root.join("dest");
+        std::fs::create_dir_all(root.join("outside")).unwrap();
+        std::fs::create_dir_all(&dest).unwrap();
+        std::os::unix::fs::symlink(root.join("outside"), dest.join("out")).unwrap();
+
+        let unsafe_files = [
+            file("../evil"),
+            file("/etc/evil"),
+            file("C:/evil"),
+            file("a/../../evil"),
+            file("..\\evil"),
+            file("out/evil"),
+            link("up", "../outside"),
+            link("abs", "/etc/passwd"),
+            link("a/b/up", "../../../outside"),
+        ];
+        for f in &unsafe_files {
+            let err = extract(std::slice::from_ref(f), &dest, &ExtractOptions::default());
+            assert!(
+                matches!(err, Err(ArchiveError::UnsafePath { .. })),
+                "{}",
+                f.path
+            );
+        }
+        assert_eq!(std::fs::read_dir(root.join("outside")).unwrap().count(), 0);
+
+        let files = vec![
+            File::new("dir/".to_string(), vec![], Some(0o700), true, None),
+            file("dir/../ok.txt"),
+            link("lib.so", "dir/../ok.txt"),
+            link("here", "."),
+            link("escape", "here/.."),
+            link("dangling", "here/../evil"),
+            file("../evil"),
+        ];
+        let options = ExtractOptions { skip_unsafe: true };
+        let report = extract(&files, &dest, &options).unwrap();
+        assert_eq!(report.extracted, 4);
+        assert_eq!(report.skipped.len(), 3);
+        assert_eq!(std::fs::read(dest.join("lib.so")).unwrap(), b"x");
+        assert!(dest.join("here").is_symlink());
+        assert!(!dest.join("escape").exists() && !dest.join("escape").is_symlink());
+        assert!(!dest.join("dangling").is_symlink());
+        assert!(!root.join("evil").exists());
+
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(dest.join("ok.txt"))
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o7777, 0o755);
+
+        // Existing links inside the destination are replaced, never written through
+        std::os::unix::fs::symlink(root.join("outside/target"), dest.join("replaced")).unwrap();
+        extract(&[file("replaced")], &dest, &options).unwrap();
+        assert!(!dest.join("replaced").is_symlink());
+        assert!(!root.join("outside/target").exists());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[cfg(feature = "tar-lz4")]
+    #[test]
+    fn test_tar_lz4() {
+        let files = vec![
+            File {
+                path: "dir".to_string(),
+                is_dir: true,
+                ..Default::default()
+            },
+            File {
+                path: "dir/data.txt".to_string(),
+                buffer: b"lz4 content ".repeat(4096),
+                mode: Some(0o644),
+                ..Default::default()
+            },
+        ];
+
+        let archive = Fmt::TarLz4.encode(files.clone()).unwrap();
+        assert!(archive.len() < files[1].buffer.len());
+        assert_eq!(Fmt::detect(&archive), Some(Fmt::TarLz4));
+
+        let decoded = Fmt::TarLz4.decode(archive).unwrap();
+        let data = decoded.iter().find(|f| f.path == "dir/data.txt").unwrap();
+        assert_eq!(data.buffer, files[1].buffer);
+        assert_eq!(data.mode, Some(0o644));
+        assert!(decoded.iter().any(|f| f.path == "dir" && f.is_dir));
+
+        // Frames written by the reference lz4 tool hold the same tar
+        let tar = Fmt::Tar
+            .decode(std::fs::read("../assets/test.tar").unwrap())
+            .unwrap();
+        let lz4 = Fmt::TarLz4
+            .decode(std::fs::read("../assets/test.tar.lz4").unwrap())
+            .unwrap();
+        assert_eq!(tar.len(), lz4.len());
+        for (a, b) in tar.iter().zip(&lz4) {
+            assert_eq!(a.path, b.path);
+            assert_eq!(a.buffer, b.buffer);
+        }
+    }
+
+    #[cfg(all(feature = "tar-lzma", feature = "tar-lz"))]
+    #[test]
+    fn test_tar_lzma_lzip() {
+        use std::io::Write;
+
+        let files = vec![File {
+            path: "gawk/ChangeLog".to_string(),
+            buffer: b"legacy content ".repeat(4096),
+            mode: Some(0o644),
+            ..Default::default()
+        }];
+
+        for fmt in [Fmt::TarLzma, Fmt::TarLz] {
+            for level in [None, Some(0), Some(9)] {
+                let options = EncodeOptions {
+                    level,
+                    ..Default::default()
+                };
+                let archive = fmt.encode_with(files.clone(), &options).unwrap();
+                assert!(archive.len() < files[0].buffer.len(), "{:?}", fmt);
+                assert_eq!(Fmt::detect(&archive), Some(fmt));
+
+                let decoded = fmt.decode(archive).unwrap();
+                assert_eq!(decoded.len(), 1);
+                assert_eq!(decoded[0].path, files[0].path);
+                assert_eq!(decoded[0].buffer, files[0].buffer);
+            }
+        }
+
+        // The lzip header codes the dictionary size of the level
+        let archive = Fmt::TarLz.encode(files.clone()).unwrap();
+        assert_eq!(&archive[..6], b"LZIP\x01\x17");
+
+        // Multi-member lzip files decode as one stream
+        let tar = Fmt::Tar.encode(files.clone()).unwrap();
+        let (head, tail) = tar.split_at(tar.len() / 2);
+        let mut archive = Vec::new();
+        let mut ends = vec![];
+        for part in [head, tail] {
+            let mut member =
+                crate::archive::TarLz::compressor(&mut archive, &EncodeOptions::default()).unwrap();
+            member.write_all(part).unwrap();
+            member.finish().unwrap();
+            ends.push(archive.len());
+        }
+        let decoded = Fmt::TarLz.decode(archive.clone()).unwrap();
+        assert_eq!(decoded[0].buffer, files[0].buffer);
+
+        // A damaged CRC in the trailer of the first member is detected
+        archive[ends[0] - 20] ^= 0xFF;
+        assert!(Fmt::TarLz.decode(archive).is_err());
+
+        let password = EncodeOptions {
+            password: Some("secret".to_string()),
+            ..Default::default()
+        };
+        for fmt in [Fmt::TarLzma, Fmt::TarLz] {
+            assert!(matches!(
+                fmt.encode_with(files.clone(), &password),
+                Err(ArchiveError::UnsupportedFormat(_))
+            ));
+        }
+    }
+
+    #[cfg(feature = "tar-z")]
+    #[test]
+    fn test_tar_z() {
+        let tar = Fmt::Tar
+            .decode(std::fs::read("../assets/test.tar").unwrap())
+            .unwrap();
+
+        // 9-bit codes fill the table quickly and exercise CLEAR codes
+        for name in ["test.tar.Z", "test-maxbits9.tar.Z"] {
+            let buffer = std::fs::read(format!("../assets/{}", name)).unwrap();
+            assert_eq!(Fmt::detect(&buffer), Some(Fmt::TarZ), "{}", name);
+
+            let files = Fmt::TarZ.decode(buffer.clone()).unwrap();
+            assert_eq!(files.len(), tar.len(), "{}", name);
+            for (a, b) in tar.iter().zip(&files) {
+                assert_eq!(a.path, b.path, "{}", name);
+                assert_eq!(a.buffer, b.buffer, "{}", name);
+            }
+
+            let mut entries = Fmt::TarZ.entries(&buffer[..]).unwrap();
+            let mut count = 0;
+            while entries.next_entry().unwrap().is_some() {
+                count += 1;
+            }
+            assert_eq!(count, tar.len(), "{}", name);
+        }
+
+        for corrupt in [
+            &[0x1F, 0x9D][..],
+            &[0x1F, 0x9D, 0x80 | 17, 0x00],
+            // A first code that is not a literal
+            &[0x1F, 0x9D, 0x90, 0xFF, 0x01],
+        ] {
+            assert!(Fmt::TarZ.decode(corrupt.to_vec()).is_err(), "{:?}", corrupt);
+        }
+
+        assert!(!Fmt::TarZ.can_encode());
+        assert!(matches!(
+            Fmt::TarZ.encode(tar.clone()),
+            Err(ArchiveError::UnsupportedFormat(_))
+        ));
+        assert!(matches!(
+            Fmt::TarZ.writer(Vec::new()),
+            Err(ArchiveError::UnsupportedFormat(_))
+        ));
+    }
+
+    #[cfg(feature = "ar")]
+    #[test]
+    fn test_ar() {
+        use crate::archive::Ar;
+
+        let files = vec![
+            File::new(
+                "debian-binary".to_string(),
+                b"2.0\n".to_vec(),
+                Some(0o644),
+                false,
+                Some(1700000000),
+            ),
+            File::new("docs".to_string(), vec![], None, true, None),
+            File::new(
+                "docs/a member name longer than sixteen bytes.txt".to_string(),
+                b"odd".to_vec(),
+                Some(0o755),
+                false,
+                None,
+            ),
+        ];
+
+        let archive = Fmt::Ar.encode(files.clone()).unwrap();
+        assert_eq!(Fmt::detect(&archive), Some(Fmt::Ar));
+        assert!(archive.starts_with(b"!<arch>\ndebian-binary   1700000000"));
+
+        // Directories are skipped
+        let decoded = Fmt::Ar.decode(archive.clone()).unwrap();
+        assert_eq!(decoded.len(), 2);
+        for (a, b) in [&files[0], &files[2]].into_iter().zip(&decoded) {
+            assert_eq!(a.path, b.path);
+            assert_eq!(a.buffer, b.buffer);
+            assert_eq!(a.mode, b.mode);
+            assert!(!b.is_dir);
+        }
+        assert_eq!(decoded[0].last_modified, Some(1700000000));
+
+        let list = Fmt::Ar.list(std::io::Cursor::new(&archive)).unwrap();
+        assert_eq!(list.len(), 2);
+        assert_eq!(list[1].path, files[2].path);
+        assert_eq!(list[1].size, 3);
+
+        // Written by GNU ar, which stores long names in a "//" table
+        let gnu = [
+            &b"!<arch>\n"[..],
+            b"//                                              32        `\n",
+            b"a_very_long_member_name_here.o/\n",
+            b"short.txt/      0           0     0     644     4         `\n",
+            b"one\n",
+            b"/0              0           0     0     644     3         `\n",
+            b"odd\n",
+        ]
+        .concat();
+        let decoded = Fmt::Ar.decode(gnu).unwrap();
+        assert_eq!(decoded.len(), 2);
+        assert_eq!(decoded[0].path, "short.txt");
+        assert_eq!(decoded[0].buffer, b"one\n");
+        assert_eq!(decoded[1].path, "a_very_long_member_name_here.o");
+        assert_eq!(decoded[1].buffer, b"odd");
+        assert_eq!(decoded[1].mode, Some(0o644));
+
+        assert!(Fmt::Ar.decode(b"!<arch>\nshort".to_vec()).is_err());
+        assert!(Fmt::Ar.decode(b"!<thin>\n".to_vec()).is_err());
+        assert!(matches!(
+            Fmt::Ar.encode(vec![File::new_symlink(
+                "link".to_string(),
+                "target".to_string(),
+                None,
+                None
+            )]),
+            Err(ArchiveError::EncodeFailed { .. })
+        ));
+
+        // Debian packages expose the entries of their data member
+        let data = vec![File::new(
+            "./usr/share/doc/hello/README".to_string(),
+            b"hello".to_vec(),
+            None,
+            false,
+            None,
+        )];
+        let control = vec![File::new(
+            "./control".to_string(),
+            b"Package: hello\n".to_vec(),
+            None,
+            false,
+            None,
+        )];
+        let deb = Fmt::Ar
+            .encode(vec![
+                files[0].clone(),
+                File::new(
+                    "control.tar.gz".to_string(),
+                    Fmt::TarGz.encode(control).unwrap(),
+                    None,
+                    false,
+                    None,
+                ),
+                File::new(
+                    "data.tar.xz".to_string(),
+                    Fmt::TarXz.encode(data).unwrap(),
+                    None,
+                    false,
+                    None,
+                ),
+            ])
+            .unwrap();
+
+        let mut entries = Ar::deb_data(&deb[..]).unwrap();
+        let mut entry = entries.next_entry().unwrap().unwrap();
+        assert_eq!(entry.info().path, "usr/share/doc/hello/README");
+        let mut content = String::new();
+        entry.read_to_string(&mut content).unwrap();
+        assert_eq!(content, "hello");
+        assert!(entries.next_entry().unwrap().is_none());
+
+        assert!(matches!(
+            Ar::deb_data(&archive[..]),
+            Err(ArchiveError::InvalidArchive(_))
+        ));
+    }
+
+    #[cfg(feature = "cpio")]
+    #[test]
+    fn test_cpio() {
+        use crate::{DecodeOptions, archive::Cpio};
+
+        // A newc member header and its padded name and content
+        fn newc(ino: u32, mode: u32, nlink: u32, name: &str, data: &[u8]) -> Vec<u8> {
+            let fields = [ino, mode, 0, 0, nlink, 0, data.len() as u32, 0, 0, 0, 0];
+            let mut member = b"070701".to_vec();
+            for field in fields.into_iter().chain([name.len() as u32 + 1, 0]) {
+                member.extend_from_slice(format!("{:08X}", field).as_bytes());
+            }
+            member.extend_from_slice(name.as_bytes());
+            member.push(0);
+            member.resize(member.len().next_multiple_of(4), 0);
+            member.extend_from_slice(data);
+            member.resize(member.len().next_multiple_of(4), 0);
+            member
+        }
+        let trailer = newc(0, 0, 1, "TRAILER!!!", b"");
+
+        let files = vec![
+            File::new(
+                "etc".to_string(),
+                vec![],
+                Some(0o755),
+                true,
+                Some(1700000000),
+            ),
+            File::new(
+                "etc/init".to_string(),
+                b"#!/bin/sh\n".to_vec(),
+                Some(0o750),
+                false,
+                Some(1700000000),
+            ),
+            File::new_symlink("init".to_string(), "etc/init".to_string(), None, None),
+        ];
+        let archive = Fmt::Cpio.encode(files.clone()).unwrap();
+        assert_eq!(Fmt::detect(&archive), Some(Fmt::Cpio));
+        assert!(archive.starts_with(b"070701"));
+        assert_eq!(archive.len() % 512, 0);
+
+        let decoded = Fmt::Cpio.decode(archive.clone()).unwrap();
+        assert_eq!(decoded.len(), files.len());
+        for (a, b) in files.iter().zip(&decoded) {
+            assert_eq!(a.path, b.path);
+            assert_eq!(a.buffer, b.buffer);
+            assert_eq!(a.is_dir, b.is_dir);
+            assert_eq!(a.link, b.link);
+        }
+        assert_eq!(decoded[1].mode, Some(0o750));
+        assert_eq!(decoded[1].last_modified, Some(1700000000));
+
+        // Owners are kept by the streaming writer
+        let mut output = Vec::new();
+        let mut writer = Fmt::Cpio.writer(&mut output).unwrap();
+        let info = EntryInfo {
+            path: "home/user/.profile".to_string(),
+            size: 3,
+            uid: Some(1000),
+            gid: Some(100),
+            ..Default::default()
+        };
+        writer.add(&info, &b"abc"[..]).unwrap();
+        writer.finish().unwrap();
+        let list = Fmt::Cpio.list(std::io::Cursor::new(&output)).unwrap();
+        assert_eq!((list[0].uid, list[0].gid), (Some(1000), Some(100)));
+        assert_eq!((list[0].size, list[0].mode), (3, Some(0o644)));
+
+        // The crc variant checksums the content
+        let mut crc = newc(1, 0o100644, 1, "sum", b"abc");
+        crc[5] = b'2';
+        crc[102..110].copy_from_slice(format!("{:08X}", 97 + 98 + 99).as_bytes());
+        crc.extend_from_slice(&trailer);
+        let decoded = Fmt::Cpio.decode(crc.clone()).unwrap();
+        assert_eq!(decoded[0].buffer, b"abc");
+        crc[109] = b'0';
+        assert!(Fmt::Cpio.decode(crc).is_err());
+
+        // Only the last hard link carries the content, device nodes are skipped
+        let mut links = newc(7, 0o100644, 2, "a", b"");
+        links.extend(newc(8, 0o020600, 1, "dev/console", b""));
+        links.extend(newc(7, 0o100644, 2, "b", b"shared"));
+        links.extend_from_slice(&trailer);
+        let decoded = Fmt::Cpio.decode(links).unwrap();
+        assert_eq!(decoded.len(), 2);
+        assert_eq!(decoded[0].buffer, b"shared");
+        assert_eq!(decoded[1].buffer, b"shared");
 
+        // An odc member header, with octal fields and no alignment
+        fn odc(name: &str, data: &[u8]) -> Vec<u8> {
+            let mut member = format!(
+                "070707{:06o}{:06o}{:06o}{:06o}{:06o}{:06o}{:06o}{:011o}{:06o}{:011o}{}\0",
+                1,
+                2,
+                0o100644,
+                1000,
+                100,
+                1,
+                0,
+                1700000000,
+                name.len() + 1,
+                data.len(),
+                name
+            )
+            .into_bytes();
+            member.extend_from_slice(data);
+            member
+        }
+        let decoded = Fmt::Cpio
+            .decode([odc("abc", b"data"), odc("TRAILER!!!", b"")].concat())
+            .unwrap();
+        assert_eq!(decoded.len(), 1);
+        assert_eq!(decoded[0].path, "abc");
+        assert_eq!(decoded[0].buffer, b"data");
+        assert_eq!(decoded[0].mode, Some(0o644));
+        assert_eq!(decoded[0].last_modified, Some(1700000000));
+
+        // RPM packages: lead, signature, header and compressed payload
+        let payload = Fmt::Cpio.encode(files[..2].to_vec()).unwrap();
+        let rpm = |compressor: &str, payload: &[u8]| {
+            let mut rpm = vec![0xED, 0xAB, 0xEE, 0xDB];
+            rpm.resize(96, 0);
+            // Empty signature
+            rpm.extend_from_slice(&[0x8E, 0xAD, 0xE8, 0x01, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+            let data = format!("cpio\0{}\0", compressor);
+            rpm.extend_from_slice(&[0x8E, 0xAD, 0xE8, 0x01, 0, 0, 0, 0, 0, 0, 0, 2]);
+            rpm.extend_from_slice(&(data.len() as u32).to_be_bytes());
+            for (tag, offset) in [(1124u32, 0u32), (1125, 5)] {
+                for field in [tag, 6, offset, 1] {
+                    rpm.extend_from_slice(&field.to_be_bytes());
+                }
+            }
+            rpm.extend_from_slice(data.as_bytes());
+            rpm.extend_from_slice(payload);
+            rpm
+        };
+        let options = DecodeOptions::default();
+        #[cfg(feature = "gz")]
+        {
+            let payload = Fmt::Gz
+                .encode(vec![File::new(
+                    String::new(),
+                    payload.clone(),
+                    None,
+                    false,
+                    None,
+                )])
+                .unwrap();
+            let decoded = Cpio::decode_rpm(rpm("gzip", &payload), &options).unwrap();
+            assert_eq!(decoded[1].path, "etc/init");
+            assert_eq!(decoded[1].buffer, files[1].buffer);
+        }
+        #[cfg(feature = "xz")]
+        {
+            let payload = Fmt::Xz
+                .encode(vec![File::new(
+                    String::new(),
+                    payload.clone(),
+                    None,
+                    false,
+                    None,
+                )])
+                .unwrap();
+            assert_eq!(
+                Cpio::decode_rpm(rpm("xz", &payload), &options)
+                    .unwrap()
+                    .len(),
+                2
+            );
+        }
+        assert!(matches!(
+            Cpio::decode_rpm(rpm("brotli", &payload[..0]), &options),
+            Err(ArchiveError::UnsupportedFormat(_))
+        ));
+        assert!(Cpio::decode_rpm(&archive, &options).is_err());
+    }
+
+    #[test]
+    fn test_single_file() {
+        use crate::{DecodeLimits, DecodeOptions};
+
+        let file = File::new(
+            "data/report.json".to_string(),
+            b"{\"ok\": true}\n".repeat(100),
+            None,
+            false,
+            Some(1_700_000_000),
+        );
+
+        for fmt in Fmt::iter().filter(|fmt| fmt.is_single_file()) {
+            let compressed = fmt.encode(vec![file.clone()]).unwrap();
+            assert_eq!(Fmt::detect(&compressed), Some(fmt), "{:?}", fmt);
+
+            let decoded = fmt.decode(compressed.clone()).unwrap();
+            assert_eq!(decoded.len(), 1, "{:?}", fmt);
+            assert_eq!(decoded[0].buffer, file.buffer, "{:?}", fmt);
+
+            let list = fmt.list(std::io::Cursor::new(compressed.clone())).unwrap();
+            assert_eq!(list.len(), 1, "{:?}", fmt);
+            assert_eq!(list[0].size, file.buffer.len() as u64, "{:?}", fmt);
+            assert_eq!(list[0].path, decoded[0].path, "{:?}", fmt);
+
+            let mut streamed = Vec::new();
+            let mut writer = fmt.writer(&mut streamed).unwrap();
+            writer.add_file(&file).unwrap();
+            assert!(writer.add_file(&File::default()).is_err(), "{:?}", fmt);
+            writer.finish().unwrap();
+            assert_eq!(fmt.decode(streamed).unwrap()[0].buffer, file.buffer);
+
+            let options = DecodeOptions {
+                limits: DecodeLimits {
+                    max_entry_size: Some(100),
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            // Streaming decompresses the file twice, within the limits
+            let report = fmt.verify(std::io::Cursor::new(&compressed)).unwrap();
+            assert_eq!(report.entries, 1, "{:?}", fmt);
+            assert!(report.is_ok(), "{:?}: {:?}", fmt, report.failures);
+            assert!(matches!(
+                fmt.verify_with(std::io::Cursor::new(&compressed), &options),
+                Err(ArchiveError::EntryTooLarge { .. })
+            ));
+            assert!(matches!(
+                fmt.decode_with(compressed, &options),
+                Err(ArchiveError::EntryTooLarge { .. })
+            ));
+
+            // Exactly one regular file can be compressed
+            for files in [
+                vec![],
+                vec![file.clone(), file.clone()],
+                vec![File::new("dir".to_string(), vec![], None, true, None)],
+            ] {
+                assert!(matches!(
+                    fmt.encode(files),
+                    Err(ArchiveError::EncodeFailed { .. })
+                ));
+            }
+        }
+
+        // The gzip header keeps the file name and modification time
+        let decoded = Fmt::Gz.decode(Fmt::Gz.encode(vec![file]).unwrap()).unwrap();
+        assert_eq!(decoded[0].path, "report.json");
+        assert_eq!(decoded[0].last_modified, Some(1_700_000_000));
+
+        // Streams written by other tools may not record a name
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+        std::io::Write::write_all(&mut encoder, b"hello").unwrap();
+        let decoded = Fmt::Gz.decode(encoder.finish().unwrap()).unwrap();
+        assert_eq!(decoded[0].path, "");
+        assert_eq!(decoded[0].buffer, b"hello");
+
+        assert_eq!(Fmt::guess("archive.tar.gz"), Some(Fmt::TarGz));
+        assert_eq!(Fmt::guess("data.json.gz"), Some(Fmt::Gz));
+    }
+
+    use std::path::PathBuf;
+
+    #[test]
+    fn encode_decode() {
+        for fmt in Fmt::iter().filter(|fmt| !fmt.is_single_file() && fmt.can_encode()) {
             let mut files = vec![];
             let base = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
             let asset_dir = base.join("../assets");
 
             for entry in std::fs::read_dir(asset_dir).expect("read dir error") {
                 let file_path = entry.expect("get path error").path();
+                if file_path.is_dir() {
+                    continue;
+                }
                 let path = file_path
                     .file_name()
                     .expect("get name error")
@@ -110,12 +2179,23 @@ mod test {
                     mode: None,
                     is_dir: false,
                     last_modified: None,
+                    link: None,
                 })
             }
 
-            let compressed = fmt.encode(files).expect("encode error");
+            let compressed = fmt.encode(files.clone()).expect("encode error");
             println!("{:?} {}", fmt, compressed.len());
             assert!(!compressed.is_empty());
+
+            let decoded = fmt.decode(compressed).expect("decode error");
+            assert_eq!(decoded.len(), files.len(), "{:?}", fmt);
+            for file in &files {
+                let found = decoded
+                    .iter()
+                    .find(|f| f.path == file.path)
+                    .unwrap_or_else(|| panic!("{:?} lost {}", fmt, file.path));
+                assert_eq!(found.buffer, file.buffer, "{:?} {}", fmt, file.path);
+            }
         }
     }
 }
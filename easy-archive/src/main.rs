@@ -3,19 +3,31 @@
 /// This binary provides a simple CLI for compressing and decompressing archives.
 use easy_archive::{ArchiveError, Fmt, human_size};
 
+#[cfg(feature = "decode")]
+use easy_archive::{Change, DecodeOptions, ExtractOptions, Volumes, extract, mode_to_string};
+
 #[cfg(feature = "encode")]
-use easy_archive::File;
+use easy_archive::{
+    EncodeOptions, File, Reproducible, VolumeWriter, ZipMethod, volume::volume_path,
+};
+
+#[cfg(feature = "decode")]
+use easy_archive::{HashAlgorithm, Manifest};
+
+#[cfg(all(feature = "decode", feature = "encode"))]
+use easy_archive::Metadata;
+
+use easy_archive::volume::volume_stem;
 
 use path_clean::PathClean;
 use std::fs;
 
-#[cfg(feature = "encode")]
-use std::io;
+use std::io::{self, IsTerminal, Write};
 
 use std::path::{Path, PathBuf};
 use std::process;
 
-use clap::Parser;
+use clap::{Parser, Subcommand};
 
 /// Command-line interface for easy-archive
 ///
@@ -39,8 +51,28 @@ fn get_help_text() -> &'static str {
             formats.extend(&[".tar.bz2", ".tbz2", ".tbz"]);
             #[cfg(feature = "tar-zstd")]
             formats.extend(&[".tar.zst", ".tzst"]);
+            #[cfg(feature = "tar-lz4")]
+            formats.extend(&[".tar.lz4", ".tlz4"]);
+            #[cfg(feature = "tar-lzma")]
+            formats.extend(&[".tar.lzma", ".tlz"]);
+            #[cfg(feature = "tar-lz")]
+            formats.push(".tar.lz");
+            #[cfg(feature = "tar-z")]
+            formats.extend(&[".tar.Z", ".taZ"]);
+            #[cfg(feature = "ar")]
+            formats.extend(&[".ar", ".a", ".deb"]);
+            #[cfg(feature = "cpio")]
+            formats.push(".cpio");
             #[cfg(feature = "zip")]
             formats.push(".zip");
+            #[cfg(feature = "gz")]
+            formats.push(".gz");
+            #[cfg(feature = "xz")]
+            formats.push(".xz");
+            #[cfg(feature = "bz")]
+            formats.push(".bz2");
+            #[cfg(feature = "zstd")]
+            formats.push(".zst");
 
             if formats.is_empty() {
                 help.push_str("  (No formats enabled)\n");
@@ -63,8 +95,19 @@ fn get_help_text() -> &'static str {
 }
 
 #[derive(Parser, Debug)]
-#[command(author, version, about, long_about = None, after_help = get_help_text())]
+#[command(
+    author,
+    version,
+    about,
+    long_about = None,
+    after_help = get_help_text(),
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// Input files or directories (multiple allowed)
     #[arg(required = true)]
     inputs: Vec<String>,
@@ -72,9 +115,179 @@ struct Cli {
     /// Output archive or directory
     #[arg(short, long)]
     output: Option<String>,
+
+    /// Compression level (0-9, 1-22 for zstd), defaults to the format default
+    #[cfg(feature = "encode")]
+    #[arg(short, long)]
+    level: Option<u32>,
+
+    /// Zip compression method: stored, deflate, bzip2, zstd or xz
+    #[cfg(feature = "encode")]
+    #[arg(short, long, default_value_t = ZipMethod::Zstd)]
+    method: ZipMethod,
+
+    /// Number of compression worker threads (xz, zstd and 7z)
+    #[cfg(feature = "encode")]
+    #[arg(short, long)]
+    threads: Option<u32>,
+
+    /// File written before a zip archive, such as a self-extracting stub or an installer script
+    #[cfg(all(feature = "encode", feature = "zip"))]
+    #[arg(long, value_name = "FILE")]
+    prefix: Option<String>,
+
+    /// Cut the archive into volumes of at most SIZE bytes (e.g. 2G, 500M), named like archive.7z.001
+    #[cfg(feature = "encode")]
+    #[arg(long, value_name = "SIZE", value_parser = parse_size)]
+    volume_size: Option<u64>,
+
+    /// Write a reproducible archive: sorted entries, normalized metadata, times clamped to SOURCE_DATE_EPOCH (implied when it is set)
+    #[cfg(feature = "encode")]
+    #[arg(long)]
+    reproducible: bool,
+
+    /// Password of encrypted archives (7z, zip); prompts when given without a value
+    #[arg(short, long, num_args = 0..=1, value_name = "PASSWORD")]
+    password: Option<Option<String>>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// List the entries of an archive without extracting them
+    #[cfg(feature = "decode")]
+    List {
+        /// Archive to list
+        input: String,
+
+        /// Password of encrypted 7z headers and zip symlinks; prompts when given without a value
+        #[arg(short, long, num_args = 0..=1, value_name = "PASSWORD")]
+        password: Option<Option<String>>,
+    },
+
+    /// Compare the entries of two archives, possibly of different formats
+    #[cfg(feature = "decode")]
+    Diff {
+        /// The old archive
+        old: String,
+
+        /// The new archive
+        new: String,
+
+        /// Print the differences as a JSON array
+        #[arg(long)]
+        json: bool,
+
+        /// Password of encrypted archives; prompts when given without a value
+        #[arg(short, long, num_args = 0..=1, value_name = "PASSWORD")]
+        password: Option<Option<String>>,
+    },
+
+    /// Check the checksums of archives without extracting them
+    #[cfg(feature = "decode")]
+    Test {
+        /// Archives to check
+        #[arg(required = true)]
+        inputs: Vec<String>,
+
+        /// Password of encrypted archives; prompts when given without a value
+        #[arg(short, long, num_args = 0..=1, value_name = "PASSWORD")]
+        password: Option<Option<String>>,
+    },
+
+    /// Write the digests of the files inside an archive, or check them
+    #[cfg(feature = "decode")]
+    Manifest {
+        /// Archive to hash
+        input: String,
+
+        /// Hash function: sha256 or blake3
+        #[arg(short, long, default_value_t = HashAlgorithm::Sha256)]
+        algorithm: HashAlgorithm,
+
+        /// File to write the manifest to, defaults to stdout
+        #[arg(short, long, conflicts_with = "check")]
+        output: Option<String>,
+
+        /// Check the archive against an existing manifest instead of writing one
+        #[arg(long, value_name = "MANIFEST")]
+        check: Option<String>,
+
+        /// Password of encrypted archives; prompts when given without a value
+        #[arg(short, long, num_args = 0..=1, value_name = "PASSWORD")]
+        password: Option<Option<String>>,
+    },
+
+    /// Convert an archive into another format without extracting it
+    #[cfg(all(feature = "decode", feature = "encode"))]
+    Convert {
+        /// Archive to convert
+        input: String,
+
+        /// Archive to write, in the format of its extension
+        output: String,
+
+        /// Compression level (0-9, 1-22 for zstd), defaults to the format default
+        #[arg(short, long)]
+        level: Option<u32>,
+
+        /// Zip compression method: stored, deflate, bzip2, zstd or xz
+        #[arg(short, long, default_value_t = ZipMethod::Zstd)]
+        method: ZipMethod,
+
+        /// Number of compression worker threads (xz, zstd and 7z)
+        #[arg(short, long)]
+        threads: Option<u32>,
+
+        /// Cut the archive into volumes of at most SIZE bytes (e.g. 2G, 500M)
+        #[arg(long, value_name = "SIZE", value_parser = parse_size)]
+        volume_size: Option<u64>,
+
+        /// Normalize metadata and clamp times to SOURCE_DATE_EPOCH (implied when it is set); entries keep the source order
+        #[arg(long)]
+        reproducible: bool,
+
+        /// Password of the encrypted input archive; prompts when given without a value
+        #[arg(short, long, num_args = 0..=1, value_name = "PASSWORD")]
+        password: Option<Option<String>>,
+    },
+
+    /// Add, delete or rename entries of a zip archive without recompressing the others
+    #[cfg(all(feature = "decode", feature = "encode", feature = "zip"))]
+    Edit {
+        /// Zip archive to edit, replaced unless --output is given
+        archive: String,
+
+        /// Add a file or directory, named NAME when given; replaces an entry of the same name
+        #[arg(long, value_name = "PATH[=NAME]")]
+        add: Vec<String>,
+
+        /// Delete an entry, along with everything below it for a directory
+        #[arg(long, value_name = "NAME")]
+        delete: Vec<String>,
+
+        /// Rename an entry, along with everything below it for a directory
+        #[arg(long, value_name = "OLD=NEW")]
+        rename: Vec<String>,
+
+        /// Write the edited archive here instead of replacing the original
+        #[arg(short, long)]
+        output: Option<String>,
+
+        /// Compression level of added entries, defaults to the method default
+        #[arg(short, long)]
+        level: Option<u32>,
+
+        /// Compression method of added entries: stored, deflate, bzip2, zstd or xz
+        #[arg(short, long, default_value_t = ZipMethod::Zstd)]
+        method: ZipMethod,
+
+        /// Password encrypting added entries and decrypting symlinks; prompts when given without a value
+        #[arg(short, long, num_args = 0..=1, value_name = "PASSWORD")]
+        password: Option<Option<String>>,
+    },
 }
 
-/// Collect files and directories recursively, skipping symlinks
+/// Collect files, directories and symlinks recursively
 ///
 /// # Arguments
 /// * `input_path` - The path to collect files from
@@ -94,12 +307,14 @@ fn collect_files(input_path: &Path, strip_root: bool) -> io::Result<Vec<File>> {
             .file_name()
             .map(|name| name.to_string_lossy().to_string())
             .unwrap_or_default();
+        let (mode, last_modified) = file_metadata(&fs::metadata(&input_path)?);
         files.push(File {
             path: file_name,
             buffer,
             is_dir: false,
-            mode: None,
-            last_modified: None,
+            mode,
+            last_modified,
+            link: None,
         });
         return Ok(files);
     }
@@ -124,12 +339,14 @@ fn collect_files(input_path: &Path, strip_root: bool) -> io::Result<Vec<File>> {
                 });
 
             if !rel_path.is_empty() && rel_path != "." {
+                let (mode, last_modified) = file_metadata(&fs::metadata(&input_path)?);
                 files.push(File {
                     path: rel_path.clone(),
                     buffer: vec![],
                     is_dir: true,
-                    mode: None,
-                    last_modified: None,
+                    mode,
+                    last_modified,
+                    link: None,
                 });
             }
         }
@@ -140,7 +357,9 @@ fn collect_files(input_path: &Path, strip_root: bool) -> io::Result<Vec<File>> {
     Ok(files)
 }
 
-/// Recursive helper function to collect files and directories
+/// Recursive helper function to collect files, directories and symlinks
+///
+/// Symlinks are stored as links and never followed.
 ///
 /// # Arguments
 /// * `base_path` - The base path for calculating relative paths
@@ -155,9 +374,10 @@ fn collect_files_recursive(
     for entry in fs::read_dir(current_path)? {
         let entry = entry?;
         let path = entry.path();
+        let file_type = entry.file_type()?;
 
-        // Skip if not a file or directory (e.g., symlinks)
-        if !path.is_file() && !path.is_dir() {
+        // Skip special files (e.g., sockets and fifos)
+        if !file_type.is_file() && !file_type.is_dir() && !file_type.is_symlink() {
             continue;
         }
 
@@ -165,31 +385,58 @@ fn collect_files_recursive(
             .strip_prefix(base_path)
             .map(|p| p.to_string_lossy().to_string())
             .unwrap_or_else(|_| path.to_string_lossy().to_string());
+        let (mode, last_modified) = file_metadata(&entry.metadata()?);
 
-        if path.is_dir() {
+        if file_type.is_symlink() {
+            let link = fs::read_link(&path)?.to_string_lossy().replace('\\', "/");
+            files.push(File::new_symlink(rel_path, link, mode, last_modified));
+        } else if file_type.is_dir() {
             files.push(File {
                 path: rel_path.clone(),
                 buffer: vec![],
                 is_dir: true,
-                mode: None,
-                last_modified: None,
+                mode,
+                last_modified,
+                link: None,
             });
             // Recurse into subdirectory
             collect_files_recursive(base_path, &path, files)?;
-        } else if path.is_file() {
+        } else {
             let buffer = fs::read(&path)?;
             files.push(File {
                 path: rel_path,
                 buffer,
                 is_dir: false,
-                mode: None,
-                last_modified: None,
+                mode,
+                last_modified,
+                link: None,
             });
         }
     }
     Ok(())
 }
 
+/// Read the permission bits and modification time of a file system entry
+///
+/// Permission bits are only known on Unix, elsewhere the format defaults apply.
+#[cfg(feature = "encode")]
+fn file_metadata(metadata: &fs::Metadata) -> (Option<u32>, Option<u64>) {
+    #[cfg(unix)]
+    let mode = {
+        use std::os::unix::fs::PermissionsExt;
+        Some(metadata.permissions().mode() & 0o7777)
+    };
+    #[cfg(not(unix))]
+    let mode = None;
+
+    let last_modified = metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|time| time.as_secs());
+    (mode, last_modified)
+}
+
 /// Display user-friendly error message
 fn display_error(error: &ArchiveError) {
     eprintln!("Error: {}", error);
@@ -204,6 +451,10 @@ fn display_error(error: &ArchiveError) {
             }
             eprintln!("\nPlease ensure all file paths are unique.");
         }
+        #[cfg(feature = "decode")]
+        ArchiveError::PasswordRequired | ArchiveError::InvalidPassword => {
+            eprintln!("\nPass the password with --password, or --password alone to be prompted.");
+        }
         ArchiveError::UnsupportedFormat(fmt) => {
             eprintln!("\nThe format '{}' is not supported or not enabled.", fmt);
             eprintln!("Check that the corresponding feature flag is enabled.");
@@ -212,18 +463,72 @@ fn display_error(error: &ArchiveError) {
     }
 }
 
+/// Resolve the `--password` option, prompting when it has no value
+fn resolve_password(password: Option<Option<String>>) -> Option<String> {
+    password.map(|password| password.unwrap_or_else(prompt_password))
+}
+
+/// Read a password from the terminal, without echo where supported
+fn prompt_password() -> String {
+    eprint!("Password: ");
+    let _ = io::stderr().flush();
+
+    // Turn off the terminal echo for the duration of the prompt
+    #[cfg(unix)]
+    let echo_off = process::Command::new("stty")
+        .arg("-echo")
+        .stdin(process::Stdio::inherit())
+        .status()
+        .is_ok_and(|status| status.success());
+
+    let mut line = String::new();
+    let result = io::stdin().read_line(&mut line);
+
+    #[cfg(unix)]
+    if echo_off {
+        let _ = process::Command::new("stty")
+            .arg("echo")
+            .stdin(process::Stdio::inherit())
+            .status();
+        eprintln!();
+    }
+
+    if let Err(e) = result {
+        eprintln!("Error: Failed to read password: {}", e);
+        process::exit(1);
+    }
+    line.trim_end_matches(['\r', '\n']).to_string()
+}
+
 /// Handle decompression operation
 #[cfg(feature = "decode")]
-fn handle_decompression(input: &str, output: &str, fmt: Fmt) {
-    let buffer = match fs::read(input) {
-        Ok(buf) => buf,
-        Err(e) => {
+fn handle_decompression(input: &str, output: &str, fmt: Fmt, password: Option<String>) {
+    use std::io::Read;
+
+    let decode = |options: &DecodeOptions| {
+        let mut buffer = Vec::new();
+        if let Err(e) = open_volumes(input).read_to_end(&mut buffer) {
             eprintln!("Error: Failed to read input file '{}': {}", input, e);
             process::exit(1);
         }
+        fmt.decode_with(buffer, options)
+    };
+
+    let mut options = DecodeOptions {
+        password,
+        ..Default::default()
+    };
+    let result = match decode(&options) {
+        Err(ArchiveError::PasswordRequired)
+            if options.password.is_none() && io::stdin().is_terminal() =>
+        {
+            options.password = Some(prompt_password());
+            decode(&options)
+        }
+        result => result,
     };
 
-    let files = match fmt.decode(buffer) {
+    let files = match result {
         Ok(f) => f,
         Err(e) => {
             display_error(&e);
@@ -241,68 +546,647 @@ fn handle_decompression(input: &str, output: &str, fmt: Fmt) {
     println!("{} of {} files", human_size(total_size), file_count);
     println!("Decompressing to {}", output);
 
-    for file in &files {
-        let output_path = Path::new(output).clean();
-        let output_path = output_path.join(&file.path).clean();
-        let dir = output_path
-            .parent()
-            .expect("Failed to get parent directory");
-
-        if !dir.exists()
-            && let Err(e) = fs::create_dir_all(dir)
+    if fmt.is_single_file() {
+        write_single_file(input, output, fmt, &files[0]);
+        println!("Decompression complete!");
+        return;
+    }
+
+    // Archives may come from untrusted sources, so report escaping entries instead of writing them
+    let options = ExtractOptions { skip_unsafe: true };
+    let report = match extract(&files, output, &options) {
+        Ok(report) => report,
+        Err(e) => {
+            display_error(&e);
+            process::exit(1);
+        }
+    };
+
+    for error in &report.skipped {
+        eprintln!("Warning: {}", error);
+    }
+
+    println!("Decompression complete!");
+}
+
+/// Open an input archive, joining the volumes of split archives
+#[cfg(feature = "decode")]
+fn open_volumes(input: &str) -> Volumes<io::BufReader<fs::File>> {
+    Volumes::open(input).unwrap_or_else(|e| {
+        eprintln!("Error: Failed to read input file '{}': {}", input, e);
+        process::exit(1);
+    })
+}
+
+/// Write the file of a single-file format, e.g. `foo.gz` to `foo`
+///
+/// When `output` is an existing directory, the file is written into it, named
+/// after the gzip header or the input file without its extension.
+#[cfg(feature = "decode")]
+fn write_single_file(input: &str, output: &str, fmt: Fmt, file: &easy_archive::File) {
+    let mut path = PathBuf::from(output);
+    if path.is_dir() {
+        let name = Path::new(&file.path)
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| strip_extension(input, fmt));
+        path.push(name);
+    }
+
+    if let Err(e) = fs::write(&path, &file.buffer) {
+        eprintln!("Error: Failed to write '{}': {}", path.display(), e);
+        process::exit(1);
+    }
+}
+
+/// Get the file name of `input` without the extension of its format
+fn strip_extension(input: &str, fmt: Fmt) -> String {
+    let name = Path::new(input)
+        .clean()
+        .file_name()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "archive".to_string());
+    let name = volume_stem(&name).unwrap_or(name);
+
+    for ext in fmt.extensions() {
+        if let Some(stem) = name.strip_suffix(ext) {
+            return stem.to_string();
+        }
+    }
+    name
+}
+
+/// Format a Unix timestamp as `YYYY-MM-DD HH:MM` in UTC
+#[cfg(feature = "decode")]
+fn format_timestamp(timestamp: Option<u64>) -> String {
+    timestamp
+        .and_then(|t| time::OffsetDateTime::from_unix_timestamp(t as i64).ok())
+        .map(|t| {
+            format!(
+                "{:04}-{:02}-{:02} {:02}:{:02}",
+                t.year(),
+                t.month() as u8,
+                t.day(),
+                t.hour(),
+                t.minute()
+            )
+        })
+        .unwrap_or_else(|| "-".repeat(16))
+}
+
+/// Handle list operation
+#[cfg(feature = "decode")]
+fn handle_list(input: &str, password: Option<String>) {
+    let Some(fmt) = Fmt::guess(input).or_else(|| detect_file(input)) else {
+        eprintln!("Error: Cannot identify the archive format of '{}'", input);
+        process::exit(1);
+    };
+
+    let list = |options: &DecodeOptions| fmt.list_with(open_volumes(input), options);
+
+    let mut options = DecodeOptions {
+        password,
+        ..Default::default()
+    };
+    let result = match list(&options) {
+        Err(ArchiveError::PasswordRequired)
+            if options.password.is_none() && io::stdin().is_terminal() =>
         {
-            eprintln!(
-                "Error: Failed to create directory '{}': {}",
-                dir.display(),
-                e
-            );
+            options.password = Some(prompt_password());
+            list(&options)
+        }
+        result => result,
+    };
+
+    let list = match result {
+        Ok(list) => list,
+        Err(e) => {
+            display_error(&e);
             process::exit(1);
         }
+    };
+
+    let mut total_size = 0;
+    for info in &list {
+        let mode = info.mode.unwrap_or(if info.is_dir { 0o755 } else { 0o644 });
+        println!(
+            "{} {:>8} {} {}",
+            mode_to_string(mode, info.is_dir),
+            human_size(info.size as usize),
+            format_timestamp(info.last_modified),
+            info.path
+        );
+        total_size += info.size as usize;
+    }
+
+    println!("{} of {} files", human_size(total_size), list.len());
+}
+
+/// Quote a string as a JSON string literal
+#[cfg(feature = "decode")]
+fn json_string(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if (c as u32) < 0x20 => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
 
-        if file.is_dir
-            && !output_path.exists()
-            && let Err(e) = fs::create_dir_all(&output_path)
+/// Handle the comparison of two archives
+#[cfg(feature = "decode")]
+fn handle_diff(old: &str, new: &str, json: bool, password: Option<String>) {
+    let fmt = |input: &str| {
+        Fmt::guess(input)
+            .or_else(|| detect_file(input))
+            .unwrap_or_else(|| {
+                eprintln!("Error: Cannot identify the archive format of '{}'", input);
+                process::exit(1);
+            })
+    };
+    let (old_fmt, new_fmt) = (fmt(old), fmt(new));
+
+    let diff = |options: &DecodeOptions| {
+        old_fmt.diff_with(open_volumes(old), new_fmt, open_volumes(new), options)
+    };
+
+    let mut options = DecodeOptions {
+        password,
+        ..Default::default()
+    };
+    let result = match diff(&options) {
+        Err(ArchiveError::PasswordRequired)
+            if options.password.is_none() && io::stdin().is_terminal() =>
         {
-            eprintln!(
-                "Error: Failed to create directory '{}': {}",
-                output_path.display(),
-                e
-            );
+            options.password = Some(prompt_password());
+            diff(&options)
+        }
+        result => result,
+    };
+
+    let differences = match result {
+        Ok(differences) => differences,
+        Err(e) => {
+            display_error(&e);
             process::exit(1);
         }
+    };
 
-        if !file.is_dir
-            && !file.buffer.is_empty()
-            && let Err(e) = fs::write(&output_path, &file.buffer)
+    if json {
+        let entries: Vec<String> = differences
+            .iter()
+            .map(|difference| {
+                let path = json_string(&difference.path);
+                match &difference.change {
+                    Change::Added => format!(r#"{{"path":{},"change":"added"}}"#, path),
+                    Change::Removed => format!(r#"{{"path":{},"change":"removed"}}"#, path),
+                    Change::Modified => format!(r#"{{"path":{},"change":"modified"}}"#, path),
+                    Change::Mode { old, new } => format!(
+                        r#"{{"path":{},"change":"mode","old":"{:o}","new":"{:o}"}}"#,
+                        path, old, new
+                    ),
+                    Change::ModificationTime { old, new } => format!(
+                        r#"{{"path":{},"change":"mtime","old":{},"new":{}}}"#,
+                        path, old, new
+                    ),
+                }
+            })
+            .collect();
+        println!("[{}]", entries.join(","));
+        return;
+    }
+
+    for difference in &differences {
+        let path = &difference.path;
+        match &difference.change {
+            Change::Added => println!("added     {}", path),
+            Change::Removed => println!("removed   {}", path),
+            Change::Modified => println!("modified  {}", path),
+            Change::Mode { old, new } => println!("mode      {} ({:o} -> {:o})", path, old, new),
+            Change::ModificationTime { old, new } => println!(
+                "mtime     {} ({} -> {}, {:+}s)",
+                path,
+                format_timestamp(Some(*old)),
+                format_timestamp(Some(*new)),
+                *new as i64 - *old as i64
+            ),
+        }
+    }
+    match differences.len() {
+        0 => println!("No differences"),
+        1 => println!("1 difference"),
+        n => println!("{} differences", n),
+    }
+}
+
+/// Handle integrity checks of archives, exiting with an error if any is damaged
+#[cfg(feature = "decode")]
+fn handle_test(inputs: &[String], password: Option<String>) {
+    let mut options = DecodeOptions {
+        password,
+        ..Default::default()
+    };
+    let mut damaged = 0;
+
+    for input in inputs {
+        let Some(fmt) = Fmt::guess(input).or_else(|| detect_file(input)) else {
+            eprintln!("Error: Cannot identify the archive format of '{}'", input);
+            damaged += 1;
+            continue;
+        };
+
+        let verify = |options: &DecodeOptions| fmt.verify_with(open_volumes(input), options);
+        let result = match verify(&options) {
+            Err(ArchiveError::PasswordRequired)
+                if options.password.is_none() && io::stdin().is_terminal() =>
+            {
+                options.password = Some(prompt_password());
+                verify(&options)
+            }
+            result => result,
+        };
+
+        match result {
+            Ok(report) => {
+                for failure in &report.failures {
+                    match &failure.path {
+                        Some(path) => println!("FAILED  {}: {}", path, failure.reason),
+                        None => println!("FAILED  {}", failure.reason),
+                    }
+                }
+                if report.is_ok() {
+                    println!("OK      {} ({} entries)", input, report.entries);
+                } else {
+                    println!(
+                        "Damaged {} ({} failures, {} entries read)",
+                        input,
+                        report.failures.len(),
+                        report.entries
+                    );
+                    damaged += 1;
+                }
+            }
+            Err(e) => {
+                println!("FAILED  {}", input);
+                display_error(&e);
+                damaged += 1;
+            }
+        }
+    }
+
+    if damaged > 0 {
+        process::exit(1);
+    }
+}
+
+/// Handle writing a manifest of an archive, or checking it against one
+#[cfg(feature = "decode")]
+fn handle_manifest(
+    input: &str,
+    algorithm: HashAlgorithm,
+    output: Option<&str>,
+    check: Option<&str>,
+    password: Option<String>,
+) {
+    let fmt = Fmt::guess(input)
+        .or_else(|| detect_file(input))
+        .unwrap_or_else(|| {
+            eprintln!("Error: Cannot identify the archive format of '{}'", input);
+            process::exit(1);
+        });
+
+    let expected = check.map(|path| {
+        let text = fs::read_to_string(path).unwrap_or_else(|e| {
+            eprintln!("Error: Failed to read manifest '{}': {}", path, e);
+            process::exit(1);
+        });
+        Manifest::parse(&text, algorithm).unwrap_or_else(|e| {
+            display_error(&e);
+            process::exit(1);
+        })
+    });
+
+    let manifest =
+        |options: &DecodeOptions| fmt.manifest_with(open_volumes(input), algorithm, options);
+    let mut options = DecodeOptions {
+        password,
+        ..Default::default()
+    };
+    let result = match manifest(&options) {
+        Err(ArchiveError::PasswordRequired)
+            if options.password.is_none() && io::stdin().is_terminal() =>
         {
+            options.password = Some(prompt_password());
+            manifest(&options)
+        }
+        result => result,
+    };
+    let manifest = result.unwrap_or_else(|e| {
+        display_error(&e);
+        process::exit(1);
+    });
+
+    let Some(expected) = expected else {
+        match output {
+            Some(output) => {
+                if let Err(e) = fs::write(output, manifest.to_string()) {
+                    eprintln!("Error: Failed to write manifest '{}': {}", output, e);
+                    process::exit(1);
+                }
+                println!(
+                    "Wrote {} digests of {} to {}",
+                    manifest.entries.len(),
+                    input,
+                    output
+                );
+            }
+            None => print!("{}", manifest),
+        }
+        return;
+    };
+
+    let mismatches = expected.compare(&manifest);
+    for mismatch in &mismatches {
+        let path = &mismatch.path;
+        match mismatch.change {
+            Change::Removed => println!("missing   {}", path),
+            Change::Added => println!("unlisted  {}", path),
+            _ => println!("FAILED    {}", path),
+        }
+    }
+    if mismatches.is_empty() {
+        println!("All {} files match", expected.entries.len());
+    } else {
+        println!("{} mismatches", mismatches.len());
+        process::exit(1);
+    }
+}
+
+/// Handle conversion between two archive formats
+#[cfg(all(feature = "decode", feature = "encode"))]
+fn handle_convert(
+    input: &str,
+    output: &str,
+    options: &EncodeOptions,
+    password: Option<String>,
+    volume_size: Option<u64>,
+) {
+    let Some(source) = Fmt::guess(input).or_else(|| detect_file(input)) else {
+        eprintln!("Error: Cannot identify the archive format of '{}'", input);
+        process::exit(1);
+    };
+    let Some(target) = Fmt::guess(output) else {
+        eprintln!("Error: Cannot identify the archive format of '{}'", output);
+        process::exit(1);
+    };
+
+    // Creating the output would truncate the input before it is read
+    let same_file = fs::canonicalize(input)
+        .ok()
+        .is_some_and(|input| fs::canonicalize(output).ok() == Some(input));
+    if same_file {
+        eprintln!("Error: Input and output are the same file");
+        process::exit(1);
+    }
+
+    let convert = |decode_options: &DecodeOptions| {
+        let reader = open_volumes(input);
+        match volume_size {
+            Some(size) => {
+                let mut volumes = VolumeWriter::create(output, size);
+                let report =
+                    source.convert_with(reader, target, &mut volumes, decode_options, options)?;
+                Ok((report, Some(volumes.finish()?)))
+            }
+            None => {
+                let writer = io::BufWriter::new(fs::File::create(output)?);
+                let report = source.convert_seekable_with(
+                    reader,
+                    target,
+                    writer,
+                    decode_options,
+                    options,
+                )?;
+                Ok((report, None))
+            }
+        }
+    };
+
+    let mut decode_options = DecodeOptions {
+        password,
+        ..Default::default()
+    };
+    let result = match convert(&decode_options) {
+        Err(ArchiveError::PasswordRequired)
+            if decode_options.password.is_none() && io::stdin().is_terminal() =>
+        {
+            decode_options.password = Some(prompt_password());
+            convert(&decode_options)
+        }
+        result => result,
+    };
+
+    let (report, volumes) = match result {
+        Ok(result) => result,
+        Err(e) => {
+            display_error(&e);
+            process::exit(1);
+        }
+    };
+
+    for (metadata, what) in [
+        (Metadata::Mode, "permissions"),
+        (Metadata::ModificationTime, "modification times"),
+        (Metadata::Owner, "owner ids"),
+        (Metadata::Directory, "directory entries"),
+    ] {
+        let mut paths = report
+            .dropped
+            .iter()
+            .filter(|dropped| dropped.metadata == metadata)
+            .map(|dropped| &dropped.path);
+        if let Some(first) = paths.next() {
             eprintln!(
-                "Error: Failed to write file '{}': {}",
-                output_path.display(),
-                e
+                "Warning: {:?} does not store {}: {} entries affected, such as '{}'",
+                target,
+                what,
+                paths.count() + 1,
+                first
             );
-            process::exit(1);
         }
+    }
 
-        // Set permissions on Unix systems
-        #[cfg(not(windows))]
-        if let Some(mode) = file.mode {
-            use std::os::unix::fs::PermissionsExt;
-            if let Err(e) = fs::set_permissions(&output_path, fs::Permissions::from_mode(mode)) {
-                eprintln!(
-                    "Warning: Failed to set permissions for '{}': {}",
-                    output_path.display(),
-                    e
-                );
+    let output = match volumes {
+        Some(count) => format!(
+            "{} .. {}",
+            volume_path(output, 1).display(),
+            volume_path(output, count).display()
+        ),
+        None => output.to_string(),
+    };
+    println!(
+        "Converted {} entries from {} to {}",
+        report.entries, input, output
+    );
+}
+
+/// The changes requested by the `edit` command
+#[cfg(all(feature = "decode", feature = "encode", feature = "zip"))]
+struct Edits {
+    add: Vec<String>,
+    delete: Vec<String>,
+    rename: Vec<String>,
+}
+
+/// Split an `edit` argument of the form `LEFT=RIGHT`
+#[cfg(all(feature = "decode", feature = "encode", feature = "zip"))]
+fn split_pair(arg: &str) -> (&str, Option<&str>) {
+    match arg.split_once('=') {
+        Some((left, right)) => (left, Some(right)),
+        None => (arg, None),
+    }
+}
+
+/// Handle zip archive editing
+///
+/// Deletions and renames apply first, then the added files replace
+/// entries of the same name. Edits that only add and delete entries are
+/// written into the archive in place. Otherwise the original archive is
+/// replaced through a temporary file next to it, so it is left untouched
+/// if editing fails.
+#[cfg(all(feature = "decode", feature = "encode", feature = "zip"))]
+fn handle_edit(archive: &str, edits: Edits, output: Option<&str>, options: &EncodeOptions) {
+    use easy_archive::archive::Zip;
+
+    let mut added = Vec::new();
+    for arg in &edits.add {
+        let (path, name) = split_pair(arg);
+        let mut files = match collect_files(Path::new(path), false) {
+            Ok(files) if !files.is_empty() => files,
+            Ok(_) => {
+                eprintln!("Error: '{}' is not a file or directory", path);
+                process::exit(1);
+            }
+            Err(e) => {
+                eprintln!("Error: Failed to read '{}': {}", path, e);
+                process::exit(1);
+            }
+        };
+        // Entries are collected under the file name of the input
+        if let Some(name) = name {
+            let root = files[0].path.clone();
+            for file in &mut files {
+                let rest = &file.path[root.len()..];
+                file.path = format!("{}{}", name.trim_end_matches('/'), rest);
             }
         }
+        added.extend(files);
     }
 
-    println!("Decompression complete!");
+    let mut renames = Vec::new();
+    for arg in &edits.rename {
+        match split_pair(arg) {
+            (from, Some(to)) => renames.push((from, to)),
+            _ => {
+                eprintln!("Error: Expected OLD=NEW to rename, got '{}'", arg);
+                process::exit(1);
+            }
+        }
+    }
+
+    // Creating the output would truncate the archive before it is read
+    let same_file = output.is_some_and(|output| {
+        fs::canonicalize(archive)
+            .ok()
+            .is_some_and(|archive| fs::canonicalize(output).ok() == Some(archive))
+    });
+    if same_file {
+        eprintln!("Error: Leave out --output to edit the archive in place");
+        process::exit(1);
+    }
+
+    let target = output.map(PathBuf::from).unwrap_or_else(|| {
+        let mut name = Path::new(archive).as_os_str().to_owned();
+        name.push(".tmp");
+        PathBuf::from(name)
+    });
+
+    let edit = || -> Result<(usize, usize, bool), ArchiveError> {
+        let reader = io::BufReader::new(fs::File::open(archive)?);
+        let mut editor = Zip::edit_with(reader, options)?;
+        let mut deleted = 0;
+        for name in &edits.delete {
+            deleted += editor.delete(name)?;
+        }
+        let mut renamed = 0;
+        for (from, to) in &renames {
+            renamed += editor.rename(from, to)?;
+        }
+        for file in &added {
+            editor.add_file(file)?;
+        }
+        if output.is_none() && editor.can_save_in_place() {
+            let file = fs::OpenOptions::new()
+                .read(true)
+                .write(true)
+                .open(archive)?;
+            editor.save_in_place(file)?;
+            return Ok((deleted, renamed, true));
+        }
+        editor.save(io::BufWriter::new(fs::File::create(&target)?))?;
+        Ok((deleted, renamed, false))
+    };
+
+    let (deleted, renamed, in_place) = match edit() {
+        Ok(counts) => counts,
+        Err(e) => {
+            if output.is_none() {
+                let _ = fs::remove_file(&target);
+            }
+            display_error(&e);
+            process::exit(1);
+        }
+    };
+
+    if output.is_none() && !in_place {
+        let replaced = fs::metadata(archive)
+            .and_then(|metadata| fs::set_permissions(&target, metadata.permissions()))
+            .and_then(|_| fs::rename(&target, archive));
+        if let Err(e) = replaced {
+            let _ = fs::remove_file(&target);
+            eprintln!("Error: Failed to replace '{}': {}", archive, e);
+            process::exit(1);
+        }
+    }
+
+    println!(
+        "Edited {}: {} added, {} deleted, {} renamed",
+        output.unwrap_or(archive),
+        added.len(),
+        deleted,
+        renamed
+    );
 }
 
 /// Handle compression operation
 #[cfg(feature = "encode")]
-fn handle_compression(inputs: &[String], output: &str, fmt: Fmt) {
+fn handle_compression(
+    inputs: &[String],
+    output: &str,
+    fmt: Fmt,
+    options: &EncodeOptions,
+    prefix: Option<&[u8]>,
+    volume_size: Option<u64>,
+) {
     let mut all_files = Vec::new();
     let strip_root = inputs.len() == 1;
 
@@ -325,7 +1209,12 @@ fn handle_compression(inputs: &[String], output: &str, fmt: Fmt) {
     let total_size: usize = all_files.iter().map(|f| f.buffer.len()).sum();
     let file_count = all_files.len();
 
-    let buffer = match fmt.encode(all_files) {
+    let result = match prefix {
+        #[cfg(feature = "zip")]
+        Some(prefix) => easy_archive::archive::Zip::encode_with_prefix(prefix, all_files, options),
+        _ => fmt.encode_with(all_files, options),
+    };
+    let buffer = match result {
         Ok(b) => b,
         Err(e) => {
             display_error(&e);
@@ -333,11 +1222,29 @@ fn handle_compression(inputs: &[String], output: &str, fmt: Fmt) {
         }
     };
 
-    if let Err(e) = fs::write(output, &buffer) {
-        eprintln!("Error: Failed to write archive '{}': {}", output, e);
-        process::exit(1);
-    }
+    let written = match volume_size {
+        Some(size) => {
+            let mut volumes = VolumeWriter::create(output, size);
+            volumes.write_all(&buffer).and_then(|_| volumes.finish())
+        }
+        None => fs::write(output, &buffer).map(|_| 1),
+    };
+    let count = match written {
+        Ok(count) => count,
+        Err(e) => {
+            eprintln!("Error: Failed to write archive '{}': {}", output, e);
+            process::exit(1);
+        }
+    };
 
+    let output = match volume_size {
+        Some(_) => format!(
+            "{} .. {}",
+            volume_path(output, 1).display(),
+            volume_path(output, count).display()
+        ),
+        None => output.to_string(),
+    };
     println!(
         "Compressed {} files ({}) to {} ({})",
         file_count,
@@ -347,6 +1254,94 @@ fn handle_compression(inputs: &[String], output: &str, fmt: Fmt) {
     );
 }
 
+/// Reproducible output settings, when asked for or when `SOURCE_DATE_EPOCH` is set
+#[cfg(feature = "encode")]
+fn reproducible_output(requested: bool) -> Option<Reproducible> {
+    let reproducible = Reproducible::from_env();
+    (requested || reproducible.mtime.is_some()).then_some(reproducible)
+}
+
+/// Parse a size such as `4096`, `500M` or `2G`, in powers of 1024
+#[cfg(feature = "encode")]
+fn parse_size(text: &str) -> Result<u64, String> {
+    let text = text.trim();
+    let (number, shift) = match text.char_indices().last() {
+        Some((i, unit)) if unit.is_ascii_alphabetic() => {
+            let shift = match unit.to_ascii_uppercase() {
+                'K' => 10,
+                'M' => 20,
+                'G' => 30,
+                'T' => 40,
+                _ => return Err(format!("unknown size unit '{}'", unit)),
+            };
+            (&text[..i], shift)
+        }
+        _ => (text, 0),
+    };
+    number
+        .parse::<u64>()
+        .ok()
+        .and_then(|number| number.checked_mul(1 << shift))
+        .filter(|size| *size > 0)
+        .ok_or_else(|| format!("invalid size '{}'", text))
+}
+
+/// Read the data to write before a zip archive
+#[cfg(all(feature = "encode", feature = "zip"))]
+fn read_prefix(path: &str, fmt: Fmt) -> Vec<u8> {
+    if fmt != Fmt::Zip {
+        eprintln!("Error: --prefix is only supported for zip archives");
+        process::exit(1);
+    }
+    fs::read(path).unwrap_or_else(|e| {
+        eprintln!("Error: Failed to read prefix file '{}': {}", path, e);
+        process::exit(1);
+    })
+}
+
+/// Number of leading bytes read to detect the format of an input file
+const DETECT_PREFIX_SIZE: u64 = 1024 * 1024;
+
+/// Number of trailing bytes read to find the end of a zip archive behind a stub
+#[cfg(feature = "zip")]
+const DETECT_SUFFIX_SIZE: u64 = 22 + u16::MAX as u64;
+
+/// Detect the archive format of an input file from its content
+///
+/// Used when the file name does not carry a known extension. Large files
+/// are also checked for a zip archive at their end, as self-extracting
+/// executables and installer scripts carry one.
+fn detect_file(path: &str) -> Option<Fmt> {
+    use std::io::Read;
+
+    let path = Path::new(path);
+    if !path.is_file() {
+        return None;
+    }
+
+    let mut file = fs::File::open(path).ok()?;
+    let mut prefix = Vec::new();
+    (&mut file)
+        .take(DETECT_PREFIX_SIZE)
+        .read_to_end(&mut prefix)
+        .ok()?;
+    if let Some(fmt) = Fmt::detect(&prefix) {
+        return Some(fmt);
+    }
+
+    #[cfg(feature = "zip")]
+    if prefix.len() as u64 == DETECT_PREFIX_SIZE {
+        use std::io::{Seek, SeekFrom};
+
+        let mut suffix = Vec::new();
+        file.seek(SeekFrom::End(-(DETECT_SUFFIX_SIZE as i64)))
+            .ok()?;
+        file.read_to_end(&mut suffix).ok()?;
+        return Fmt::detect(&suffix).filter(|fmt| *fmt == Fmt::Zip);
+    }
+    None
+}
+
 fn get_available_path(base_path: &Path, is_directory: bool) -> String {
     if !base_path.exists() {
         return base_path.to_string_lossy().into_owned();
@@ -382,17 +1377,7 @@ fn get_default_output(inputs: &[String], input_fmt: Option<Fmt>) -> String {
         let input_path = Path::new(&inputs[0]).clean();
 
         if let Some(fmt) = input_fmt {
-            let mut dir_name = input_path
-                .file_name()
-                .map(|s| s.to_string_lossy().into_owned())
-                .unwrap_or_else(|| "archive".to_string());
-
-            for ext in fmt.extensions() {
-                if dir_name.ends_with(ext) {
-                    dir_name = dir_name[..dir_name.len() - ext.len()].to_string();
-                    break;
-                }
-            }
+            let dir_name = strip_extension(&inputs[0], fmt);
 
             let mut base_output = input_path
                 .parent()
@@ -448,10 +1433,106 @@ fn get_default_output(inputs: &[String], input_fmt: Option<Fmt>) -> String {
 
 fn main() {
     let cli = Cli::parse();
+
+    match cli.command {
+        #[cfg(feature = "decode")]
+        Some(Command::List { input, password }) => {
+            handle_list(&input, resolve_password(password));
+            return;
+        }
+        #[cfg(feature = "decode")]
+        Some(Command::Diff {
+            old,
+            new,
+            json,
+            password,
+        }) => {
+            handle_diff(&old, &new, json, resolve_password(password));
+            return;
+        }
+        #[cfg(feature = "decode")]
+        Some(Command::Test { inputs, password }) => {
+            handle_test(&inputs, resolve_password(password));
+            return;
+        }
+        #[cfg(feature = "decode")]
+        Some(Command::Manifest {
+            input,
+            algorithm,
+            output,
+            check,
+            password,
+        }) => {
+            handle_manifest(
+                &input,
+                algorithm,
+                output.as_deref(),
+                check.as_deref(),
+                resolve_password(password),
+            );
+            return;
+        }
+        #[cfg(all(feature = "decode", feature = "encode"))]
+        Some(Command::Convert {
+            input,
+            output,
+            level,
+            method,
+            threads,
+            volume_size,
+            reproducible,
+            password,
+        }) => {
+            let options = EncodeOptions {
+                level,
+                method,
+                threads,
+                password: None,
+                reproducible: reproducible_output(reproducible),
+            };
+            handle_convert(
+                &input,
+                &output,
+                &options,
+                resolve_password(password),
+                volume_size,
+            );
+            return;
+        }
+        #[cfg(all(feature = "decode", feature = "encode", feature = "zip"))]
+        Some(Command::Edit {
+            archive,
+            add,
+            delete,
+            rename,
+            output,
+            level,
+            method,
+            password,
+        }) => {
+            let options = EncodeOptions {
+                level,
+                method,
+                threads: None,
+                password: resolve_password(password),
+                reproducible: reproducible_output(false),
+            };
+            let edits = Edits {
+                add,
+                delete,
+                rename,
+            };
+            handle_edit(&archive, edits, output.as_deref(), &options);
+            return;
+        }
+        None => {}
+    }
+
     let inputs = cli.inputs;
+    let password = resolve_password(cli.password);
 
     let input_fmt = if inputs.len() == 1 {
-        Fmt::guess(&inputs[0])
+        Fmt::guess(&inputs[0]).or_else(|| detect_file(&inputs[0]))
     } else {
         None // Multiple files always evaluate to a single compression output archive
     };
@@ -467,16 +1548,46 @@ fn main() {
         #[cfg(feature = "decode")]
         (Some(fmt), None) => {
             // Decompression
-            handle_decompression(&inputs[0], &output, fmt);
+            handle_decompression(&inputs[0], &output, fmt, password);
         }
         #[cfg(feature = "encode")]
         (None, Some(fmt)) => {
             // Compression
-            handle_compression(&inputs, &output, fmt);
+            let options = EncodeOptions {
+                level: cli.level,
+                method: cli.method,
+                threads: cli.threads,
+                password,
+                reproducible: reproducible_output(cli.reproducible),
+            };
+            #[cfg(feature = "zip")]
+            let prefix = cli.prefix.map(|path| read_prefix(&path, fmt));
+            #[cfg(not(feature = "zip"))]
+            let prefix: Option<Vec<u8>> = None;
+            handle_compression(
+                &inputs,
+                &output,
+                fmt,
+                &options,
+                prefix.as_deref(),
+                cli.volume_size,
+            );
         }
+        #[cfg(all(feature = "decode", feature = "encode"))]
+        (Some(_), Some(_)) => {
+            let options = EncodeOptions {
+                level: cli.level,
+                method: cli.method,
+                threads: cli.threads,
+                password: None,
+                reproducible: reproducible_output(cli.reproducible),
+            };
+            handle_convert(&inputs[0], &output, &options, password, cli.volume_size);
+        }
+        #[cfg(not(all(feature = "decode", feature = "encode")))]
         (Some(_), Some(_)) => {
             eprintln!("Error: Both input and output are archive formats.");
-            eprintln!("Please specify one as a directory for compression/decompression.");
+            eprintln!("Converting between formats needs both encode and decode support.");
             process::exit(1);
         }
         #[cfg(all(feature = "decode", not(feature = "encode")))]
@@ -506,3 +1617,65 @@ fn main() {
         }
     }
 }
+
+#[cfg(all(test, unix))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_compress_metadata() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("easy-archive-cli-{}", process::id()));
+        let input = dir.join("input");
+        fs::create_dir_all(input.join("bin")).unwrap();
+        fs::write(input.join("bin/run.sh"), "#!/bin/sh\n").unwrap();
+        fs::set_permissions(input.join("bin/run.sh"), fs::Permissions::from_mode(0o755)).unwrap();
+        fs::write(input.join("notes.txt"), "notes").unwrap();
+        fs::set_permissions(input.join("notes.txt"), fs::Permissions::from_mode(0o600)).unwrap();
+
+        let inputs = [input.to_string_lossy().to_string()];
+        let output = dir.join("output.tar");
+        let compress = |options: &EncodeOptions| {
+            handle_compression(
+                &inputs,
+                &output.to_string_lossy(),
+                Fmt::Tar,
+                options,
+                None,
+                None,
+            );
+            Fmt::Tar.decode(fs::read(&output).unwrap()).unwrap()
+        };
+        let metadata = |files: &[File], path: &str| {
+            let file = files.iter().find(|file| file.path == path).unwrap();
+            (file.mode, file.last_modified)
+        };
+
+        // The permission bits and times of the inputs are kept
+        let files = compress(&EncodeOptions::default());
+        let (mode, last_modified) = metadata(&files, "notes.txt");
+        assert_eq!(mode, Some(0o600));
+        assert!(last_modified.is_some_and(|time| time > 1700000000));
+
+        // Reproducible archives normalize them, executables stay executable
+        let options = EncodeOptions {
+            reproducible: Some(Reproducible {
+                mtime: Some(1700000000),
+            }),
+            ..Default::default()
+        };
+        let files = compress(&options);
+        assert_eq!(
+            metadata(&files, "bin/run.sh"),
+            (Some(0o755), Some(1700000000))
+        );
+        assert_eq!(
+            metadata(&files, "notes.txt"),
+            (Some(0o644), Some(1700000000))
+        );
+        assert_eq!(metadata(&files, "bin").0, Some(0o755));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
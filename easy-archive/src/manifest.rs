@@ -0,0 +1,274 @@
+/// Per-entry digest manifests, in the format of `sha256sum`
+use crate::{
+    File, Fmt,
+    diff::{Difference, Summary, compare},
+    error::{ArchiveError, Result},
+    types::{DecodeOptions, EntryInfo},
+    utils::{LimitTracker, hash_reader},
+};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::{Read, Seek};
+use strum_macros::{Display, EnumIter, EnumString};
+
+/// Hash function of a [`Manifest`]
+///
+/// Parses from and displays as its lowercase name, e.g. `"sha256"`.
+#[derive(EnumIter, EnumString, Display, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+pub enum HashAlgorithm {
+    /// SHA-256 (the default), as written by `sha256sum`
+    #[default]
+    Sha256,
+    /// BLAKE3, as written by `b3sum`
+    #[cfg(feature = "blake3")]
+    Blake3,
+}
+
+/// A running digest of one of the [`HashAlgorithm`]s
+enum Hasher {
+    Sha256(Sha256),
+    #[cfg(feature = "blake3")]
+    Blake3(Box<blake3::Hasher>),
+}
+
+impl Hasher {
+    fn new(algorithm: HashAlgorithm) -> Self {
+        match algorithm {
+            HashAlgorithm::Sha256 => Hasher::Sha256(Sha256::new()),
+            #[cfg(feature = "blake3")]
+            HashAlgorithm::Blake3 => Hasher::Blake3(Box::default()),
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            Hasher::Sha256(hasher) => hasher.update(data),
+            #[cfg(feature = "blake3")]
+            Hasher::Blake3(hasher) => {
+                hasher.update(data);
+            }
+        }
+    }
+
+    /// The digest as lowercase hexadecimal
+    fn finalize(self) -> String {
+        let digest: [u8; 32] = match self {
+            Hasher::Sha256(hasher) => hasher.finalize().into(),
+            #[cfg(feature = "blake3")]
+            Hasher::Blake3(hasher) => hasher.finalize().into(),
+        };
+        digest.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+}
+
+/// The digest of a file of an archive
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestEntry {
+    /// The path of the file in the archive
+    pub path: String,
+    /// The digest of its content, as lowercase hexadecimal
+    pub digest: String,
+}
+
+/// The digests of the regular files of an archive
+///
+/// Displays as a checksum file such as `SHA256SUMS`, one `<digest>  <path>`
+/// line per file, which [`Manifest::parse`] reads back. Paths holding a
+/// backslash or a line break are escaped the way `sha256sum` does.
+///
+/// # Example
+/// ```no_run
+/// use easy_archive::{Fmt, HashAlgorithm};
+///
+/// let archive = std::fs::File::open("release.tar.gz")?;
+/// let manifest = Fmt::TarGz.manifest(archive, HashAlgorithm::Sha256)?;
+/// std::fs::write("SHA256SUMS", manifest.to_string())?;
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Manifest {
+    /// The hash function of the digests
+    pub algorithm: HashAlgorithm,
+    /// The files, in archive order
+    pub entries: Vec<ManifestEntry>,
+}
+
+/// Escape a path for a checksum line, returning whether it needed escaping
+fn escape(path: &str) -> (bool, String) {
+    if !path.contains(['\\', '\n', '\r']) {
+        return (false, path.to_string());
+    }
+    let escaped = path
+        .replace('\\', "\\\\")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r");
+    (true, escaped)
+}
+
+/// Undo [`escape`]
+fn unescape(path: &str) -> Option<String> {
+    let mut unescaped = String::with_capacity(path.len());
+    let mut chars = path.chars();
+    while let Some(c) = chars.next() {
+        unescaped.push(match c {
+            '\\' => match chars.next()? {
+                '\\' => '\\',
+                'n' => '\n',
+                'r' => '\r',
+                _ => return None,
+            },
+            c => c,
+        });
+    }
+    Some(unescaped)
+}
+
+impl Manifest {
+    /// Compute the manifest of decoded files
+    ///
+    /// Directories and symlinks are skipped, as they have no content.
+    ///
+    /// # Arguments
+    /// * `files` - The files of an archive
+    /// * `algorithm` - The hash function of the digests
+    pub fn from_files(files: &[File], algorithm: HashAlgorithm) -> Self {
+        let entries = files
+            .iter()
+            .filter(|file| !file.is_dir && file.link.is_none())
+            .map(|file| {
+                let mut hasher = Hasher::new(algorithm);
+                hasher.update(&file.buffer);
+                ManifestEntry {
+                    path: file.path.clone(),
+                    digest: hasher.finalize(),
+                }
+            })
+            .collect();
+        Manifest { algorithm, entries }
+    }
+
+    /// Read a checksum file, as written by `sha256sum` or `b3sum`
+    ///
+    /// Lines are `<digest>  <path>`, or `<digest> *<path>` for files hashed
+    /// in binary mode. Empty lines are skipped.
+    ///
+    /// # Arguments
+    /// * `text` - The content of the checksum file
+    /// * `algorithm` - The hash function of the digests, which the checksum
+    ///   file does not record
+    ///
+    /// # Returns
+    /// * `Ok(Manifest)` - The listed files
+    /// * `Err(ArchiveError)` - If a line is malformed
+    pub fn parse(text: &str, algorithm: HashAlgorithm) -> Result<Self> {
+        let mut entries = Vec::new();
+        for (index, line) in text.lines().enumerate() {
+            if line.is_empty() {
+                continue;
+            }
+            let invalid = |reason: &str| {
+                ArchiveError::InvalidManifest(format!("line {}: {}", index + 1, reason))
+            };
+
+            let (escaped, line) = match line.strip_prefix('\\') {
+                Some(line) => (true, line),
+                None => (false, line),
+            };
+            let (digest, path) = line
+                .split_once(' ')
+                .ok_or_else(|| invalid("expected '<digest>  <path>'"))?;
+            let path = path
+                .strip_prefix([' ', '*'])
+                .filter(|path| !path.is_empty())
+                .ok_or_else(|| invalid("expected '<digest>  <path>'"))?;
+            if digest.len() != 64 || !digest.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(invalid(&format!(
+                    "'{}' is not a {} digest",
+                    digest, algorithm
+                )));
+            }
+            let path = match escaped {
+                true => unescape(path).ok_or_else(|| invalid("invalid escape in path"))?,
+                false => path.to_string(),
+            };
+
+            entries.push(ManifestEntry {
+                path,
+                digest: digest.to_ascii_lowercase(),
+            });
+        }
+        Ok(Manifest { algorithm, entries })
+    }
+
+    /// Compare the files of an archive against this manifest
+    ///
+    /// Files missing from the archive are reported as [`Change::Removed`],
+    /// files the manifest does not list as [`Change::Added`], and files whose
+    /// digest differs as [`Change::Modified`]. Both manifests are expected
+    /// to use the same [`HashAlgorithm`].
+    ///
+    /// [`Change::Removed`]: crate::Change::Removed
+    /// [`Change::Added`]: crate::Change::Added
+    /// [`Change::Modified`]: crate::Change::Modified
+    ///
+    /// # Arguments
+    /// * `actual` - The manifest computed from the archive
+    ///
+    /// # Returns
+    /// The differences, sorted by path
+    pub fn compare(&self, actual: &Manifest) -> Vec<Difference> {
+        fn summarize(manifest: &Manifest) -> BTreeMap<String, Summary<&str>> {
+            manifest
+                .entries
+                .iter()
+                .map(|entry| {
+                    let summary = Summary::new(false, None, None, None, entry.digest.as_str());
+                    (entry.path.clone(), summary)
+                })
+                .collect()
+        }
+        compare(summarize(self), summarize(actual), 1)
+    }
+}
+
+impl fmt::Display for Manifest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for entry in &self.entries {
+            let (escaped, path) = escape(&entry.path);
+            let marker = if escaped { "\\" } else { "" };
+            writeln!(f, "{}{}  {}", marker, entry.digest, path)?;
+        }
+        Ok(())
+    }
+}
+
+/// Compute the manifest of an archive, streaming its entries
+///
+/// See [`Fmt::manifest_with`].
+pub(crate) fn manifest<R: Read + Seek>(
+    fmt: Fmt,
+    mut reader: R,
+    algorithm: HashAlgorithm,
+    options: &DecodeOptions,
+) -> Result<Manifest> {
+    let mut tracker = LimitTracker::for_stream(&mut reader, &options.limits)?;
+
+    let mut entries = Vec::new();
+    fmt.for_each_entry(reader, options, &mut |info: &EntryInfo, content| {
+        if info.is_dir || info.link.is_some() {
+            return tracker.add(&info.path, info.size);
+        }
+        let mut hasher = Hasher::new(algorithm);
+        tracker.stream(info, content, |content| {
+            hash_reader(content, |data| hasher.update(data))
+        })?;
+        entries.push(ManifestEntry {
+            path: info.path.clone(),
+            digest: hasher.finalize(),
+        });
+        Ok(())
+    })?;
+    Ok(Manifest { algorithm, entries })
+}
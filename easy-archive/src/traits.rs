@@ -1,6 +1,11 @@
 /// Traits for archive encoding and decoding operations
 use crate::{File, error::Result};
 
+#[cfg(feature = "decode")]
+use crate::types::DecodeOptions;
+#[cfg(feature = "encode")]
+use crate::types::EncodeOptions;
+
 /// Trait for decoding archives from bytes
 ///
 /// Implementors of this trait can decode archive data into a list of files.
@@ -26,6 +31,25 @@ pub trait Decode {
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
     fn decode<T: AsRef<[u8]>>(buffer: T) -> Result<Vec<File>>;
+
+    /// Decode an archive from a byte buffer with custom options
+    ///
+    /// The default implementation decodes with [`Decode::decode`] and checks
+    /// the limits on the result, after the data has been unpacked.
+    ///
+    /// # Arguments
+    /// * `buffer` - The archive data (can be any type that converts to &[u8])
+    /// * `options` - The limits on the unpacked data
+    fn decode_with<T: AsRef<[u8]>>(buffer: T, options: &DecodeOptions) -> Result<Vec<File>> {
+        let packed = buffer.as_ref().len();
+        let files = Self::decode(buffer)?;
+
+        let mut tracker = crate::utils::LimitTracker::new(&options.limits, packed);
+        for file in &files {
+            tracker.add(&file.path, file.buffer.len() as u64)?;
+        }
+        Ok(files)
+    }
 }
 
 /// Trait for encoding files into archives
@@ -59,6 +83,38 @@ pub trait Encode {
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
     fn encode(files: Vec<File>) -> Result<Vec<u8>>;
+
+    /// Encode files into an archive with custom options
+    ///
+    /// The default implementation ignores the options and calls [`Encode::encode`].
+    ///
+    /// # Arguments
+    /// * `files` - The list of files to include in the archive
+    /// * `options` - The compression level, ZIP method and worker threads
+    fn encode_with(files: Vec<File>, options: &EncodeOptions) -> Result<Vec<u8>> {
+        let _ = options;
+        Self::encode(files)
+    }
+}
+
+/// A compressing writer that has to be finalized once all data is written
+///
+/// Implemented by the compressor of every TAR variant and single-file
+/// format, so that data can be streamed into it regardless of the
+/// compression in use.
+#[cfg(all(
+    feature = "encode",
+    any(
+        feature = "tar",
+        feature = "gz",
+        feature = "xz",
+        feature = "bz",
+        feature = "zstd"
+    )
+))]
+pub(crate) trait Compressor: std::io::Write {
+    /// Flush the remaining data and write the stream trailer
+    fn finish(self: Box<Self>) -> std::io::Result<()>;
 }
 
 /// Combined trait for types that support both encoding and decoding
@@ -1,33 +1,70 @@
 /// Core types for archive operations
 pub use strum::IntoEnumIterator;
-use strum_macros::EnumIter;
+use strum_macros::{Display, EnumIter, EnumString};
 
 use crate::error::Result;
 
+#[cfg(all(feature = "tar", feature = "decode"))]
+use crate::{archive::tar::Entries, error::ArchiveError};
+#[cfg(feature = "decode")]
+use std::io::{Read, Seek};
+
+#[cfg(feature = "ar")]
+use crate::archive::Ar;
+#[cfg(feature = "bz")]
+use crate::archive::Bz;
+#[cfg(feature = "cpio")]
+use crate::archive::Cpio;
+#[cfg(feature = "gz")]
+use crate::archive::Gz;
 #[cfg(feature = "tar")]
 use crate::archive::Tar;
 #[cfg(feature = "tar-bz")]
 use crate::archive::TarBz;
 #[cfg(feature = "tar-gz")]
 use crate::archive::TarGz;
+#[cfg(feature = "tar-lz")]
+use crate::archive::TarLz;
+#[cfg(feature = "tar-lz4")]
+use crate::archive::TarLz4;
+#[cfg(feature = "tar-lzma")]
+use crate::archive::TarLzma;
 #[cfg(feature = "tar-xz")]
 use crate::archive::TarXz;
+#[cfg(feature = "tar-z")]
+use crate::archive::TarZ;
 #[cfg(feature = "tar-zstd")]
 use crate::archive::TarZstd;
+#[cfg(feature = "xz")]
+use crate::archive::Xz;
 #[cfg(feature = "zip")]
 use crate::archive::Zip;
+#[cfg(feature = "zstd")]
+use crate::archive::Zstd;
 #[cfg(feature = "7z")]
 use crate::archive::seven_zip::SevenZip;
 
+#[cfg(all(feature = "decode", feature = "encode"))]
+use crate::convert::ConvertReport;
+#[cfg(feature = "decode")]
+use crate::diff::Difference;
+#[cfg(feature = "decode")]
+use crate::manifest::{HashAlgorithm, Manifest};
 #[cfg(feature = "decode")]
 use crate::traits::Decode;
+#[cfg(feature = "decode")]
+use crate::verify::VerifyReport;
 #[cfg(feature = "encode")]
-use crate::traits::Encode;
+use crate::{traits::Encode, writer::ArchiveWriter};
 
 /// Archive format enumeration
 ///
 /// Represents the supported archive formats. Each variant is conditionally
 /// compiled based on the corresponding feature flag.
+///
+/// The single-file formats (`Gz`, `Xz`, `Bz`, `Zstd`) compress exactly one
+/// file. Decoding them yields one [`File`], named after the gzip header when
+/// it records a name, and with an empty path otherwise.
 #[cfg_attr(feature = "wasm", wasm_bindgen::prelude::wasm_bindgen)]
 #[derive(EnumIter, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Fmt {
@@ -52,6 +89,36 @@ pub enum Fmt {
     /// 7z archive format
     #[cfg(feature = "7z")]
     SevenZip,
+    /// Gzip-compressed single file (.gz)
+    #[cfg(feature = "gz")]
+    Gz,
+    /// XZ-compressed single file (.xz)
+    #[cfg(feature = "xz")]
+    Xz,
+    /// Bzip2-compressed single file (.bz2)
+    #[cfg(feature = "bz")]
+    Bz,
+    /// Zstd-compressed single file (.zst, .zstd)
+    #[cfg(feature = "zstd")]
+    Zstd,
+    /// LZ4-compressed tar archive (.tar.lz4, .tlz4)
+    #[cfg(feature = "tar-lz4")]
+    TarLz4,
+    /// LZMA-compressed tar archive in the legacy LZMA-alone container (.tar.lzma, .tlz)
+    #[cfg(feature = "tar-lzma")]
+    TarLzma,
+    /// Lzip-compressed tar archive (.tar.lz)
+    #[cfg(feature = "tar-lz")]
+    TarLz,
+    /// Tar archive compressed with Unix `compress` (.tar.Z, .taZ), decode only
+    #[cfg(feature = "tar-z")]
+    TarZ,
+    /// Unix ar archive, including Debian packages (.ar, .a, .deb)
+    #[cfg(feature = "ar")]
+    Ar,
+    /// cpio archive in the newc, crc or odc variant (.cpio)
+    #[cfg(feature = "cpio")]
+    Cpio,
 }
 
 impl Fmt {
@@ -73,7 +140,7 @@ impl Fmt {
     /// ```
     #[cfg(feature = "decode")]
     pub fn decode(&self, buffer: Vec<u8>) -> Result<Vec<File>> {
-        match self {
+        match *self {
             #[cfg(feature = "zip")]
             Fmt::Zip => Zip::decode(buffer),
             #[cfg(feature = "tar")]
@@ -88,6 +155,286 @@ impl Fmt {
             Fmt::TarZstd => TarZstd::decode(buffer),
             #[cfg(feature = "7z")]
             Fmt::SevenZip => SevenZip::decode(buffer),
+            #[cfg(feature = "gz")]
+            Fmt::Gz => Gz::decode(buffer),
+            #[cfg(feature = "xz")]
+            Fmt::Xz => Xz::decode(buffer),
+            #[cfg(feature = "bz")]
+            Fmt::Bz => Bz::decode(buffer),
+            #[cfg(feature = "zstd")]
+            Fmt::Zstd => Zstd::decode(buffer),
+            #[cfg(feature = "tar-lz4")]
+            Fmt::TarLz4 => TarLz4::decode(buffer),
+            #[cfg(feature = "tar-lzma")]
+            Fmt::TarLzma => TarLzma::decode(buffer),
+            #[cfg(feature = "tar-lz")]
+            Fmt::TarLz => TarLz::decode(buffer),
+            #[cfg(feature = "tar-z")]
+            Fmt::TarZ => TarZ::decode(buffer),
+            #[cfg(feature = "ar")]
+            Fmt::Ar => Ar::decode(buffer),
+            #[cfg(feature = "cpio")]
+            Fmt::Cpio => Cpio::decode(buffer),
+        }
+    }
+
+    /// Decode an archive from bytes with custom options
+    ///
+    /// Decoding stops with a specific error as soon as one of the
+    /// [`DecodeLimits`] is exceeded, before the offending data is buffered.
+    ///
+    /// # Arguments
+    /// * `buffer` - The archive data as bytes
+    /// * `options` - The limits on the unpacked data
+    ///
+    /// # Returns
+    /// * `Ok(Vec<File>)` - List of files extracted from the archive
+    /// * `Err(ArchiveError)` - If decoding fails or a limit is exceeded
+    ///
+    /// # Example
+    /// ```no_run
+    /// use easy_archive::{DecodeLimits, DecodeOptions, Fmt};
+    /// let data = std::fs::read("upload.tar.gz")?;
+    /// let options = DecodeOptions {
+    ///     limits: DecodeLimits {
+    ///         max_total_size: Some(1 << 30),
+    ///         max_ratio: Some(100),
+    ///         ..Default::default()
+    ///     },
+    ///     ..Default::default()
+    /// };
+    /// let files = Fmt::TarGz.decode_with(data, &options)?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[cfg(feature = "decode")]
+    pub fn decode_with(&self, buffer: Vec<u8>, options: &DecodeOptions) -> Result<Vec<File>> {
+        match *self {
+            #[cfg(feature = "zip")]
+            Fmt::Zip => Zip::decode_with(buffer, options),
+            #[cfg(feature = "tar")]
+            Fmt::Tar => Tar::decode_with(buffer, options),
+            #[cfg(feature = "tar-gz")]
+            Fmt::TarGz => TarGz::decode_with(buffer, options),
+            #[cfg(feature = "tar-xz")]
+            Fmt::TarXz => TarXz::decode_with(buffer, options),
+            #[cfg(feature = "tar-bz")]
+            Fmt::TarBz => TarBz::decode_with(buffer, options),
+            #[cfg(feature = "tar-zstd")]
+            Fmt::TarZstd => TarZstd::decode_with(buffer, options),
+            #[cfg(feature = "7z")]
+            Fmt::SevenZip => SevenZip::decode_with(buffer, options),
+            #[cfg(feature = "gz")]
+            Fmt::Gz => Gz::decode_with(buffer, options),
+            #[cfg(feature = "xz")]
+            Fmt::Xz => Xz::decode_with(buffer, options),
+            #[cfg(feature = "bz")]
+            Fmt::Bz => Bz::decode_with(buffer, options),
+            #[cfg(feature = "zstd")]
+            Fmt::Zstd => Zstd::decode_with(buffer, options),
+            #[cfg(feature = "tar-lz4")]
+            Fmt::TarLz4 => TarLz4::decode_with(buffer, options),
+            #[cfg(feature = "tar-lzma")]
+            Fmt::TarLzma => TarLzma::decode_with(buffer, options),
+            #[cfg(feature = "tar-lz")]
+            Fmt::TarLz => TarLz::decode_with(buffer, options),
+            #[cfg(feature = "tar-z")]
+            Fmt::TarZ => TarZ::decode_with(buffer, options),
+            #[cfg(feature = "ar")]
+            Fmt::Ar => Ar::decode_with(buffer, options),
+            #[cfg(feature = "cpio")]
+            Fmt::Cpio => Cpio::decode_with(buffer, options),
+        }
+    }
+
+    /// Stream the entries of an archive from a reader
+    ///
+    /// Unlike [`Fmt::decode`], the archive is never fully loaded into memory:
+    /// entries are yielded one at a time, each with a reader for its content.
+    /// Only the tar-based formats can be streamed.
+    ///
+    /// # Arguments
+    /// * `reader` - A reader providing the archive data
+    ///
+    /// # Returns
+    /// * `Ok(Entries)` - A streaming iterator over the archive entries
+    /// * `Err(ArchiveError)` - If the format cannot be streamed or the stream is invalid
+    ///
+    /// # Example
+    /// ```no_run
+    /// use easy_archive::Fmt;
+    /// let reader = std::fs::File::open("archive.tar.xz")?;
+    /// let mut entries = Fmt::TarXz.entries(reader)?;
+    /// while let Some(entry) = entries.next_entry()? {
+    ///     println!("{} {}", entry.info().path, entry.info().size);
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[cfg(all(feature = "tar", feature = "decode"))]
+    pub fn entries<'a, R: Read + 'a>(&self, reader: R) -> Result<Entries<'a>> {
+        Ok(Entries::new(self.decompressor(reader)?))
+    }
+
+    /// Wrap a reader so that it yields the uncompressed TAR stream
+    #[cfg(all(feature = "tar", feature = "decode"))]
+    fn decompressor<'a, R: Read + 'a>(&self, reader: R) -> Result<Box<dyn Read + 'a>> {
+        let reader: Box<dyn Read + 'a> = match self {
+            Fmt::Tar => Box::new(reader),
+            #[cfg(feature = "tar-gz")]
+            Fmt::TarGz => TarGz::decompress(reader)?,
+            #[cfg(feature = "tar-xz")]
+            Fmt::TarXz => TarXz::decompress(reader)?,
+            #[cfg(feature = "tar-bz")]
+            Fmt::TarBz => TarBz::decompress(reader)?,
+            #[cfg(feature = "tar-zstd")]
+            Fmt::TarZstd => TarZstd::decompress(reader)?,
+            #[cfg(feature = "tar-lz4")]
+            Fmt::TarLz4 => TarLz4::decompress(reader)?,
+            #[cfg(feature = "tar-lzma")]
+            Fmt::TarLzma => TarLzma::decompress(reader)?,
+            #[cfg(feature = "tar-lz")]
+            Fmt::TarLz => TarLz::decompress(reader)?,
+            #[cfg(feature = "tar-z")]
+            Fmt::TarZ => TarZ::decompress(reader)?,
+            #[allow(unreachable_patterns)]
+            _ => {
+                return Err(ArchiveError::UnsupportedFormat(format!(
+                    "{:?} does not support streaming entries",
+                    self
+                )));
+            }
+        };
+        Ok(reader)
+    }
+
+    /// List the entries of an archive without extracting their content
+    ///
+    /// ZIP and 7z archives only have their central directory or header
+    /// read, while TAR-based formats skip over the content blocks. Single-file
+    /// formats are decompressed to learn the size of their file.
+    ///
+    /// # Arguments
+    /// * `reader` - A reader providing the archive data
+    ///
+    /// # Returns
+    /// * `Ok(Vec<EntryInfo>)` - The metadata of every entry, in archive order
+    /// * `Err(ArchiveError)` - If the archive cannot be read
+    ///
+    /// # Example
+    /// ```no_run
+    /// use easy_archive::Fmt;
+    /// let reader = std::fs::File::open("archive.zip")?;
+    /// for info in Fmt::Zip.list(reader)? {
+    ///     println!("{} {}", info.path, info.size);
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[cfg(feature = "decode")]
+    pub fn list<R: Read + Seek>(&self, reader: R) -> Result<Vec<EntryInfo>> {
+        self.list_with(reader, &DecodeOptions::default())
+    }
+
+    /// List the entries of an archive with custom options
+    ///
+    /// Like [`Fmt::list`], using the password of the options to read 7z
+    /// archives with encrypted headers and the targets of encrypted zip symlinks.
+    ///
+    /// # Arguments
+    /// * `reader` - A reader providing the archive data
+    /// * `options` - The password of encrypted archives
+    ///
+    /// # Returns
+    /// * `Ok(Vec<EntryInfo>)` - The metadata of every entry, in archive order
+    /// * `Err(ArchiveError)` - If the archive cannot be read or the password is wrong
+    #[cfg(feature = "decode")]
+    #[cfg_attr(not(any(feature = "zip", feature = "7z")), allow(unused_variables))]
+    pub fn list_with<R: Read + Seek>(
+        &self,
+        reader: R,
+        options: &DecodeOptions,
+    ) -> Result<Vec<EntryInfo>> {
+        match *self {
+            #[cfg(feature = "zip")]
+            Fmt::Zip => Zip::list(reader, options),
+            #[cfg(feature = "7z")]
+            Fmt::SevenZip => SevenZip::list(reader, options),
+            #[cfg(feature = "gz")]
+            Fmt::Gz => Gz::list(reader),
+            #[cfg(feature = "xz")]
+            Fmt::Xz => Xz::list(reader),
+            #[cfg(feature = "bz")]
+            Fmt::Bz => Bz::list(reader),
+            #[cfg(feature = "zstd")]
+            Fmt::Zstd => Zstd::list(reader),
+            #[cfg(feature = "ar")]
+            Fmt::Ar => Ar::list(reader),
+            #[cfg(feature = "cpio")]
+            Fmt::Cpio => Cpio::list(reader),
+            #[cfg(feature = "tar")]
+            _ => {
+                let mut entries = self.entries(reader)?;
+                let mut list = Vec::new();
+                while let Some(entry) = entries.next_entry()? {
+                    list.push(entry.info().clone());
+                }
+                Ok(list)
+            }
+        }
+    }
+
+    /// Pass every entry of an archive to `f`, along with its content
+    ///
+    /// Single-file formats are decompressed twice, as their size is only
+    /// known once the whole stream is read.
+    #[cfg(feature = "decode")]
+    #[cfg_attr(
+        not(any(
+            feature = "zip",
+            feature = "7z",
+            feature = "gz",
+            feature = "xz",
+            feature = "bz",
+            feature = "zstd"
+        )),
+        allow(unused_variables)
+    )]
+    pub(crate) fn for_each_entry<R: Read + Seek>(
+        self,
+        reader: R,
+        options: &DecodeOptions,
+        f: &mut dyn FnMut(&EntryInfo, &mut dyn Read) -> Result<()>,
+    ) -> Result<()> {
+        #[allow(unreachable_patterns)]
+        match self {
+            #[cfg(feature = "zip")]
+            Fmt::Zip => Zip::for_each_entry(reader, options, f),
+            #[cfg(feature = "7z")]
+            Fmt::SevenZip => SevenZip::for_each_entry(reader, options, f),
+            #[cfg(feature = "ar")]
+            Fmt::Ar => Ar::for_each_entry(reader, f),
+            #[cfg(feature = "cpio")]
+            Fmt::Cpio => Cpio::for_each_entry(reader, f),
+            #[cfg(feature = "gz")]
+            Fmt::Gz => Gz::for_each_entry(reader, options, f),
+            #[cfg(feature = "xz")]
+            Fmt::Xz => Xz::for_each_entry(reader, options, f),
+            #[cfg(feature = "bz")]
+            Fmt::Bz => Bz::for_each_entry(reader, options, f),
+            #[cfg(feature = "zstd")]
+            Fmt::Zstd => Zstd::for_each_entry(reader, options, f),
+            #[cfg(feature = "tar")]
+            _ => {
+                let mut entries = self.entries(reader)?;
+                while let Some(mut entry) = entries.next_entry()? {
+                    let info = entry.info().clone();
+                    f(&info, &mut entry)?;
+                }
+                entries.finish()
+            }
+            #[cfg(not(feature = "tar"))]
+            _ => Err(crate::error::ArchiveError::UnsupportedFormat(format!(
+                "{:?} is not enabled",
+                self
+            ))),
         }
     }
 
@@ -116,7 +463,7 @@ impl Fmt {
     /// ```
     #[cfg(feature = "encode")]
     pub fn encode(&self, files: Vec<File>) -> Result<Vec<u8>> {
-        match self {
+        match *self {
             #[cfg(feature = "zip")]
             Fmt::Zip => Zip::encode(files),
             #[cfg(feature = "tar")]
@@ -131,9 +478,478 @@ impl Fmt {
             Fmt::TarZstd => TarZstd::encode(files),
             #[cfg(feature = "7z")]
             Fmt::SevenZip => SevenZip::encode(files),
+            #[cfg(feature = "gz")]
+            Fmt::Gz => Gz::encode(files),
+            #[cfg(feature = "xz")]
+            Fmt::Xz => Xz::encode(files),
+            #[cfg(feature = "bz")]
+            Fmt::Bz => Bz::encode(files),
+            #[cfg(feature = "zstd")]
+            Fmt::Zstd => Zstd::encode(files),
+            #[cfg(feature = "tar-lz4")]
+            Fmt::TarLz4 => TarLz4::encode(files),
+            #[cfg(feature = "tar-lzma")]
+            Fmt::TarLzma => TarLzma::encode(files),
+            #[cfg(feature = "tar-lz")]
+            Fmt::TarLz => TarLz::encode(files),
+            #[cfg(feature = "tar-z")]
+            Fmt::TarZ => TarZ::encode(files),
+            #[cfg(feature = "ar")]
+            Fmt::Ar => Ar::encode(files),
+            #[cfg(feature = "cpio")]
+            Fmt::Cpio => Cpio::encode(files),
+        }
+    }
+
+    /// Encode files into an archive with custom options
+    ///
+    /// # Arguments
+    /// * `files` - List of files to include in the archive
+    /// * `options` - Compression level, ZIP method and worker threads
+    ///
+    /// # Returns
+    /// * `Ok(Vec<u8>)` - The encoded archive as bytes
+    /// * `Err(ArchiveError)` - If encoding fails or duplicate files are detected
+    ///
+    /// # Example
+    /// ```no_run
+    /// use easy_archive::{EncodeOptions, File, Fmt, ZipMethod};
+    /// let files = vec![
+    ///     File {
+    ///         path: "hello.txt".to_string(),
+    ///         buffer: b"Hello, world!".to_vec(),
+    ///         ..Default::default()
+    ///     }
+    /// ];
+    /// let options = EncodeOptions {
+    ///     method: ZipMethod::Deflate,
+    ///     ..Default::default()
+    /// };
+    /// let archive = Fmt::Zip.encode_with(files, &options)?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[cfg(feature = "encode")]
+    pub fn encode_with(&self, files: Vec<File>, options: &EncodeOptions) -> Result<Vec<u8>> {
+        match *self {
+            #[cfg(feature = "zip")]
+            Fmt::Zip => Zip::encode_with(files, options),
+            #[cfg(feature = "tar")]
+            Fmt::Tar => Tar::encode_with(files, options),
+            #[cfg(feature = "tar-gz")]
+            Fmt::TarGz => TarGz::encode_with(files, options),
+            #[cfg(feature = "tar-xz")]
+            Fmt::TarXz => TarXz::encode_with(files, options),
+            #[cfg(feature = "tar-bz")]
+            Fmt::TarBz => TarBz::encode_with(files, options),
+            #[cfg(feature = "tar-zstd")]
+            Fmt::TarZstd => TarZstd::encode_with(files, options),
+            #[cfg(feature = "7z")]
+            Fmt::SevenZip => SevenZip::encode_with(files, options),
+            #[cfg(feature = "gz")]
+            Fmt::Gz => Gz::encode_with(files, options),
+            #[cfg(feature = "xz")]
+            Fmt::Xz => Xz::encode_with(files, options),
+            #[cfg(feature = "bz")]
+            Fmt::Bz => Bz::encode_with(files, options),
+            #[cfg(feature = "zstd")]
+            Fmt::Zstd => Zstd::encode_with(files, options),
+            #[cfg(feature = "tar-lz4")]
+            Fmt::TarLz4 => TarLz4::encode_with(files, options),
+            #[cfg(feature = "tar-lzma")]
+            Fmt::TarLzma => TarLzma::encode_with(files, options),
+            #[cfg(feature = "tar-lz")]
+            Fmt::TarLz => TarLz::encode_with(files, options),
+            #[cfg(feature = "tar-z")]
+            Fmt::TarZ => TarZ::encode_with(files, options),
+            #[cfg(feature = "ar")]
+            Fmt::Ar => Ar::encode_with(files, options),
+            #[cfg(feature = "cpio")]
+            Fmt::Cpio => Cpio::encode_with(files, options),
         }
     }
 
+    /// Create a writer that encodes an archive incrementally
+    ///
+    /// Entries are added one at a time with their content provided as a
+    /// reader, so archives larger than the available memory can be written.
+    ///
+    /// # Arguments
+    /// * `writer` - The destination of the encoded archive
+    ///
+    /// # Returns
+    /// * `Ok(ArchiveWriter)` - A writer accepting entries
+    /// * `Err(ArchiveError)` - If the writer cannot be created
+    ///
+    /// # Example
+    /// ```no_run
+    /// use easy_archive::{File, Fmt};
+    /// let output = std::fs::File::create("archive.zip")?;
+    /// let mut writer = Fmt::Zip.writer(output)?;
+    /// writer.add_file(&File {
+    ///     path: "hello.txt".to_string(),
+    ///     buffer: b"Hello, world!".to_vec(),
+    ///     ..Default::default()
+    /// })?;
+    /// writer.finish()?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[cfg(feature = "encode")]
+    pub fn writer<'a, W: std::io::Write + 'a>(&self, writer: W) -> Result<ArchiveWriter<'a>> {
+        self.writer_with(writer, &EncodeOptions::default())
+    }
+
+    /// Create an incremental writer with custom encode options
+    ///
+    /// See [`Fmt::writer`] and [`Fmt::encode_with`].
+    #[cfg(feature = "encode")]
+    pub fn writer_with<'a, W: std::io::Write + 'a>(
+        &self,
+        writer: W,
+        options: &EncodeOptions,
+    ) -> Result<ArchiveWriter<'a>> {
+        ArchiveWriter::new(*self, writer, options)
+    }
+
+    /// Create an incremental writer for a destination that can seek
+    ///
+    /// Behaves like [`Fmt::writer`], except that 7z archives are written in
+    /// place rather than staged in memory, so their size is not bounded by
+    /// the available memory. The archive starts at the current position of
+    /// `writer`.
+    ///
+    /// # Arguments
+    /// * `writer` - The destination of the encoded archive
+    ///
+    /// # Returns
+    /// * `Ok(ArchiveWriter)` - A writer accepting entries
+    /// * `Err(ArchiveError)` - If the writer cannot be created
+    ///
+    /// # Example
+    /// ```no_run
+    /// use easy_archive::{File, Fmt};
+    /// let output = std::fs::File::create("archive.7z")?;
+    /// let mut writer = Fmt::SevenZip.writer_seekable(output)?;
+    /// writer.add_file(&File {
+    ///     path: "hello.txt".to_string(),
+    ///     buffer: b"Hello, world!".to_vec(),
+    ///     ..Default::default()
+    /// })?;
+    /// writer.finish()?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[cfg(feature = "encode")]
+    pub fn writer_seekable<'a, W: std::io::Write + std::io::Seek + 'a>(
+        &self,
+        writer: W,
+    ) -> Result<ArchiveWriter<'a>> {
+        self.writer_seekable_with(writer, &EncodeOptions::default())
+    }
+
+    /// Create an incremental writer for a destination that can seek, with
+    /// custom encode options
+    ///
+    /// See [`Fmt::writer_seekable`] and [`Fmt::encode_with`].
+    #[cfg(feature = "encode")]
+    pub fn writer_seekable_with<'a, W: std::io::Write + std::io::Seek + 'a>(
+        &self,
+        writer: W,
+        options: &EncodeOptions,
+    ) -> Result<ArchiveWriter<'a>> {
+        ArchiveWriter::new_seekable(*self, writer, options)
+    }
+
+    /// Convert an archive into another format, streaming its entries
+    ///
+    /// Entries are read from `reader` and written to `writer` one at a time
+    /// along with their metadata, so neither archive is held in memory as a
+    /// whole. Single-file sources are the exception, as are 7z targets,
+    /// which are staged in memory unless written with
+    /// [`Fmt::convert_seekable`]. Metadata that the target format cannot store is
+    /// listed in the returned report, and the entries are written without it.
+    ///
+    /// # Arguments
+    /// * `reader` - A reader providing the source archive, in this format
+    /// * `target` - The format to convert to
+    /// * `writer` - The writer receiving the converted archive
+    ///
+    /// # Returns
+    /// * `Ok(ConvertReport)` - The number of entries and the dropped metadata
+    /// * `Err(ArchiveError)` - If reading the source or writing the target fails
+    ///
+    /// # Example
+    /// ```no_run
+    /// use easy_archive::Fmt;
+    ///
+    /// let input = std::fs::File::open("release.zip")?;
+    /// let output = std::fs::File::create("release.tar.zst")?;
+    /// let report = Fmt::convert(Fmt::Zip, input, Fmt::TarZstd, output)?;
+    /// for dropped in &report.dropped {
+    ///     println!("{}: {:?} dropped", dropped.path, dropped.metadata);
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[cfg(all(feature = "decode", feature = "encode"))]
+    pub fn convert<R: Read + Seek, W: std::io::Write>(
+        self,
+        reader: R,
+        target: Fmt,
+        writer: W,
+    ) -> Result<ConvertReport> {
+        self.convert_with(
+            reader,
+            target,
+            writer,
+            &DecodeOptions::default(),
+            &EncodeOptions::default(),
+        )
+    }
+
+    /// Convert an archive into another format with custom options
+    ///
+    /// The decode options hold the password and the limits of the source,
+    /// which are enforced on the data actually read from every entry. An
+    /// entry holding more or less data than it declares fails the
+    /// conversion. See [`Fmt::convert`].
+    #[cfg(all(feature = "decode", feature = "encode"))]
+    pub fn convert_with<R: Read + Seek, W: std::io::Write>(
+        self,
+        reader: R,
+        target: Fmt,
+        writer: W,
+        decode_options: &DecodeOptions,
+        encode_options: &EncodeOptions,
+    ) -> Result<ConvertReport> {
+        let output = target.writer_with(writer, encode_options)?;
+        crate::convert::convert(self, reader, target, output, decode_options)
+    }
+
+    /// Convert an archive into another format, writing to a destination
+    /// that can seek
+    ///
+    /// Behaves like [`Fmt::convert`], except that 7z targets are written in
+    /// place rather than staged in memory (see [`Fmt::writer_seekable`]).
+    #[cfg(all(feature = "decode", feature = "encode"))]
+    pub fn convert_seekable<R: Read + Seek, W: std::io::Write + Seek>(
+        self,
+        reader: R,
+        target: Fmt,
+        writer: W,
+    ) -> Result<ConvertReport> {
+        self.convert_seekable_with(
+            reader,
+            target,
+            writer,
+            &DecodeOptions::default(),
+            &EncodeOptions::default(),
+        )
+    }
+
+    /// Convert an archive into another format with custom options, writing
+    /// to a destination that can seek
+    ///
+    /// See [`Fmt::convert_seekable`] and [`Fmt::convert_with`].
+    #[cfg(all(feature = "decode", feature = "encode"))]
+    pub fn convert_seekable_with<R: Read + Seek, W: std::io::Write + Seek>(
+        self,
+        reader: R,
+        target: Fmt,
+        writer: W,
+        decode_options: &DecodeOptions,
+        encode_options: &EncodeOptions,
+    ) -> Result<ConvertReport> {
+        let output = target.writer_seekable_with(writer, encode_options)?;
+        crate::convert::convert(self, reader, target, output, decode_options)
+    }
+
+    /// Compare two archives, possibly of different formats
+    ///
+    /// The entries of both archives are streamed and their content hashed
+    /// with SHA-256, so neither archive is held in memory. Entries are
+    /// matched by path, as in [`crate::diff()`]. When either archive is a
+    /// zip, modification times are compared with its two-second precision.
+    ///
+    /// # Arguments
+    /// * `reader` - The old archive, in this format
+    /// * `other` - The format of the new archive
+    /// * `other_reader` - The new archive
+    ///
+    /// # Returns
+    /// * `Ok(Vec<Difference>)` - The differences, sorted by path
+    /// * `Err(ArchiveError)` - If either archive cannot be read, or an entry
+    ///   holds more or less data than it declares
+    ///
+    /// # Example
+    /// ```no_run
+    /// use easy_archive::Fmt;
+    ///
+    /// let old = std::fs::File::open("release-1.0.zip")?;
+    /// let new = std::fs::File::open("release-1.1.tar.gz")?;
+    /// for difference in Fmt::Zip.diff(old, Fmt::TarGz, new)? {
+    ///     println!("{}: {:?}", difference.path, difference.change);
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[cfg(feature = "decode")]
+    pub fn diff<A: Read + Seek, B: Read + Seek>(
+        self,
+        reader: A,
+        other: Fmt,
+        other_reader: B,
+    ) -> Result<Vec<Difference>> {
+        self.diff_with(reader, other, other_reader, &DecodeOptions::default())
+    }
+
+    /// Compare two archives with custom options
+    ///
+    /// The password and the limits of the options apply to both archives.
+    /// See [`Fmt::diff`].
+    #[cfg(feature = "decode")]
+    pub fn diff_with<A: Read + Seek, B: Read + Seek>(
+        self,
+        reader: A,
+        other: Fmt,
+        other_reader: B,
+        options: &DecodeOptions,
+    ) -> Result<Vec<Difference>> {
+        crate::diff::diff_archives(self, reader, other, other_reader, options)
+    }
+
+    /// Check the integrity of an archive without extracting it
+    ///
+    /// Every entry is read to its end and its content discarded, so that
+    /// the checksums of the format are compared: the CRC-32 of zip entries,
+    /// the CRCs of 7z streams, the header checksums of tar entries and the
+    /// trailing checksums of gzip, xz and zstd streams. An entry holding more
+    /// or less data than it declares counts as damaged too. A damaged entry
+    /// is reported and verification goes on with the next one, as long as
+    /// the archive structure allows.
+    ///
+    /// # Arguments
+    /// * `reader` - A reader providing the archive, in this format
+    ///
+    /// # Returns
+    /// * `Ok(VerifyReport)` - The number of entries and the failures found
+    /// * `Err(ArchiveError)` - If the archive cannot be checked, such as an
+    ///   encrypted archive without a password or one exceeding the decode limits
+    ///
+    /// # Example
+    /// ```no_run
+    /// use easy_archive::Fmt;
+    ///
+    /// let report = Fmt::Zip.verify(std::fs::File::open("upload.zip")?)?;
+    /// for failure in &report.failures {
+    ///     let path = failure.path.as_deref().unwrap_or("<archive>");
+    ///     eprintln!("{}: {}", path, failure.reason);
+    /// }
+    /// assert!(report.is_ok());
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[cfg(feature = "decode")]
+    pub fn verify<R: Read + Seek>(self, reader: R) -> Result<VerifyReport> {
+        self.verify_with(reader, &DecodeOptions::default())
+    }
+
+    /// Check the integrity of an archive with custom options
+    ///
+    /// The options hold the password and the limits, which are enforced on
+    /// the data actually read from every entry. See [`Fmt::verify`].
+    #[cfg(feature = "decode")]
+    pub fn verify_with<R: Read + Seek>(
+        self,
+        reader: R,
+        options: &DecodeOptions,
+    ) -> Result<VerifyReport> {
+        crate::verify::verify(self, reader, options)
+    }
+
+    /// Compute the digests of the regular files of an archive
+    ///
+    /// Entries are streamed and hashed one at a time, so the archive is never
+    /// held in memory. Directories and symlinks are skipped.
+    ///
+    /// # Arguments
+    /// * `reader` - A reader providing the archive, in this format
+    /// * `algorithm` - The hash function of the digests
+    ///
+    /// # Returns
+    /// * `Ok(Manifest)` - The digest of every file, in archive order
+    /// * `Err(ArchiveError)` - If the archive cannot be read, or an entry
+    ///   holds more or less data than it declares
+    ///
+    /// # Example
+    /// ```no_run
+    /// use easy_archive::{Fmt, HashAlgorithm};
+    ///
+    /// let archive = std::fs::File::open("release.zip")?;
+    /// let manifest = Fmt::Zip.manifest(archive, HashAlgorithm::Sha256)?;
+    /// print!("{}", manifest);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[cfg(feature = "decode")]
+    pub fn manifest<R: Read + Seek>(self, reader: R, algorithm: HashAlgorithm) -> Result<Manifest> {
+        self.manifest_with(reader, algorithm, &DecodeOptions::default())
+    }
+
+    /// Compute the digests of the files of an archive with custom options
+    ///
+    /// See [`Fmt::manifest`].
+    #[cfg(feature = "decode")]
+    pub fn manifest_with<R: Read + Seek>(
+        self,
+        reader: R,
+        algorithm: HashAlgorithm,
+        options: &DecodeOptions,
+    ) -> Result<Manifest> {
+        crate::manifest::manifest(self, reader, algorithm, options)
+    }
+
+    /// Check the files of an archive against a manifest
+    ///
+    /// The digests are computed with the algorithm of `expected`, and
+    /// compared as described in [`Manifest::compare`].
+    ///
+    /// # Arguments
+    /// * `reader` - A reader providing the archive, in this format
+    /// * `expected` - The manifest to check against
+    ///
+    /// # Returns
+    /// * `Ok(Vec<Difference>)` - The mismatching files, sorted by path
+    /// * `Err(ArchiveError)` - If the archive cannot be read
+    ///
+    /// # Example
+    /// ```no_run
+    /// use easy_archive::{Fmt, HashAlgorithm, Manifest};
+    ///
+    /// let sums = std::fs::read_to_string("SHA256SUMS")?;
+    /// let expected = Manifest::parse(&sums, HashAlgorithm::Sha256)?;
+    /// let archive = std::fs::File::open("release.tar.gz")?;
+    /// let mismatches = Fmt::TarGz.verify_manifest(archive, &expected)?;
+    /// assert!(mismatches.is_empty(), "{:?}", mismatches);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[cfg(feature = "decode")]
+    pub fn verify_manifest<R: Read + Seek>(
+        self,
+        reader: R,
+        expected: &Manifest,
+    ) -> Result<Vec<Difference>> {
+        self.verify_manifest_with(reader, expected, &DecodeOptions::default())
+    }
+
+    /// Check the files of an archive against a manifest with custom options
+    ///
+    /// See [`Fmt::verify_manifest`].
+    #[cfg(feature = "decode")]
+    pub fn verify_manifest_with<R: Read + Seek>(
+        self,
+        reader: R,
+        expected: &Manifest,
+        options: &DecodeOptions,
+    ) -> Result<Vec<Difference>> {
+        let actual = self.manifest_with(reader, expected.algorithm, options)?;
+        Ok(expected.compare(&actual))
+    }
+
     /// Guess the archive format from a filename
     ///
     /// # Arguments
@@ -149,8 +965,15 @@ impl Fmt {
     /// assert_eq!(Fmt::guess("archive.tar.gz"), Some(Fmt::TarGz));
     /// assert_eq!(Fmt::guess("file.zip"), Some(Fmt::Zip));
     /// assert_eq!(Fmt::guess("unknown.txt"), None);
+    ///
+    /// // Volumes of split archives
+    /// assert_eq!(Fmt::guess("archive.7z.001"), Some(Fmt::SevenZip));
+    /// assert_eq!(Fmt::guess("archive.z01"), Some(Fmt::Zip));
     /// ```
     pub fn guess(name: &str) -> Option<Self> {
+        if let Some(stem) = crate::volume::volume_stem(name) {
+            return Self::guess(&stem);
+        }
         for fmt in Fmt::iter() {
             for ext in fmt.extensions() {
                 if name.ends_with(ext) {
@@ -161,6 +984,157 @@ impl Fmt {
         None
     }
 
+    /// Detect the archive format from the leading bytes of its content
+    ///
+    /// Recognizes the gzip, xz, bzip2, zstd, LZ4, lzip, `compress`, zip, 7z,
+    /// ar, cpio and ustar magic numbers, and LZMA-alone headers.
+    /// For compressed streams the decompressed prefix is inspected, so that
+    /// only streams holding a TAR archive are reported as compressed TAR and
+    /// other streams as a single compressed file. When the prefix cannot be
+    /// decompressed (e.g. because `buffer` holds only the first bytes of a
+    /// large file) the TAR variant is assumed.
+    ///
+    /// Zip archives behind a stub, such as self-extracting executables and
+    /// installer scripts, are recognized by the end of central directory
+    /// record at the end of `buffer`.
+    ///
+    /// # Arguments
+    /// * `buffer` - The archive data, or at least its first few kilobytes
+    ///
+    /// # Returns
+    /// * `Some(Fmt)` - The detected format
+    /// * `None` - If no supported format matches
+    ///
+    /// # Example
+    /// ```no_run
+    /// use easy_archive::Fmt;
+    /// let data = std::fs::read("download")?;
+    /// let fmt = Fmt::detect(&data);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn detect(buffer: &[u8]) -> Option<Self> {
+        #[cfg(feature = "7z")]
+        if buffer.starts_with(&[0x37, 0x7A, 0xBC, 0xAF, 0x27, 0x1C]) {
+            return Some(Fmt::SevenZip);
+        }
+        // The first volume of a spanned zip archive starts with a marker
+        #[cfg(feature = "zip")]
+        if buffer.starts_with(b"PK\x03\x04")
+            || buffer.starts_with(b"PK\x05\x06")
+            || buffer.starts_with(b"PK\x07\x08PK\x03\x04")
+        {
+            return Some(Fmt::Zip);
+        }
+        #[cfg(feature = "gz")]
+        if buffer.starts_with(&[0x1F, 0x8B]) {
+            #[cfg(feature = "tar-gz")]
+            if Fmt::TarGz.holds_tar(buffer) {
+                return Some(Fmt::TarGz);
+            }
+            return Some(Fmt::Gz);
+        }
+        #[cfg(feature = "xz")]
+        if buffer.starts_with(&[0xFD, b'7', b'z', b'X', b'Z', 0x00]) {
+            #[cfg(feature = "tar-xz")]
+            if Fmt::TarXz.holds_tar(buffer) {
+                return Some(Fmt::TarXz);
+            }
+            return Some(Fmt::Xz);
+        }
+        #[cfg(feature = "bz")]
+        if buffer.starts_with(b"BZh") {
+            #[cfg(feature = "tar-bz")]
+            if Fmt::TarBz.holds_tar(buffer) {
+                return Some(Fmt::TarBz);
+            }
+            return Some(Fmt::Bz);
+        }
+        #[cfg(feature = "zstd")]
+        if buffer.starts_with(&[0x28, 0xB5, 0x2F, 0xFD]) {
+            #[cfg(feature = "tar-zstd")]
+            if Fmt::TarZstd.holds_tar(buffer) {
+                return Some(Fmt::TarZstd);
+            }
+            return Some(Fmt::Zstd);
+        }
+        #[cfg(feature = "tar-lz4")]
+        if buffer.starts_with(&[0x04, 0x22, 0x4D, 0x18]) && Fmt::TarLz4.holds_tar(buffer) {
+            return Some(Fmt::TarLz4);
+        }
+        #[cfg(feature = "tar-lz")]
+        if buffer.starts_with(b"LZIP") && Fmt::TarLz.holds_tar(buffer) {
+            return Some(Fmt::TarLz);
+        }
+        #[cfg(feature = "tar-z")]
+        if buffer.starts_with(&[0x1F, 0x9D]) && Fmt::TarZ.holds_tar(buffer) {
+            return Some(Fmt::TarZ);
+        }
+        #[cfg(feature = "ar")]
+        if buffer.starts_with(crate::archive::ar::MAGIC) {
+            return Some(Fmt::Ar);
+        }
+        #[cfg(feature = "cpio")]
+        if crate::archive::cpio::is_cpio(buffer) {
+            return Some(Fmt::Cpio);
+        }
+        #[cfg(feature = "tar")]
+        if crate::archive::tar::is_tar_header(buffer) {
+            return Some(Fmt::Tar);
+        }
+        #[cfg(feature = "zip")]
+        if ends_with_zip(buffer) {
+            return Some(Fmt::Zip);
+        }
+        // LZMA-alone has no magic number, so it is checked last
+        #[cfg(feature = "tar-lzma")]
+        if is_lzma_alone(buffer) && Fmt::TarLzma.holds_tar(buffer) {
+            return Some(Fmt::TarLzma);
+        }
+        None
+    }
+
+    /// Check whether a compressed stream starts with a TAR header
+    ///
+    /// Without the `decode` feature the stream cannot be inspected and is
+    /// assumed to hold a TAR archive.
+    #[cfg(any(
+        feature = "tar-gz",
+        feature = "tar-xz",
+        feature = "tar-bz",
+        feature = "tar-zstd",
+        feature = "tar-lz4",
+        feature = "tar-lzma",
+        feature = "tar-lz",
+        feature = "tar-z"
+    ))]
+    fn holds_tar(&self, buffer: &[u8]) -> bool {
+        #[cfg(feature = "decode")]
+        {
+            use crate::archive::tar::{BLOCK_SIZE, is_tar_header};
+
+            let Ok(mut reader) = self.decompressor(buffer) else {
+                return true;
+            };
+            let mut block = [0; BLOCK_SIZE];
+            let mut filled = 0;
+            while filled < BLOCK_SIZE {
+                match reader.read(&mut block[filled..]) {
+                    Ok(0) => break,
+                    Ok(n) => filled += n,
+                    Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                    // Truncated input, the prefix is inconclusive
+                    Err(_) => return true,
+                }
+            }
+            is_tar_header(&block[..filled])
+        }
+        #[cfg(not(feature = "decode"))]
+        {
+            let _ = buffer;
+            true
+        }
+    }
+
     /// Get the file extensions for this format
     ///
     /// # Returns
@@ -172,7 +1146,7 @@ impl Fmt {
     /// assert_eq!(Fmt::TarGz.extensions(), &[".tar.gz", ".tgz"]);
     /// ```
     pub fn extensions(&self) -> &[&'static str] {
-        match self {
+        match *self {
             #[cfg(feature = "tar")]
             Fmt::Tar => &[".tar"],
             #[cfg(feature = "tar-gz")]
@@ -187,8 +1161,110 @@ impl Fmt {
             Fmt::Zip => &[".zip"],
             #[cfg(feature = "7z")]
             Fmt::SevenZip => &[".7z"],
+            #[cfg(feature = "gz")]
+            Fmt::Gz => &[".gz"],
+            #[cfg(feature = "xz")]
+            Fmt::Xz => &[".xz"],
+            #[cfg(feature = "bz")]
+            Fmt::Bz => &[".bz2"],
+            #[cfg(feature = "zstd")]
+            Fmt::Zstd => &[".zst", ".zstd"],
+            #[cfg(feature = "tar-lz4")]
+            Fmt::TarLz4 => &[".tar.lz4", ".tlz4"],
+            #[cfg(feature = "tar-lzma")]
+            Fmt::TarLzma => &[".tar.lzma", ".tlz"],
+            #[cfg(feature = "tar-lz")]
+            Fmt::TarLz => &[".tar.lz"],
+            #[cfg(feature = "tar-z")]
+            Fmt::TarZ => &[".tar.Z", ".taZ"],
+            #[cfg(feature = "ar")]
+            Fmt::Ar => &[".ar", ".a", ".deb"],
+            #[cfg(feature = "cpio")]
+            Fmt::Cpio => &[".cpio"],
+        }
+    }
+
+    /// Check whether this format compresses a single file instead of an archive
+    ///
+    /// # Example
+    /// ```
+    /// use easy_archive::Fmt;
+    /// assert!(Fmt::Gz.is_single_file());
+    /// assert!(!Fmt::TarGz.is_single_file());
+    /// ```
+    pub fn is_single_file(&self) -> bool {
+        match self {
+            #[cfg(feature = "gz")]
+            Fmt::Gz => true,
+            #[cfg(feature = "xz")]
+            Fmt::Xz => true,
+            #[cfg(feature = "bz")]
+            Fmt::Bz => true,
+            #[cfg(feature = "zstd")]
+            Fmt::Zstd => true,
+            #[allow(unreachable_patterns)]
+            _ => false,
         }
     }
+
+    /// Check whether archives of this format can be written
+    ///
+    /// Decode-only formats fail to encode with [`ArchiveError::UnsupportedFormat`].
+    ///
+    /// # Example
+    /// ```
+    /// use easy_archive::Fmt;
+    /// assert!(Fmt::TarGz.can_encode());
+    /// assert!(!Fmt::TarZ.can_encode());
+    /// ```
+    pub fn can_encode(&self) -> bool {
+        match self {
+            #[cfg(feature = "tar-z")]
+            Fmt::TarZ => false,
+            #[allow(unreachable_patterns)]
+            _ => true,
+        }
+    }
+}
+
+/// Check whether a buffer starts with a plausible LZMA-alone header
+///
+/// The header is 13 bytes: the properties byte, a little-endian dictionary
+/// size and uncompressed size. Like `xz --format=auto`, only the dictionary
+/// sizes written by common encoders are accepted, and the uncompressed size
+/// must be unknown or below 256 GiB.
+#[cfg(feature = "tar-lzma")]
+fn is_lzma_alone(buffer: &[u8]) -> bool {
+    if buffer.len() < 13 || buffer[0] > (4 * 5 + 4) * 9 + 8 {
+        return false;
+    }
+
+    let dict_size = u32::from_le_bytes([buffer[1], buffer[2], buffer[3], buffer[4]]);
+    let high = 1u32 << (31 - dict_size.leading_zeros().min(31));
+    let dict_ok = dict_size == u32::MAX || dict_size == high || dict_size == high | (high >> 1);
+
+    let mut size = [0; 8];
+    size.copy_from_slice(&buffer[5..13]);
+    let size = u64::from_le_bytes(size);
+
+    dict_ok && (size == u64::MAX || size < 1 << 38)
+}
+
+/// Check whether a buffer ends with a zip end of central directory record
+///
+/// The record is 22 bytes long and followed by a comment of up to 64 KiB,
+/// whose length must reach exactly the end of the buffer.
+#[cfg(feature = "zip")]
+fn ends_with_zip(buffer: &[u8]) -> bool {
+    const RECORD_SIZE: usize = 22;
+    let Some(last) = buffer.len().checked_sub(RECORD_SIZE) else {
+        return false;
+    };
+    let first = last.saturating_sub(u16::MAX as usize);
+    (first..=last).rev().any(|i| {
+        buffer[i..].starts_with(b"PK\x05\x06")
+            && u16::from_le_bytes([buffer[i + 20], buffer[i + 21]]) as usize == last - i
+    })
 }
 
 /// Represents a file or directory entry in an archive
@@ -216,6 +1292,283 @@ pub struct File {
     /// Last modification time as Unix timestamp (seconds since epoch)
     #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = "lastModified"))]
     pub last_modified: Option<u64>,
+
+    /// Target of a symbolic link, `None` for regular files and directories
+    ///
+    /// Symbolic links carry no content, so `buffer` is empty.
+    #[cfg_attr(feature = "wasm", wasm_bindgen(skip))]
+    pub link: Option<String>,
+}
+
+/// Compression method used for ZIP entries
+///
+/// Parses from and displays as its lowercase name, e.g. `"deflate"`.
+#[derive(EnumIter, EnumString, Display, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+pub enum ZipMethod {
+    /// No compression
+    Stored,
+    /// Deflate, readable by every unzip tool
+    Deflate,
+    /// Bzip2
+    Bzip2,
+    /// Zstd (the default), fast with a good ratio but not supported everywhere
+    #[default]
+    Zstd,
+    /// LZMA (not supported for encoding)
+    Lzma,
+    /// XZ
+    Xz,
+}
+
+/// Options controlling how archives are encoded
+///
+/// The default options reproduce the behavior of [`Fmt::encode`].
+///
+/// # Example
+/// ```
+/// use easy_archive::{EncodeOptions, ZipMethod};
+/// let options = EncodeOptions {
+///     level: Some(9),
+///     method: ZipMethod::Deflate,
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EncodeOptions {
+    /// Compression level, or `None` for the format default
+    ///
+    /// Out-of-range levels are clamped to the range supported by the
+    /// compressor (0-9 for gzip, xz and 7z, 1-9 for bzip2, 1-22 for zstd).
+    pub level: Option<u32>,
+
+    /// Compression method of ZIP entries, ignored by other formats
+    pub method: ZipMethod,
+
+    /// Number of worker threads, or `None` for single-threaded encoding
+    ///
+    /// Used by the xz, zstd and 7z compressors; ignored by other formats
+    /// and on wasm targets.
+    pub threads: Option<u32>,
+
+    /// Password to encrypt the archive with, or `None` for no encryption
+    ///
+    /// 7z archives are encrypted with AES-256, including their headers. Zip
+    /// entries are encrypted with WinZip AES-256, leaving names and sizes
+    /// readable. Formats without encryption support fail with
+    /// [`ArchiveError::UnsupportedFormat`] instead of writing plain data.
+    ///
+    /// [`ArchiveError::UnsupportedFormat`]: crate::ArchiveError::UnsupportedFormat
+    pub password: Option<String>,
+
+    /// Make the output reproducible, or `None` to write entries as given
+    ///
+    /// See [`Reproducible`].
+    pub reproducible: Option<Reproducible>,
+}
+
+#[cfg(all(
+    feature = "encode",
+    any(
+        feature = "tar",
+        feature = "zip",
+        feature = "7z",
+        feature = "ar",
+        feature = "cpio",
+        feature = "gz"
+    )
+))]
+impl EncodeOptions {
+    /// The files to encode, sorted and normalized when the output is reproducible
+    pub(crate) fn prepare_files(&self, files: Vec<File>) -> Vec<File> {
+        match &self.reproducible {
+            Some(reproducible) => reproducible.normalize_files(files),
+            None => files,
+        }
+    }
+}
+
+/// Settings making encoded archives reproducible
+///
+/// Encoding the same files then yields the same bytes, whatever their order,
+/// owners and permissions on the machine building the archive:
+/// - entries are sorted by path (except with [`ArchiveWriter`], which writes
+///   them in the order they are added)
+/// - modification times are clamped to [`Reproducible::mtime`]
+/// - owner ids are zeroed, and owner names are never written
+/// - modes become 0755 for directories and executables, 0644 for other files
+///   and 0777 for symlinks
+/// - zip entries without a usable time get the MS-DOS epoch (1980-01-01)
+///   rather than the current time
+///
+/// Gzip headers then only hold fixed fields: no timestamp for compressed tar
+/// archives, and an unknown operating system. Encrypted archives are never
+/// reproducible, as encryption uses a random salt.
+///
+/// # Example
+/// ```
+/// use easy_archive::{EncodeOptions, Reproducible};
+/// let options = EncodeOptions {
+///     // Honors SOURCE_DATE_EPOCH, see https://reproducible-builds.org
+///     reproducible: Some(Reproducible::from_env()),
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Reproducible {
+    /// Latest modification time to record, as a Unix timestamp
+    ///
+    /// Later times are clamped to it, and entries without a time get it.
+    /// `None` keeps the times as given.
+    pub mtime: Option<u64>,
+}
+
+impl Reproducible {
+    /// Clamp modification times to the `SOURCE_DATE_EPOCH` environment variable
+    ///
+    /// Times are kept as given when the variable is unset or not a timestamp.
+    pub fn from_env() -> Self {
+        let mtime = std::env::var("SOURCE_DATE_EPOCH")
+            .ok()
+            .and_then(|epoch| epoch.trim().parse().ok());
+        Reproducible { mtime }
+    }
+
+    /// Normalize the metadata of an entry
+    #[cfg(feature = "encode")]
+    pub(crate) fn normalize(&self, info: &EntryInfo) -> EntryInfo {
+        let mode = match (info.is_dir, &info.link) {
+            (_, Some(_)) => 0o777,
+            (true, None) => 0o755,
+            (false, None) if info.mode.is_some_and(|mode| mode & 0o111 != 0) => 0o755,
+            (false, None) => 0o644,
+        };
+        let last_modified = match (info.last_modified, self.mtime) {
+            (Some(time), Some(max)) => Some(time.min(max)),
+            (time, max) => time.or(max),
+        };
+
+        EntryInfo {
+            mode: Some(mode),
+            last_modified,
+            uid: None,
+            gid: None,
+            ..info.clone()
+        }
+    }
+
+    /// Sort files by path and normalize their metadata
+    #[cfg(all(
+        feature = "encode",
+        any(
+            feature = "tar",
+            feature = "zip",
+            feature = "7z",
+            feature = "ar",
+            feature = "cpio",
+            feature = "gz"
+        )
+    ))]
+    fn normalize_files(&self, mut files: Vec<File>) -> Vec<File> {
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        for file in &mut files {
+            let info = self.normalize(&EntryInfo::from(&*file));
+            file.mode = info.mode;
+            file.last_modified = info.last_modified;
+        }
+        files
+    }
+}
+
+/// Limits guarding decoding against decompression bombs
+///
+/// Every limit is disabled when `None`. Declared sizes are checked before
+/// any data is unpacked, and the actual amount of unpacked data is enforced
+/// while reading, so an archive lying about its sizes is still stopped.
+///
+/// # Example
+/// ```
+/// use easy_archive::DecodeLimits;
+/// let limits = DecodeLimits {
+///     max_total_size: Some(1 << 30),
+///     max_entries: Some(10_000),
+///     max_ratio: Some(100),
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DecodeLimits {
+    /// Maximum number of bytes unpacked from all entries together
+    pub max_total_size: Option<u64>,
+
+    /// Maximum number of entries, including directories and symlinks
+    pub max_entries: Option<u64>,
+
+    /// Maximum number of bytes unpacked from a single entry
+    pub max_entry_size: Option<u64>,
+
+    /// Maximum ratio between the unpacked bytes and the archive size
+    pub max_ratio: Option<u64>,
+}
+
+/// Options controlling how archives are decoded
+///
+/// The default options reproduce the behavior of [`Fmt::decode`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DecodeOptions {
+    /// Limits on the amount of data unpacked from the archive
+    pub limits: DecodeLimits,
+
+    /// Password of encrypted archives, ignored by unencrypted ones
+    ///
+    /// Supports 7z AES-256 as well as zip ZipCrypto and WinZip AES entries.
+    pub password: Option<String>,
+}
+
+/// Metadata of an archive entry, without its content
+///
+/// Used by the streaming APIs, where the content is read separately
+/// from the entry header.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EntryInfo {
+    /// The relative path of the entry within the archive
+    pub path: String,
+
+    /// The size of the entry content in bytes
+    pub size: u64,
+
+    /// Unix file permissions (e.g., 0o755 for rwxr-xr-x)
+    pub mode: Option<u32>,
+
+    /// Whether this entry represents a directory
+    pub is_dir: bool,
+
+    /// Last modification time as Unix timestamp (seconds since epoch)
+    pub last_modified: Option<u64>,
+
+    /// Target of a symbolic link, `None` for regular files and directories
+    pub link: Option<String>,
+
+    /// Numeric id of the owning user, for formats that record it (tar, cpio, ar)
+    pub uid: Option<u32>,
+
+    /// Numeric id of the owning group, for formats that record it (tar, cpio, ar)
+    pub gid: Option<u32>,
+}
+
+impl From<&File> for EntryInfo {
+    fn from(file: &File) -> Self {
+        EntryInfo {
+            path: file.path.clone(),
+            size: file.buffer.len() as u64,
+            mode: file.mode,
+            is_dir: file.is_dir,
+            last_modified: file.last_modified,
+            link: file.link.clone(),
+            uid: None,
+            gid: None,
+        }
+    }
 }
 
 #[cfg(feature = "wasm")]
@@ -245,6 +1598,30 @@ impl File {
             mode,
             is_dir,
             last_modified,
+            link: None,
+        }
+    }
+
+    /// Create a new symbolic link entry
+    ///
+    /// # Arguments
+    /// * `path` - The relative path of the link within the archive
+    /// * `link` - The path the link points to
+    /// * `mode` - Optional Unix permissions
+    /// * `last_modified` - Optional modification timestamp
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = "newSymlink"))]
+    pub fn new_symlink(
+        path: String,
+        link: String,
+        mode: Option<u32>,
+        last_modified: Option<u64>,
+    ) -> Self {
+        File {
+            path,
+            mode,
+            last_modified,
+            link: Some(link),
+            ..Default::default()
         }
     }
 }
@@ -278,6 +1655,18 @@ impl File {
         self.path = path;
     }
 
+    /// Get the symbolic link target (WASM only)
+    #[wasm_bindgen(getter = link)]
+    pub fn get_link(&self) -> Option<String> {
+        self.link.clone()
+    }
+
+    /// Set the symbolic link target (WASM only)
+    #[wasm_bindgen(setter = link)]
+    pub fn set_link(&mut self, link: Option<String>) {
+        self.link = link;
+    }
+
     /// Get the buffer size in bytes (WASM only)
     #[wasm_bindgen(getter = bufferSize)]
     pub fn buffer_size(&self) -> usize {
@@ -309,6 +1698,34 @@ mod test {
             ("a.tar.xz", Fmt::TarXz),
             #[cfg(feature = "tar-bz")]
             ("a.tar.bz2", Fmt::TarBz),
+            #[cfg(feature = "gz")]
+            ("data.json.gz", Fmt::Gz),
+            #[cfg(feature = "xz")]
+            ("binary.xz", Fmt::Xz),
+            #[cfg(feature = "bz")]
+            ("a.bz2", Fmt::Bz),
+            #[cfg(feature = "zstd")]
+            ("a.zst", Fmt::Zstd),
+            #[cfg(feature = "tar-lz4")]
+            ("a.tar.lz4", Fmt::TarLz4),
+            #[cfg(feature = "tar-lz4")]
+            ("a.tlz4", Fmt::TarLz4),
+            #[cfg(feature = "tar-lzma")]
+            ("gawk-3.1.8.tar.lzma", Fmt::TarLzma),
+            #[cfg(feature = "tar-lzma")]
+            ("a.tlz", Fmt::TarLzma),
+            #[cfg(feature = "tar-lz")]
+            ("gawk-5.3.0.tar.lz", Fmt::TarLz),
+            #[cfg(feature = "tar-z")]
+            ("emacs-18.59.tar.Z", Fmt::TarZ),
+            #[cfg(feature = "tar-z")]
+            ("a.taZ", Fmt::TarZ),
+            #[cfg(feature = "ar")]
+            ("libfoo.a", Fmt::Ar),
+            #[cfg(feature = "ar")]
+            ("hello_2.10-3_amd64.deb", Fmt::Ar),
+            #[cfg(feature = "cpio")]
+            ("initramfs.cpio", Fmt::Cpio),
         ];
 
         for (name, fmt) in test_cases {
@@ -320,4 +1737,37 @@ mod test {
             );
         }
     }
+
+    #[test]
+    fn test_detect() {
+        for entry in std::fs::read_dir("../assets").unwrap() {
+            let path = entry.unwrap().path();
+            let name = path.to_string_lossy().to_string();
+            let Some(fmt) = Fmt::guess(&name) else {
+                continue;
+            };
+            let buffer = std::fs::read(&path).unwrap();
+            assert_eq!(Fmt::detect(&buffer), Some(fmt), "Failed to detect {}", name);
+            assert_eq!(
+                Fmt::detect(&buffer[..buffer.len().min(4096)]),
+                Some(fmt),
+                "Failed to detect the prefix of {}",
+                name
+            );
+        }
+
+        assert_eq!(Fmt::detect(b"hello world"), None);
+        assert_eq!(Fmt::detect(&[]), None);
+        assert_eq!(Fmt::detect(&[0; 1024]), None);
+
+        #[cfg(all(feature = "tar-gz", feature = "decode"))]
+        {
+            use std::io::Write;
+            let mut encoder =
+                flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(&[b'x'; 1024]).unwrap();
+            let gz = encoder.finish().unwrap();
+            assert_eq!(Fmt::detect(&gz), Some(Fmt::Gz));
+        }
+    }
 }
@@ -1,6 +1,21 @@
 use crate::File;
 /// Utility functions for archive operations
 use crate::error::{ArchiveError, Result};
+#[cfg(all(
+    feature = "encode",
+    any(
+        feature = "tar",
+        feature = "gz",
+        feature = "xz",
+        feature = "bz",
+        feature = "zstd",
+        feature = "ar",
+        feature = "cpio"
+    )
+))]
+use crate::types::EncodeOptions;
+#[cfg(feature = "decode")]
+use crate::types::{DecodeLimits, EntryInfo};
 use std::collections::HashSet;
 
 /// Clean and normalize a file path
@@ -129,3 +144,263 @@ pub fn check_duplicate_files(files: &[File]) -> Result<()> {
 
     Ok(())
 }
+
+/// Fail when encryption is requested from a format that can't encrypt
+///
+/// Writing plain data when a password was given would silently leak it.
+#[cfg(all(
+    feature = "encode",
+    any(
+        feature = "tar",
+        feature = "gz",
+        feature = "xz",
+        feature = "bz",
+        feature = "zstd",
+        feature = "ar",
+        feature = "cpio"
+    )
+))]
+pub(crate) fn reject_password(format: &str, options: &EncodeOptions) -> Result<()> {
+    match options.password {
+        Some(_) => Err(ArchiveError::UnsupportedFormat(format!(
+            "{} encryption",
+            format
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Upper bound for buffers pre-allocated from sizes declared by an archive
+#[cfg(feature = "decode")]
+pub(crate) const MAX_PREALLOCATION: u64 = 1 << 20;
+
+/// A reader counting the bytes read through it
+#[cfg(feature = "decode")]
+struct CountingReader<R> {
+    inner: R,
+    count: u64,
+}
+
+#[cfg(feature = "decode")]
+impl<R: std::io::Read> std::io::Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.count += n as u64;
+        Ok(n)
+    }
+}
+
+/// Tracks the data unpacked by a decode run against its [`DecodeLimits`]
+#[cfg(feature = "decode")]
+pub(crate) struct LimitTracker<'a> {
+    limits: &'a DecodeLimits,
+    packed: u64,
+    entries: u64,
+    total: u64,
+}
+
+#[cfg(feature = "decode")]
+impl<'a> LimitTracker<'a> {
+    /// Create a tracker for an archive of `packed` bytes
+    pub(crate) fn new(limits: &'a DecodeLimits, packed: usize) -> Self {
+        LimitTracker {
+            limits,
+            packed: (packed as u64).max(1),
+            entries: 0,
+            total: 0,
+        }
+    }
+
+    /// Create a tracker for the archive read by `reader`, from its current
+    /// position to its end
+    pub(crate) fn for_stream<R: std::io::Seek>(
+        reader: &mut R,
+        limits: &'a DecodeLimits,
+    ) -> Result<Self> {
+        use std::io::SeekFrom;
+
+        let start = reader.stream_position()?;
+        let packed = reader.seek(SeekFrom::End(0))? - start;
+        reader.seek(SeekFrom::Start(start))?;
+        Ok(Self::new(limits, packed as usize))
+    }
+
+    /// Check an entry count, e.g. the one declared by a central directory
+    pub(crate) fn check_count(&self, count: u64) -> Result<()> {
+        match self.limits.max_entries {
+            Some(limit) if count > limit => Err(ArchiveError::TooManyEntries { limit }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Check that `size` more bytes for the entry at `path` fit within the limits
+    fn check_size(&self, path: &str, size: u64) -> Result<()> {
+        if let Some(limit) = self.limits.max_entry_size
+            && size > limit
+        {
+            return Err(ArchiveError::EntryTooLarge {
+                path: path.to_string(),
+                limit,
+            });
+        }
+
+        let total = self.total.saturating_add(size);
+        if let Some(limit) = self.limits.max_total_size
+            && total > limit
+        {
+            return Err(ArchiveError::TotalSizeExceeded { limit });
+        }
+        if let Some(limit) = self.limits.max_ratio
+            && total > self.packed.saturating_mul(limit)
+        {
+            return Err(ArchiveError::RatioExceeded { limit });
+        }
+
+        Ok(())
+    }
+
+    /// Start reading an entry whose declared size is `size`
+    ///
+    /// # Returns
+    /// The number of bytes the entry may unpack. Reading one byte more than
+    /// that and passing the actual length to [`LimitTracker::finish`] detects
+    /// content beyond the declared size.
+    fn start(&mut self, path: &str, size: u64) -> Result<u64> {
+        self.entries += 1;
+        self.check_count(self.entries)?;
+        self.check_size(path, size)?;
+
+        let mut allowed = self.limits.max_entry_size.unwrap_or(u64::MAX);
+        if let Some(limit) = self.limits.max_total_size {
+            allowed = allowed.min(limit - self.total);
+        }
+        if let Some(limit) = self.limits.max_ratio {
+            allowed = allowed.min(self.packed.saturating_mul(limit) - self.total);
+        }
+        Ok(allowed)
+    }
+
+    /// Account for the `len` bytes actually unpacked for the entry at `path`
+    pub(crate) fn finish(&mut self, path: &str, len: u64) -> Result<()> {
+        self.check_size(path, len)?;
+        self.total += len;
+        Ok(())
+    }
+
+    /// Account for an entry that has already been unpacked
+    pub(crate) fn add(&mut self, path: &str, len: u64) -> Result<()> {
+        self.start(path, len)?;
+        self.finish(path, len)
+    }
+
+    /// Pass the content of a streamed entry to `f`, within the limits
+    ///
+    /// The content is cut one byte past what the limits allow, and whatever
+    /// `f` leaves unread is read afterwards, so the actual size of the entry
+    /// is always known. It is checked against the limits like
+    /// [`LimitTracker::read`] does, and must match the declared size.
+    ///
+    /// # Arguments
+    /// * `info` - The entry metadata, with the size declared by the archive
+    /// * `content` - The entry content
+    /// * `f` - Consumes the bounded content
+    pub(crate) fn stream<T>(
+        &mut self,
+        info: &EntryInfo,
+        content: &mut dyn std::io::Read,
+        f: impl FnOnce(&mut dyn std::io::Read) -> Result<T>,
+    ) -> Result<T> {
+        use std::io::Read;
+
+        let allowed = self.start(&info.path, info.size)?;
+        let mut content = CountingReader {
+            inner: content.take(allowed.saturating_add(1)),
+            count: 0,
+        };
+        let value = f(&mut content)?;
+        std::io::copy(&mut content, &mut std::io::sink())?;
+
+        self.finish(&info.path, content.count)?;
+        if content.count != info.size {
+            return Err(ArchiveError::SizeMismatch {
+                path: info.path.clone(),
+                declared: info.size,
+                actual: content.count,
+            });
+        }
+        Ok(value)
+    }
+
+    /// Read the content of an entry within the limits, discarding it
+    ///
+    /// # Arguments
+    /// * `path` - The entry path, for error reporting
+    /// * `size` - The entry size declared by the archive
+    /// * `reader` - The entry content
+    /// * `map_err` - Converts a read failure into a format-specific error
+    ///
+    /// # Returns
+    /// The number of bytes actually read
+    #[cfg(any(feature = "gz", feature = "xz", feature = "bz", feature = "zstd"))]
+    pub(crate) fn skip<R: std::io::Read>(
+        &mut self,
+        path: &str,
+        size: u64,
+        reader: R,
+        map_err: impl FnOnce(std::io::Error) -> ArchiveError,
+    ) -> Result<u64> {
+        let allowed = self.start(path, size)?;
+        let len = std::io::copy(
+            &mut reader.take(allowed.saturating_add(1)),
+            &mut std::io::sink(),
+        )
+        .map_err(map_err)?;
+        self.finish(path, len)?;
+        Ok(len)
+    }
+
+    /// Read the content of an entry within the limits
+    ///
+    /// # Arguments
+    /// * `path` - The entry path, for error reporting
+    /// * `size` - The entry size declared by the archive
+    /// * `reader` - The entry content
+    /// * `map_err` - Converts a read failure into a format-specific error
+    pub(crate) fn read<R: std::io::Read>(
+        &mut self,
+        path: &str,
+        size: u64,
+        reader: R,
+        map_err: impl FnOnce(std::io::Error) -> ArchiveError,
+    ) -> Result<Vec<u8>> {
+        use std::io::Read;
+
+        let allowed = self.start(path, size)?;
+        let mut buffer = Vec::with_capacity(size.min(allowed).min(MAX_PREALLOCATION) as usize);
+        reader
+            .take(allowed.saturating_add(1))
+            .read_to_end(&mut buffer)
+            .map_err(map_err)?;
+        self.finish(path, buffer.len() as u64)?;
+        Ok(buffer)
+    }
+}
+
+/// Pass `reader` to `update` up to its end, one chunk at a time
+///
+/// Reading to the end lets the format compare its checksum, e.g. the
+/// CRC-32 of a zip entry. Bound the reader with [`LimitTracker::stream`].
+#[cfg(feature = "decode")]
+pub(crate) fn hash_reader<R: std::io::Read>(
+    mut reader: R,
+    mut update: impl FnMut(&[u8]),
+) -> Result<()> {
+    let mut buffer = [0; 64 * 1024];
+    loop {
+        let n = reader.read(&mut buffer)?;
+        if n == 0 {
+            return Ok(());
+        }
+        update(&buffer[..n]);
+    }
+}
@@ -0,0 +1,87 @@
+/// Integrity checks of archives
+use crate::{
+    Fmt,
+    error::{ArchiveError, Result},
+    types::{DecodeOptions, EntryInfo},
+    utils::LimitTracker,
+};
+use std::io::{self, Read, Seek};
+
+/// A part of an archive that failed verification
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifyFailure {
+    /// The path of the entry, `None` when the archive structure itself is
+    /// damaged, such as a corrupt header or compressed stream
+    pub path: Option<String>,
+    /// Why verification failed
+    pub reason: String,
+}
+
+/// Summary of a [`Fmt::verify`] run
+#[derive(Debug, Default)]
+pub struct VerifyReport {
+    /// Number of entries read from the archive
+    pub entries: usize,
+    /// The entries and parts of the archive that failed verification
+    pub failures: Vec<VerifyFailure>,
+}
+
+impl VerifyReport {
+    /// Check whether the whole archive passed verification
+    pub fn is_ok(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+/// Check whether an error means the archive is damaged, rather than that
+/// it cannot be checked (missing password, decode limits)
+fn is_damage(error: &ArchiveError) -> bool {
+    matches!(
+        error,
+        ArchiveError::Io(_)
+            | ArchiveError::DecodeFailed { .. }
+            | ArchiveError::InvalidArchive(_)
+            | ArchiveError::DecompressionError(_)
+            | ArchiveError::SizeMismatch { .. }
+    )
+}
+
+/// Read every entry of an archive, discarding the content
+///
+/// See [`Fmt::verify_with`].
+pub(crate) fn verify<R: Read + Seek>(
+    fmt: Fmt,
+    mut reader: R,
+    options: &DecodeOptions,
+) -> Result<VerifyReport> {
+    let mut tracker = LimitTracker::for_stream(&mut reader, &options.limits)?;
+
+    let mut report = VerifyReport::default();
+    let result = fmt.for_each_entry(reader, options, &mut |info: &EntryInfo, content| {
+        report.entries += 1;
+        // Checksums are compared once an entry is read to its end
+        let result = tracker.stream(info, content, |content| {
+            io::copy(content, &mut io::sink())?;
+            Ok(())
+        });
+        match result {
+            Err(e) if is_damage(&e) => {
+                report.failures.push(VerifyFailure {
+                    path: Some(info.path.clone()),
+                    reason: e.to_string(),
+                });
+                Ok(())
+            }
+            result => result,
+        }
+    });
+
+    match result {
+        Err(e) if is_damage(&e) => report.failures.push(VerifyFailure {
+            path: None,
+            reason: e.to_string(),
+        }),
+        result => result?,
+    }
+    Ok(report)
+}
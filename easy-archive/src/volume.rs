@@ -0,0 +1,441 @@
+/// Multi-volume (split) archives
+///
+/// Large archives are often cut into volumes, either as raw byte ranges
+/// (`archive.7z.001`, `archive.tar.gz.002`, …, as written by 7-Zip or
+/// `split`) or as a spanned zip archive (`archive.z01`, …, `archive.zip`,
+/// as written by `zip -s`). [`Volumes`] reads such a set as one stream and
+/// [`VolumeWriter`] cuts an archive into volumes of a maximum size.
+#[cfg(feature = "decode")]
+use crate::error::{ArchiveError, Result};
+#[cfg(any(feature = "decode", feature = "encode"))]
+use std::fs;
+#[cfg(any(feature = "decode", feature = "encode"))]
+use std::io;
+#[cfg(feature = "decode")]
+use std::io::{BufReader, Read, Seek, SeekFrom};
+#[cfg(feature = "encode")]
+use std::io::{BufWriter, Write};
+#[cfg(any(feature = "decode", feature = "encode"))]
+use std::path::{Path, PathBuf};
+
+/// How the volumes of a split archive are named
+enum Naming<'a> {
+    /// `archive.7z.001`, `archive.7z.002`, …
+    #[cfg_attr(not(feature = "decode"), allow(dead_code))]
+    Numbered { stem: &'a str, width: usize },
+    /// `archive.z01`, `archive.z02`, …, `archive.zip`
+    Zip { stem: &'a str },
+}
+
+/// Recognize the name of a volume of a split archive
+fn parse_name(name: &str) -> Option<Naming<'_>> {
+    let (stem, ext) = name.rsplit_once('.')?;
+    if stem.is_empty() {
+        return None;
+    }
+    if ext.len() >= 2 && ext.bytes().all(|b| b.is_ascii_digit()) {
+        return Some(Naming::Numbered {
+            stem,
+            width: ext.len(),
+        });
+    }
+    match ext.strip_prefix('z') {
+        Some(number) if number.len() >= 2 && number.bytes().all(|b| b.is_ascii_digit()) => {
+            Some(Naming::Zip { stem })
+        }
+        _ => None,
+    }
+}
+
+/// Get the name of the whole archive from the name of one of its volumes
+///
+/// # Arguments
+/// * `name` - The file name of a volume
+///
+/// # Returns
+/// * `Some(String)` - The name without the volume number, e.g. `archive.7z`
+///   for `archive.7z.001` and `archive.zip` for `archive.z01`
+/// * `None` - If `name` is not named like a volume
+///
+/// # Example
+/// ```
+/// use easy_archive::volume::volume_stem;
+/// assert_eq!(volume_stem("archive.7z.001").as_deref(), Some("archive.7z"));
+/// assert_eq!(volume_stem("archive.z02").as_deref(), Some("archive.zip"));
+/// assert_eq!(volume_stem("archive.zip"), None);
+/// ```
+pub fn volume_stem(name: &str) -> Option<String> {
+    match parse_name(name)? {
+        Naming::Numbered { stem, .. } => Some(stem.to_string()),
+        Naming::Zip { stem } => Some(format!("{}.zip", stem)),
+    }
+}
+
+/// Find all volumes of the split archive a file belongs to
+///
+/// Numbered volumes are collected from `.000` or `.001` on, in the width
+/// of the given name, until the first missing number. The volumes of a
+/// spanned zip archive are `.z01`, `.z02`, … followed by the `.zip` file.
+/// Any other file is an archive of its own.
+///
+/// # Arguments
+/// * `path` - The path of any volume, usually the first one
+///
+/// # Returns
+/// * `Ok(Vec<PathBuf>)` - The paths of the volumes, in order
+/// * `Err(ArchiveError)` - If `path` does not exist or a volume is missing
+///
+/// # Example
+/// ```no_run
+/// use easy_archive::volume::volume_paths;
+/// for path in volume_paths("archive.7z.001")? {
+///     println!("{}", path.display());
+/// }
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[cfg(feature = "decode")]
+pub fn volume_paths<P: AsRef<Path>>(path: P) -> Result<Vec<PathBuf>> {
+    let path = path.as_ref();
+    fs::metadata(path)?;
+
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let sibling = |name: String| path.with_file_name(name);
+    let missing = |path: PathBuf| {
+        ArchiveError::InvalidArchive(format!(
+            "Missing volume '{}' of a split archive",
+            path.display()
+        ))
+    };
+
+    let zip_stem = match parse_name(&name) {
+        Some(Naming::Numbered { stem, width }) => {
+            let number = |i: usize| sibling(format!("{}.{:0width$}", stem, i, width = width));
+            let first = if number(0).is_file() { 0 } else { 1 };
+            let paths: Vec<_> = (first..)
+                .map(number)
+                .take_while(|path| path.is_file())
+                .collect();
+            if !paths.iter().any(|volume| volume == path) {
+                return Err(missing(number(first)));
+            }
+            return Ok(paths);
+        }
+        Some(Naming::Zip { stem }) => stem,
+        None => match name.strip_suffix(".zip") {
+            Some(stem) => stem,
+            None => return Ok(vec![path.to_path_buf()]),
+        },
+    };
+
+    let mut paths: Vec<_> = (1..)
+        .map(|i| sibling(format!("{}.z{:02}", zip_stem, i)))
+        .take_while(|path| path.is_file())
+        .collect();
+    let last = sibling(format!("{}.zip", zip_stem));
+    if !last.is_file() {
+        return Err(missing(last));
+    }
+    if !paths.is_empty() && !paths.iter().any(|volume| volume == path) && *path != last {
+        return Err(missing(sibling(format!("{}.z01", zip_stem))));
+    }
+    paths.push(last);
+    Ok(paths)
+}
+
+/// Reader over the volumes of a split archive, joined into one stream
+///
+/// The joined stream can be decoded like a single archive, by reading it
+/// into a buffer or by passing it to [`crate::Fmt::list`] and
+/// [`crate::Fmt::entries`]. The offsets of a spanned zip archive are
+/// relative to its volumes, so its central directory is rebased when the
+/// reader is created; the local entries are read from the volumes as is.
+///
+/// # Example
+/// ```no_run
+/// use easy_archive::{Fmt, volume::Volumes};
+/// use std::io::Read;
+///
+/// let mut volumes = Volumes::open("archive.7z.001")?;
+/// let mut buffer = Vec::new();
+/// volumes.read_to_end(&mut buffer)?;
+/// let files = Fmt::SevenZip.decode(buffer)?;
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[cfg(feature = "decode")]
+pub struct Volumes<R> {
+    parts: Vec<R>,
+    /// Offset of each volume in the joined stream, followed by its size
+    starts: Vec<u64>,
+    /// Rebased records replacing the end of the stream from an offset on
+    tail: Option<(u64, Vec<u8>)>,
+    pos: u64,
+    /// Volume whose position matches `pos`
+    current: Option<usize>,
+}
+
+#[cfg(feature = "decode")]
+impl Volumes<BufReader<fs::File>> {
+    /// Open the split archive a file belongs to
+    ///
+    /// # Arguments
+    /// * `path` - The path of any volume, see [`volume_paths`]
+    ///
+    /// # Returns
+    /// * `Ok(Volumes)` - The joined volumes
+    /// * `Err(ArchiveError)` - If a volume is missing or cannot be read
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let parts = volume_paths(path)?
+            .iter()
+            .map(|path| fs::File::open(path).map(BufReader::new))
+            .collect::<io::Result<Vec<_>>>()?;
+        Self::new(parts)
+    }
+}
+
+#[cfg(feature = "decode")]
+impl<R: Read + Seek> Volumes<R> {
+    /// Join volumes given in order
+    ///
+    /// # Arguments
+    /// * `parts` - The volumes, e.g. files or in-memory cursors
+    ///
+    /// # Returns
+    /// * `Ok(Volumes)` - The joined volumes
+    /// * `Err(ArchiveError)` - If a volume cannot be read or a spanned zip
+    ///   archive is malformed
+    pub fn new(mut parts: Vec<R>) -> Result<Self> {
+        let mut starts = Vec::with_capacity(parts.len() + 1);
+        let mut len = 0;
+        for part in &mut parts {
+            starts.push(len);
+            len += part.seek(SeekFrom::End(0))?;
+        }
+        starts.push(len);
+
+        #[allow(unused_mut)]
+        let mut volumes = Volumes {
+            parts,
+            starts,
+            tail: None,
+            pos: 0,
+            current: None,
+        };
+
+        #[cfg(feature = "zip")]
+        {
+            let starts = volumes.starts.clone();
+            volumes.tail = crate::archive::zip::split::join_split_zip(&mut volumes, &starts)?;
+            volumes.pos = 0;
+        }
+
+        Ok(volumes)
+    }
+
+    /// Get the number of volumes
+    pub fn count(&self) -> usize {
+        self.parts.len()
+    }
+
+    /// Get the size of the joined stream in bytes
+    pub fn size(&self) -> u64 {
+        self.starts.last().copied().unwrap_or(0)
+    }
+}
+
+#[cfg(feature = "decode")]
+impl<R: Read + Seek> Read for Volumes<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let end = match &self.tail {
+            Some((offset, tail)) if self.pos >= *offset => {
+                let start = ((self.pos - offset) as usize).min(tail.len());
+                let n = buf.len().min(tail.len() - start);
+                buf[..n].copy_from_slice(&tail[start..start + n]);
+                self.pos += n as u64;
+                return Ok(n);
+            }
+            Some((offset, _)) => *offset,
+            None => self.size(),
+        };
+        if self.pos >= end || buf.is_empty() {
+            return Ok(0);
+        }
+
+        // The first volume ending after the position holds it
+        let i = self.starts[1..].partition_point(|volume_end| *volume_end <= self.pos);
+        if self.current != Some(i) {
+            self.parts[i].seek(SeekFrom::Start(self.pos - self.starts[i]))?;
+            self.current = Some(i);
+        }
+
+        let max = (buf.len() as u64)
+            .min(self.starts[i + 1].min(end) - self.pos)
+            .try_into()
+            .unwrap_or(buf.len());
+        let n = self.parts[i].read(&mut buf[..max])?;
+        if n == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "volume is shorter than when it was opened",
+            ));
+        }
+        self.pos += n as u64;
+        Ok(n)
+    }
+}
+
+#[cfg(feature = "decode")]
+impl<R: Read + Seek> Seek for Volumes<R> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let target = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(offset) => self.size().checked_add_signed(offset),
+            SeekFrom::Current(offset) => self.pos.checked_add_signed(offset),
+        };
+        let Some(target) = target else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            ));
+        };
+        if target != self.pos {
+            self.pos = target;
+            self.current = None;
+        }
+        Ok(self.pos)
+    }
+}
+
+/// Writer cutting its output into volumes of a maximum size
+///
+/// The output is cut at byte boundaries, like 7-Zip's `-v` switch and
+/// `split` do, whatever the archive format. The volumes are read back
+/// with [`Volumes`], 7-Zip, or by concatenating them. Zip archives are not
+/// written as a spanned set, so their volumes are numbered like the others.
+///
+/// # Example
+/// ```no_run
+/// use easy_archive::{File, Fmt, volume::VolumeWriter};
+///
+/// // Writes archive.7z.001, archive.7z.002, … of at most 2 GiB each
+/// let mut volumes = VolumeWriter::create("archive.7z", 2 << 30);
+/// let mut writer = Fmt::SevenZip.writer(&mut volumes)?;
+/// writer.add_file(&File {
+///     path: "hello.txt".to_string(),
+///     buffer: b"Hello, world!".to_vec(),
+///     ..Default::default()
+/// })?;
+/// writer.finish()?;
+/// let count = volumes.finish()?;
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[cfg(feature = "encode")]
+pub struct VolumeWriter<W: Write> {
+    create: Box<dyn FnMut(usize) -> io::Result<W>>,
+    volume_size: u64,
+    current: Option<W>,
+    /// Bytes written to the current volume
+    written: u64,
+    /// Number of volumes created so far
+    count: usize,
+}
+
+#[cfg(feature = "encode")]
+impl VolumeWriter<BufWriter<fs::File>> {
+    /// Create a writer of volumes named after the archive
+    ///
+    /// The volumes are `path` followed by `.001`, `.002`, …, created as
+    /// the output reaches them.
+    ///
+    /// # Arguments
+    /// * `path` - The path of the whole archive, e.g. `archive.7z`
+    /// * `volume_size` - The maximum size of a volume in bytes
+    ///
+    /// # Panics
+    /// If `volume_size` is zero
+    pub fn create<P: AsRef<Path>>(path: P, volume_size: u64) -> Self {
+        let path = path.as_ref().to_path_buf();
+        VolumeWriter::new(volume_size, move |index| {
+            fs::File::create(volume_path(&path, index)).map(BufWriter::new)
+        })
+    }
+}
+
+#[cfg(feature = "encode")]
+impl<W: Write> VolumeWriter<W> {
+    /// Create a writer of volumes opened by a callback
+    ///
+    /// # Arguments
+    /// * `volume_size` - The maximum size of a volume in bytes
+    /// * `create` - Opens the volume of the given number, starting at 1
+    ///
+    /// # Panics
+    /// If `volume_size` is zero
+    pub fn new<F>(volume_size: u64, create: F) -> Self
+    where
+        F: FnMut(usize) -> io::Result<W> + 'static,
+    {
+        assert!(volume_size > 0, "volume size must be positive");
+        VolumeWriter {
+            create: Box::new(create),
+            volume_size,
+            current: None,
+            written: 0,
+            count: 0,
+        }
+    }
+
+    /// Flush the last volume
+    ///
+    /// # Returns
+    /// * `Ok(usize)` - The number of volumes written
+    /// * `Err(io::Error)` - If flushing fails
+    pub fn finish(mut self) -> io::Result<usize> {
+        self.flush()?;
+        Ok(self.count)
+    }
+}
+
+#[cfg(feature = "encode")]
+impl<W: Write> Write for VolumeWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let mut volume = match self.current.take() {
+            Some(volume) if self.written < self.volume_size => volume,
+            full => {
+                if let Some(mut full) = full {
+                    full.flush()?;
+                }
+                self.count += 1;
+                self.written = 0;
+                (self.create)(self.count)?
+            }
+        };
+
+        let max = (buf.len() as u64).min(self.volume_size - self.written) as usize;
+        let result = volume.write(&buf[..max]);
+        self.current = Some(volume);
+        let n = result?;
+        self.written += n as u64;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match &mut self.current {
+            Some(volume) => volume.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Get the path of a numbered volume, e.g. `archive.7z.001`
+#[cfg(feature = "encode")]
+pub fn volume_path<P: AsRef<Path>>(path: P, index: usize) -> PathBuf {
+    let mut name = path.as_ref().as_os_str().to_owned();
+    name.push(format!(".{:03}", index));
+    PathBuf::from(name)
+}
@@ -0,0 +1,459 @@
+/// Incremental archive writer
+use crate::{
+    File, Fmt,
+    error::{ArchiveError, Result},
+    types::{EncodeOptions, EntryInfo, Reproducible},
+};
+use std::collections::HashSet;
+use std::convert::Infallible;
+#[cfg(feature = "7z")]
+use std::io::{Cursor, Seek, SeekFrom};
+use std::io::{Read, Write};
+use std::marker::PhantomData;
+
+#[cfg(feature = "tar")]
+use crate::archive::tar::encode::{append_tar_entry, finish_tar_archive};
+#[cfg(any(
+    feature = "tar",
+    feature = "gz",
+    feature = "xz",
+    feature = "bz",
+    feature = "zstd"
+))]
+use crate::traits::Compressor;
+
+#[cfg(feature = "ar")]
+use crate::archive::Ar;
+#[cfg(feature = "tar")]
+use crate::archive::Tar;
+#[cfg(feature = "tar-bz")]
+use crate::archive::TarBz;
+#[cfg(feature = "tar-gz")]
+use crate::archive::TarGz;
+#[cfg(feature = "tar-lz")]
+use crate::archive::TarLz;
+#[cfg(feature = "tar-lz4")]
+use crate::archive::TarLz4;
+#[cfg(feature = "tar-lzma")]
+use crate::archive::TarLzma;
+#[cfg(feature = "tar-xz")]
+use crate::archive::TarXz;
+#[cfg(feature = "tar-z")]
+use crate::archive::TarZ;
+#[cfg(feature = "tar-zstd")]
+use crate::archive::TarZstd;
+#[cfg(feature = "cpio")]
+use crate::archive::cpio::encode::CpioWriter;
+
+#[cfg(feature = "bz")]
+use crate::archive::Bz;
+#[cfg(feature = "gz")]
+use crate::archive::Gz;
+#[cfg(feature = "xz")]
+use crate::archive::Xz;
+#[cfg(feature = "zstd")]
+use crate::archive::Zstd;
+
+/// Format-specific writer state
+enum Inner<'a> {
+    #[cfg(feature = "tar")]
+    Tar(tar::Builder<Box<dyn Compressor + 'a>>),
+    #[cfg(feature = "zip")]
+    Zip {
+        writer: Box<zip::ZipWriter<zip::write::StreamWriter<Box<dyn Write + 'a>>>>,
+        options: EncodeOptions,
+    },
+    #[cfg(feature = "7z")]
+    SevenZip(sevenz_rust2::ArchiveWriter<SevenZipOutput<'a>>),
+    #[cfg(feature = "ar")]
+    Ar(Box<dyn Write + 'a>),
+    #[cfg(feature = "cpio")]
+    Cpio(CpioWriter<Box<dyn Write + 'a>>),
+    #[cfg(any(feature = "gz", feature = "xz", feature = "bz", feature = "zstd"))]
+    Single {
+        format: &'static str,
+        /// Creates the compressor once the metadata of the only file is known
+        start: Option<StartCompressor<'a>>,
+        compressor: Option<Box<dyn Compressor + 'a>>,
+    },
+    /// Keeps `'a` in use when no format is enabled; never constructed
+    #[allow(dead_code)]
+    Unused(Infallible, PhantomData<&'a ()>),
+}
+
+/// A writer that can also seek, for formats that patch their headers
+#[cfg(feature = "7z")]
+trait WriteSeek: Write + Seek {}
+
+#[cfg(feature = "7z")]
+impl<W: Write + Seek> WriteSeek for W {}
+
+/// Destination of a 7z archive, whose start header is written last
+#[cfg(feature = "7z")]
+enum SevenZipOutput<'a> {
+    /// Staged in memory and copied to `output` once finished
+    Staged {
+        buffer: Cursor<Vec<u8>>,
+        output: Box<dyn Write + 'a>,
+    },
+    /// Written in place, with offsets relative to `start`
+    Seekable {
+        writer: Box<dyn WriteSeek + 'a>,
+        start: u64,
+    },
+}
+
+#[cfg(feature = "7z")]
+impl SevenZipOutput<'_> {
+    /// Write out a staged archive and flush the destination
+    fn finish(self) -> Result<()> {
+        match self {
+            SevenZipOutput::Staged { buffer, mut output } => {
+                output.write_all(&buffer.into_inner())?;
+                output.flush()?;
+            }
+            SevenZipOutput::Seekable { mut writer, .. } => writer.flush()?,
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "7z")]
+impl Write for SevenZipOutput<'_> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            SevenZipOutput::Staged { buffer, .. } => buffer.write(buf),
+            SevenZipOutput::Seekable { writer, .. } => writer.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            SevenZipOutput::Staged { buffer, .. } => buffer.flush(),
+            SevenZipOutput::Seekable { writer, .. } => writer.flush(),
+        }
+    }
+}
+
+#[cfg(feature = "7z")]
+impl Seek for SevenZipOutput<'_> {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        match self {
+            SevenZipOutput::Staged { buffer, .. } => buffer.seek(pos),
+            SevenZipOutput::Seekable { writer, start } => {
+                let pos = match pos {
+                    SeekFrom::Start(offset) => SeekFrom::Start(*start + offset),
+                    pos => pos,
+                };
+                let position = writer.seek(pos)?;
+                position.checked_sub(*start).ok_or_else(|| {
+                    std::io::Error::new(
+                        std::io::ErrorKind::InvalidInput,
+                        "seek before the start of the archive",
+                    )
+                })
+            }
+        }
+    }
+}
+
+/// Deferred creation of the compressor of a single-file format
+#[cfg(any(feature = "gz", feature = "xz", feature = "bz", feature = "zstd"))]
+type StartCompressor<'a> = Box<dyn FnOnce(&EntryInfo) -> Result<Box<dyn Compressor + 'a>> + 'a>;
+
+#[cfg(any(feature = "gz", feature = "xz", feature = "bz", feature = "zstd"))]
+impl<'a> Inner<'a> {
+    /// Create the state of a single-file format
+    fn single(
+        format: &'static str,
+        options: &EncodeOptions,
+        start: impl FnOnce(&EntryInfo) -> Result<Box<dyn Compressor + 'a>> + 'a,
+    ) -> Result<Self> {
+        // Fail before any entry is added, like the other formats
+        crate::utils::reject_password(format, options)?;
+        Ok(Inner::Single {
+            format,
+            start: Some(Box::new(start)),
+            compressor: None,
+        })
+    }
+}
+
+/// Writes an archive incrementally, one entry at a time
+///
+/// Entries are streamed to the underlying writer as they are added, so only
+/// the entry being written has to be available. Obtain a writer with
+/// [`Fmt::writer`] and call [`ArchiveWriter::finish`] once all entries are added.
+///
+/// ZIP archives are written in streaming mode, using data descriptors.
+/// 7z archives need to patch their start header once all entries are written.
+/// Writers from [`Fmt::writer_seekable`] do so in place; with [`Fmt::writer`]
+/// the whole compressed archive is staged in memory instead and copied to
+/// the underlying writer on [`ArchiveWriter::finish`], so its size is bounded
+/// by the available memory.
+///
+/// Single-file formats (gz, xz, bz2, zst) accept exactly one regular file.
+/// ar archives are flat: directories are skipped and symbolic links rejected.
+///
+/// # Example
+/// ```no_run
+/// use easy_archive::{EntryInfo, Fmt};
+///
+/// let output = std::fs::File::create("archive.tar.gz")?;
+/// let mut writer = Fmt::TarGz.writer(output)?;
+///
+/// let content = std::fs::File::open("large.bin")?;
+/// let info = EntryInfo {
+///     path: "large.bin".to_string(),
+///     size: content.metadata()?.len(),
+///     ..Default::default()
+/// };
+/// writer.add(&info, content)?;
+/// writer.finish()?;
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub struct ArchiveWriter<'a> {
+    inner: Inner<'a>,
+    paths: HashSet<String>,
+    reproducible: Option<Reproducible>,
+}
+
+impl<'a> ArchiveWriter<'a> {
+    /// Create a writer for the given format
+    pub(crate) fn new<W: Write + 'a>(fmt: Fmt, writer: W, options: &EncodeOptions) -> Result<Self> {
+        let inner = match fmt {
+            #[cfg(feature = "tar")]
+            Fmt::Tar => Inner::Tar(tar::Builder::new(Tar::compressor(writer, options)?)),
+            #[cfg(feature = "tar-gz")]
+            Fmt::TarGz => Inner::Tar(tar::Builder::new(TarGz::compressor(writer, options)?)),
+            #[cfg(feature = "tar-xz")]
+            Fmt::TarXz => Inner::Tar(tar::Builder::new(TarXz::compressor(writer, options)?)),
+            #[cfg(feature = "tar-bz")]
+            Fmt::TarBz => Inner::Tar(tar::Builder::new(TarBz::compressor(writer, options)?)),
+            #[cfg(feature = "tar-zstd")]
+            Fmt::TarZstd => Inner::Tar(tar::Builder::new(TarZstd::compressor(writer, options)?)),
+            #[cfg(feature = "tar-lz4")]
+            Fmt::TarLz4 => Inner::Tar(tar::Builder::new(TarLz4::compressor(writer, options)?)),
+            #[cfg(feature = "tar-lzma")]
+            Fmt::TarLzma => Inner::Tar(tar::Builder::new(TarLzma::compressor(writer, options)?)),
+            #[cfg(feature = "tar-lz")]
+            Fmt::TarLz => Inner::Tar(tar::Builder::new(TarLz::compressor(writer, options)?)),
+            #[cfg(feature = "tar-z")]
+            Fmt::TarZ => Inner::Tar(tar::Builder::new(TarZ::compressor(writer, options)?)),
+            #[cfg(feature = "zip")]
+            Fmt::Zip => {
+                let writer: Box<dyn Write + 'a> = Box::new(writer);
+                Inner::Zip {
+                    writer: Box::new(zip::ZipWriter::new_stream(writer)),
+                    options: options.clone(),
+                }
+            }
+            #[cfg(feature = "7z")]
+            Fmt::SevenZip => {
+                let output = SevenZipOutput::Staged {
+                    buffer: Cursor::new(Vec::new()),
+                    output: Box::new(writer),
+                };
+                Inner::SevenZip(crate::archive::seven_zip::encode::archive_writer(
+                    output, options,
+                )?)
+            }
+            #[cfg(feature = "ar")]
+            Fmt::Ar => {
+                let mut writer: Box<dyn Write + 'a> = Box::new(writer);
+                Ar::start(&mut writer, options)?;
+                Inner::Ar(writer)
+            }
+            #[cfg(feature = "cpio")]
+            Fmt::Cpio => {
+                let writer: Box<dyn Write + 'a> = Box::new(writer);
+                Inner::Cpio(CpioWriter::new(writer, options)?)
+            }
+            #[cfg(feature = "gz")]
+            Fmt::Gz => {
+                // The gzip header records the file name and modification time
+                let owned = options.clone();
+                Inner::single("gz", options, move |info| {
+                    Gz::compressor(writer, &owned, Some(info))
+                })?
+            }
+            #[cfg(feature = "xz")]
+            Fmt::Xz => {
+                let compressor = Xz::compressor(writer, options)?;
+                Inner::single("xz", options, move |_| Ok(compressor))?
+            }
+            #[cfg(feature = "bz")]
+            Fmt::Bz => {
+                let compressor = Bz::compressor(writer, options)?;
+                Inner::single("bz2", options, move |_| Ok(compressor))?
+            }
+            #[cfg(feature = "zstd")]
+            Fmt::Zstd => {
+                let compressor = Zstd::compressor(writer, options)?;
+                Inner::single("zst", options, move |_| Ok(compressor))?
+            }
+        };
+
+        Ok(ArchiveWriter {
+            inner,
+            paths: HashSet::new(),
+            reproducible: options.reproducible,
+        })
+    }
+
+    /// Create a writer for the given format, writing 7z archives in place
+    pub(crate) fn new_seekable<W: Write + std::io::Seek + 'a>(
+        fmt: Fmt,
+        writer: W,
+        options: &EncodeOptions,
+    ) -> Result<Self> {
+        #[cfg(feature = "7z")]
+        if fmt == Fmt::SevenZip {
+            let mut writer = writer;
+            let start = writer.stream_position()?;
+            let output = SevenZipOutput::Seekable {
+                writer: Box::new(writer),
+                start,
+            };
+            return Ok(ArchiveWriter {
+                inner: Inner::SevenZip(crate::archive::seven_zip::encode::archive_writer(
+                    output, options,
+                )?),
+                paths: HashSet::new(),
+                reproducible: options.reproducible,
+            });
+        }
+        Self::new(fmt, writer, options)
+    }
+
+    /// Add an entry to the archive
+    ///
+    /// # Arguments
+    /// * `info` - The entry metadata; `size` must match the content length
+    /// * `reader` - The entry content (ignored for directories)
+    ///
+    /// # Returns
+    /// * `Ok(())` - If the entry was written
+    /// * `Err(ArchiveError)` - If writing fails or the path was already added
+    pub fn add<R: Read>(&mut self, info: &EntryInfo, reader: R) -> Result<()> {
+        if !self.paths.insert(info.path.clone()) {
+            return Err(ArchiveError::DuplicateFiles {
+                paths: vec![info.path.clone()],
+            });
+        }
+        let normalized;
+        let info = match &self.reproducible {
+            Some(reproducible) => {
+                normalized = reproducible.normalize(info);
+                &normalized
+            }
+            None => info,
+        };
+
+        match &mut self.inner {
+            #[cfg(feature = "tar")]
+            Inner::Tar(builder) => append_tar_entry(builder, info, reader),
+            #[cfg(feature = "zip")]
+            Inner::Zip { writer, options } => {
+                crate::archive::zip::encode::append_entry(writer, options, info, reader)
+            }
+            #[cfg(feature = "7z")]
+            Inner::SevenZip(writer) => {
+                let entry = crate::archive::seven_zip::encode::archive_entry(info);
+                // Symlinks store their target as content
+                let reader: Option<Box<dyn Read + '_>> = match &info.link {
+                    Some(link) => Some(Box::new(link.as_bytes())),
+                    None => (!info.is_dir).then(|| Box::new(reader) as Box<dyn Read>),
+                };
+                writer.push_archive_entry(entry, reader).map_err(|e| {
+                    ArchiveError::EncodeFailed {
+                        format: "7z".to_string(),
+                        reason: format!("Failed to add '{}': {}", info.path, e),
+                    }
+                })?;
+                Ok(())
+            }
+            #[cfg(feature = "ar")]
+            Inner::Ar(writer) => crate::archive::ar::encode::append_ar_entry(writer, info, reader),
+            #[cfg(feature = "cpio")]
+            Inner::Cpio(writer) => writer.append(info, reader),
+            #[cfg(any(feature = "gz", feature = "xz", feature = "bz", feature = "zstd"))]
+            Inner::Single {
+                format,
+                start,
+                compressor,
+            } => {
+                let reason = if info.is_dir || info.link.is_some() {
+                    format!("'{}' is not a regular file", info.path)
+                } else if let Some(start) = start.take() {
+                    let mut writer = start(info)?;
+                    std::io::copy(&mut reader.take(info.size), &mut writer).map_err(|e| {
+                        ArchiveError::CompressionError(format!("Failed to compress: {}", e))
+                    })?;
+                    *compressor = Some(writer);
+                    return Ok(());
+                } else {
+                    "Expected exactly one file, got more".to_string()
+                };
+                Err(ArchiveError::EncodeFailed {
+                    format: format.to_string(),
+                    reason,
+                })
+            }
+            Inner::Unused(never, _) => match *never {},
+        }
+    }
+
+    /// Add an in-memory [`File`] to the archive
+    pub fn add_file(&mut self, file: &File) -> Result<()> {
+        self.add(&EntryInfo::from(file), &file.buffer[..])
+    }
+
+    /// Write the archive trailer and flush the underlying writer
+    pub fn finish(self) -> Result<()> {
+        match self.inner {
+            #[cfg(feature = "tar")]
+            Inner::Tar(builder) => finish_tar_archive(builder),
+            #[cfg(feature = "zip")]
+            Inner::Zip { writer, .. } => {
+                let mut output = writer.finish().map_err(|e| ArchiveError::EncodeFailed {
+                    format: "zip".to_string(),
+                    reason: format!("Failed to finalize zip archive: {}", e),
+                })?;
+                output.flush()?;
+                Ok(())
+            }
+            #[cfg(feature = "7z")]
+            Inner::SevenZip(writer) => {
+                let output = writer.finish().map_err(|e| ArchiveError::EncodeFailed {
+                    format: "7z".to_string(),
+                    reason: format!("Failed to finish: {}", e),
+                })?;
+                output.finish()
+            }
+            #[cfg(feature = "ar")]
+            Inner::Ar(mut writer) => {
+                writer.flush()?;
+                Ok(())
+            }
+            #[cfg(feature = "cpio")]
+            Inner::Cpio(writer) => {
+                writer.finish()?;
+                Ok(())
+            }
+            #[cfg(any(feature = "gz", feature = "xz", feature = "bz", feature = "zstd"))]
+            Inner::Single {
+                format, compressor, ..
+            } => match compressor {
+                Some(compressor) => compressor.finish().map_err(|e| {
+                    ArchiveError::CompressionError(format!("Finalization failed: {}", e))
+                }),
+                None => Err(ArchiveError::EncodeFailed {
+                    format: format.to_string(),
+                    reason: "Expected exactly one file, got 0".to_string(),
+                }),
+            },
+            Inner::Unused(never, _) => match never {},
+        }
+    }
+}
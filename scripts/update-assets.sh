@@ -12,4 +12,19 @@ tar -cjf test.tar.bz2 mujs-build-0.0.11
 tar --zstd -cf  test.tar.zst mujs-build-0.0.11
 zip -r test.zip mujs-build-0.0.11
 
-rm -rf mujs-build-0.0.11
\ No newline at end of file
+rm -rf mujs-build-0.0.11
+# GNU sparse files, in the old GNU format and the PAX formats 0.0 to 1.0
+mkdir -p sparse work/sparse
+printf 'middle%.0s' $(seq 100) | dd of=work/sparse/holes.bin bs=1 seek=65536 conv=notrunc
+printf 'end\n' | dd of=work/sparse/holes.bin bs=1 seek=1048572 conv=notrunc
+for i in $(seq 0 15); do
+  printf 'chunk %d\n' $i | dd of=work/sparse/many.bin bs=1 seek=$((i * 65536 + 100)) conv=notrunc
+done
+truncate -s 1M work/sparse/many.bin
+options="--sparse --owner=0 --group=0 --mtime=@1700000000 -C work"
+tar $options --format=gnu -czf sparse/gnu.tar.gz sparse
+for version in 0.0 0.1 1.0; do
+  tar $options --format=pax --sparse-version=$version --pax-option=delete=atime,delete=ctime \
+    -czf sparse/pax-$version.tar.gz sparse
+done
+rm -rf work